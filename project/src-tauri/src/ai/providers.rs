@@ -49,6 +49,8 @@ pub trait AIProvider: Send + Sync {
         text: &str,
         target_style: Option<&str>,
     ) -> Result<AIResponse<StyleAnalysis>>;
+
+    async fn generate_text(&self, prompt: &str) -> Result<AIResponse<String>>;
 }
 
 /// OpenAI provider implementation
@@ -219,6 +221,18 @@ impl AIProvider for OpenAIProvider {
 
         Ok(AIResponse::success(analysis, 5, start.elapsed().as_millis()))
     }
+
+    async fn generate_text(
+        &self,
+        _prompt: &str,
+    ) -> Result<AIResponse<String>> {
+        let start = Instant::now();
+        
+        // For now, return mock data
+        let text = "Suggested draft".to_string();
+
+        Ok(AIResponse::success(text, 10, start.elapsed().as_millis()))
+    }
 }
 
 /// Anthropic provider implementation  
@@ -314,6 +328,12 @@ impl AIProvider for AnthropicProvider {
         };
         Ok(AIResponse::success(analysis, 8, start.elapsed().as_millis()))
     }
+
+    async fn generate_text(&self, _prompt: &str) -> Result<AIResponse<String>> {
+        let start = Instant::now();
+        let text = "Anthropic draft".to_string();
+        Ok(AIResponse::success(text, 10, start.elapsed().as_millis()))
+    }
 }
 
 /// Local/offline provider implementation
@@ -400,4 +420,10 @@ impl AIProvider for LocalProvider {
         };
         Ok(AIResponse::success(analysis, 1, start.elapsed().as_millis()))
     }
+
+    async fn generate_text(&self, _prompt: &str) -> Result<AIResponse<String>> {
+        let start = Instant::now();
+        let text = "Local draft".to_string();
+        Ok(AIResponse::success(text, 1, start.elapsed().as_millis()))
+    }
 }
@@ -141,6 +141,11 @@ impl AIService {
             .await
     }
 
+    /// Write free text following a prompt, e.g. a constrained draft
+    pub async fn generate_text(&self, prompt: &str) -> Result<AIResponse<String>> {
+        self.provider.generate_text(prompt).await
+    }
+
     /// Check if the service is properly configured
     pub fn is_configured(&self) -> bool {
         match &self.settings.provider {
@@ -171,6 +176,7 @@ impl AIService {
             "generate_character_suggestions" => 8,
             "generate_plot_suggestions" => 10,
             "analyze_writing_style" => 5,
+            "generate_text" => 10,
             _ => 5,
         };
 
@@ -1,8 +1,19 @@
 //! Oulipo constraint-related Tauri commands.
 
-use crate::oulipo::{OulipoService, ConstraintResult, Violation};
+use crate::oulipo::{OulipoService, ConstraintResult, Composition, Fix, FixOutcome, Scope, SessionUpdate, TextDelta, ConstraintWorkflowConfig, WorkflowResult, CandidateGenerator, GenerationOutcome, GenerationRun, RuleDefinition, RuleFormat};
+use crate::oulipo::session::TrackedViolation;
+use crate::oulipo::generators::anagram::AnagramOptions;
+use crate::oulipo::generators::lipogram::LipogramRewriteOptions;
+use crate::oulipo::generators::palindrome::PalindromeOptions;
+use crate::oulipo::generators::snowball::SnowballOptions;
+use crate::oulipo::constraints::{BelleAbsenteUnit, InitialMatch, PalindromeMode, SnowballVariant};
+use crate::oulipo::constraints::letter_inventory::LetterQuota;
+use crate::oulipo::constraints::meter::Substitution;
+use crate::oulipo::rhyme::RhymeLevel;
+use crate::oulipo::thesaurus::{PartOfSpeech, ThesaurusEntry};
 use crate::commands::state::AppState;
 use tauri::State;
+use std::collections::HashMap;
 
 /// Check lipogram constraint (text without specific letters)
 #[tauri::command]
@@ -12,32 +23,65 @@ pub fn check_lipogram(
     forbidden_letter: String,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .check_lipogram(&text, &forbidden_letter)
         .map_err(|e| e.to_string())
 }
 
-/// Check palindrome constraint
+/// Check beau présent constraint (only the letters of a name)
+#[tauri::command]
+pub fn beau_present_check(
+    state: State<'_, AppState>,
+    text: String,
+    name: String,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_beau_present(&text, &name)
+        .map_err(|e| e.to_string())
+}
+
+/// Check belle absente constraint (each line, or stanza, omits the next letter of a name)
+#[tauri::command]
+pub fn belle_absente_check(
+    state: State<'_, AppState>,
+    text: String,
+    name: String,
+    unit: Option<BelleAbsenteUnit>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_belle_absente(&text, &name, unit.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Check palindrome constraint on letters (the default), words or lines
 #[tauri::command]
 pub fn check_palindrome(
     state: State<'_, AppState>,
     text: String,
+    mode: Option<PalindromeMode>,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
-        .check_palindrome(&text)
+        .oulipo_service()?
+        .check_palindrome_with_mode(&text, mode.unwrap_or_default())
         .map_err(|e| e.to_string())
 }
 
-/// Check snowball constraint (each word one letter longer)
+/// Check snowball constraint (each word one letter longer by default)
+///
+/// `variant` selects the melting, diamond or line-level form, and `start` fixes
+/// the length of the first word (or the word count of the first line).
 #[tauri::command]
 pub fn check_snowball(
     state: State<'_, AppState>,
     text: String,
+    variant: Option<SnowballVariant>,
+    start: Option<usize>,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
-        .check_snowball(&text)
+        .oulipo_service()?
+        .check_snowball_variant(&text, variant.unwrap_or_default(), start)
         .map_err(|e| e.to_string())
 }
 
@@ -49,7 +93,7 @@ pub fn check_univocalic(
     vowel: String,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .check_univocalic(&text, &vowel)
         .map_err(|e| e.to_string())
 }
@@ -69,8 +113,9 @@ pub fn lipogram_check(
 pub fn palindrome_check(
     state: State<'_, AppState>,
     text: String,
+    mode: Option<PalindromeMode>,
 ) -> Result<ConstraintResult, String> {
-    check_palindrome(state, text)
+    check_palindrome(state, text, mode)
 }
 
 /// Check snowball constraint (alias for consistency)
@@ -78,8 +123,10 @@ pub fn palindrome_check(
 pub fn snowball_check(
     state: State<'_, AppState>,
     text: String,
+    variant: Option<SnowballVariant>,
+    start: Option<usize>,
 ) -> Result<ConstraintResult, String> {
-    check_snowball(state, text)
+    check_snowball(state, text, variant, start)
 }
 
 /// Check univocalic constraint (alias for consistency)
@@ -99,7 +146,7 @@ pub fn prisoners_constraint_check(
     text: String,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .check_prisoners_constraint(&text)
         .map_err(|e| e.to_string())
 }
@@ -112,11 +159,224 @@ pub fn sestina_check(
     end_words: Vec<String>,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .check_sestina(&text, &end_words)
         .map_err(|e| e.to_string())
 }
 
+/// Check n-ina (quenine) constraint
+#[tauri::command]
+pub fn nina_check(
+    state: State<'_, AppState>,
+    text: String,
+    end_words: Vec<String>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_nina(&text, &end_words)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that every letter of the alphabet appears
+#[tauri::command]
+pub fn pangram_check(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_pangram(&text)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that no letter appears more than once
+#[tauri::command]
+pub fn heterogram_check(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_heterogram(&text)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that every letter used appears exactly `n` times
+#[tauri::command]
+pub fn isogram_check(
+    state: State<'_, AppState>,
+    text: String,
+    n: usize,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_isogram(&text, n)
+        .map_err(|e| e.to_string())
+}
+
+/// Check per-letter quotas, e.g. `{ "e": { "max": 3 }, "z": { "min": 1 } }`
+#[tauri::command]
+pub fn letter_quota_check(
+    state: State<'_, AppState>,
+    text: String,
+    quotas: HashMap<String, LetterQuota>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_letter_quotas(&text, quotas)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that every word begins with the same letter, or sound with `matching: "sound"`
+#[tauri::command]
+pub fn tautogram_check(
+    state: State<'_, AppState>,
+    text: String,
+    letter: Option<String>,
+    matching: Option<InitialMatch>,
+    allow_function_words: Option<bool>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_tautogram(&text, letter.as_deref(), matching.unwrap_or_default(), allow_function_words.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Check that every stressed word begins with the same sound, or letter with `matching: "letter"`
+#[tauri::command]
+pub fn alliteration_check(
+    state: State<'_, AppState>,
+    text: String,
+    letter: Option<String>,
+    matching: Option<InitialMatch>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_alliteration(&text, letter.as_deref(), matching.unwrap_or(InitialMatch::Sound))
+        .map_err(|e| e.to_string())
+}
+
+/// Check villanelle form, requiring perfect rhymes by default
+#[tauri::command]
+pub fn villanelle_check(
+    state: State<'_, AppState>,
+    text: String,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_villanelle(&text, min_level.unwrap_or(RhymeLevel::Perfect))
+        .map_err(|e| e.to_string())
+}
+
+/// Check pantoum form
+#[tauri::command]
+pub fn pantoum_check(
+    state: State<'_, AppState>,
+    text: String,
+    circular_ending: Option<bool>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_pantoum(&text, circular_ending.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Check terza rima form, requiring perfect rhymes by default
+#[tauri::command]
+pub fn terza_rima_check(
+    state: State<'_, AppState>,
+    text: String,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_terza_rima(&text, min_level.unwrap_or(RhymeLevel::Perfect))
+        .map_err(|e| e.to_string())
+}
+
+/// Check rondeau form, requiring perfect rhymes by default
+#[tauri::command]
+pub fn rondeau_check(
+    state: State<'_, AppState>,
+    text: String,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_rondeau(&text, min_level.unwrap_or(RhymeLevel::Perfect))
+        .map_err(|e| e.to_string())
+}
+
+/// Check ghazal form; the radif is detected from the opening couplet when omitted
+#[tauri::command]
+pub fn ghazal_check(
+    state: State<'_, AppState>,
+    text: String,
+    radif: Option<String>,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_ghazal(&text, radif.as_deref(), min_level.unwrap_or(RhymeLevel::Slant))
+        .map_err(|e| e.to_string())
+}
+
+/// Check haiku constraint (5-7-5 syllables per line)
+#[tauri::command]
+pub fn haiku_check(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_haiku(&text)
+        .map_err(|e| e.to_string())
+}
+
+/// Check metrical scansion, iambic pentameter by default
+#[tauri::command]
+pub fn meter_check(
+    state: State<'_, AppState>,
+    text: String,
+    meter: Option<String>,
+    substitutions: Option<Vec<Substitution>>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_meter(
+            &text,
+            meter.as_deref().unwrap_or("iambic pentameter"),
+            &substitutions.unwrap_or_default(),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Check a rhyme scheme such as "ABAB CDCD EFEF GG", requiring perfect rhymes by default
+#[tauri::command]
+pub fn rhyme_scheme_check(
+    state: State<'_, AppState>,
+    text: String,
+    scheme: String,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_rhyme_scheme(&text, &scheme, min_level.unwrap_or(RhymeLevel::Perfect))
+        .map_err(|e| e.to_string())
+}
+
+/// Count syllables in a word, line or passage
+#[tauri::command]
+pub fn count_syllables(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<usize, String> {
+    Ok(state.services()
+        .oulipo_service()?
+        .count_syllables(&text))
+}
+
 /// Apply N+7 transformation
 #[tauri::command]
 pub fn n_plus_7_transform(
@@ -125,33 +385,71 @@ pub fn n_plus_7_transform(
     offset: i32,
 ) -> Result<ConstraintResult, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .n_plus_7_transform(&text, offset)
         .map_err(|e| e.to_string())
 }
 
-/// Generate haiku
+/// Generate haiku; the same seed gives the same haiku
 #[tauri::command]
 pub fn generate_haiku(
     state: State<'_, AppState>,
     theme: Option<String>,
+    seed: Option<u64>,
+) -> Result<String, String> {
+    state.services()
+        .oulipo_service()?
+        .generate_haiku(theme.as_deref(), seed)
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a snowball between two word lengths, optionally using theme words
+#[tauri::command]
+pub fn generate_snowball(
+    state: State<'_, AppState>,
+    options: Option<SnowballOptions>,
+    seed: Option<u64>,
+) -> Result<String, String> {
+    let mut options = options.unwrap_or_default();
+    options.seed = seed.or(options.seed);
+    
+    state.services()
+        .oulipo_service()?
+        .generate_snowball(&options)
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a fill-in n-ina skeleton
+#[tauri::command]
+pub fn generate_nina_skeleton(
+    state: State<'_, AppState>,
+    end_words: Vec<String>,
 ) -> Result<String, String> {
     state.services()
-        .oulipo_service()
-        .generate_haiku(theme.as_deref())
+        .oulipo_service()?
+        .generate_nina_skeleton(&end_words)
         .map_err(|e| e.to_string())
 }
 
 /// Generate anagrams
+///
+/// `options` can set a minimum word length, required or excluded words and the
+/// maximum number of words per anagram.
 #[tauri::command]
 pub fn generate_anagrams(
     state: State<'_, AppState>,
     word: String,
     max_results: Option<usize>,
+    options: Option<AnagramOptions>,
+    seed: Option<u64>,
 ) -> Result<Vec<String>, String> {
+    let mut options = options.unwrap_or_default();
+    options.max_results = max_results.unwrap_or(options.max_results);
+    options.seed = seed.or(options.seed);
+    
     state.services()
-        .oulipo_service()
-        .generate_anagrams(&word, max_results.unwrap_or(10))
+        .oulipo_service()?
+        .generate_anagrams_with_options(&word, &options)
         .map_err(|e| e.to_string())
 }
 
@@ -163,110 +461,90 @@ pub fn check_anagram(
     word2: String,
 ) -> Result<bool, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .check_anagram(&word1, &word2)
         .map_err(|e| e.to_string())
 }
 
-/// Generate combinatorial poem
+/// Generate combinatorial poem; the same seed gives the same random arrangement
 #[tauri::command]
 pub fn generate_combinatorial_poem(
     state: State<'_, AppState>,
     word_sets: Vec<Vec<String>>,
     pattern: Option<String>,
+    seed: Option<u64>,
 ) -> Result<String, String> {
     state.services()
-        .oulipo_service()
-        .generate_combinatorial_poem(&word_sets, pattern.as_deref())
+        .oulipo_service()?
+        .generate_combinatorial_poem(&word_sets, pattern.as_deref(), seed)
+        .map_err(|e| e.to_string())
+}
+
+/// Check that source poems are interchangeable line by line, requiring perfect rhymes by default
+#[tauri::command]
+pub fn combinatorial_book_check(
+    state: State<'_, AppState>,
+    sources: Vec<Vec<String>>,
+    min_level: Option<RhymeLevel>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_combinatorial_sources(&sources, min_level.unwrap_or(RhymeLevel::Perfect))
+        .map_err(|e| e.to_string())
+}
+
+/// Read the poem at an index of a combinatorial book, or a random one drawn with the seed
+#[tauri::command]
+pub fn combinatorial_book_poem(
+    state: State<'_, AppState>,
+    sources: Vec<Vec<String>>,
+    index: Option<u64>,
+    seed: Option<u64>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .combinatorial_poem(&sources, index, seed)
+        .map_err(|e| e.to_string())
+}
+
+/// List consecutive poems of a combinatorial book with their indices
+#[tauri::command]
+pub fn combinatorial_book_poems(
+    state: State<'_, AppState>,
+    sources: Vec<Vec<String>>,
+    start: Option<u64>,
+    count: Option<usize>,
+) -> Result<Vec<(u64, String)>, String> {
+    state.services()
+        .oulipo_service()?
+        .enumerate_combinatorial_poems(&sources, start.unwrap_or(0), count.unwrap_or(10))
         .map_err(|e| e.to_string())
 }
 
 /// Validate text length constraint
+///
+/// Lengths count characters; text that is too long comes with trim fixes.
 #[tauri::command]
 pub fn validate_text_length(
     text: String,
     min_length: Option<usize>,
     max_length: Option<usize>,
 ) -> Result<ConstraintResult, String> {
-    let length = text.chars().count();
-    
-    let mut violations = Vec::new();
-    let mut is_valid = true;
-    
-    if let Some(min) = min_length {
-        if length < min {
-            violations.push(format!("Text too short: {} characters (minimum: {})", length, min));
-            is_valid = false;
-        }
-    }
-    
-    if let Some(max) = max_length {
-        if length > max {
-            violations.push(format!("Text too long: {} characters (maximum: {})", length, max));
-            is_valid = false;
-        }
-    }
-    
-    Ok(ConstraintResult {
-        success: is_valid,
-        result: None,
-        violations: violations.into_iter().map(|v| crate::oulipo::types::Violation {
-            position: 0,
-            length: 0,
-            issue: v,
-            suggestion: None,
-        }).collect(),
-        suggestions: if is_valid { 
-            Vec::new() 
-        } else { 
-            vec!["Adjust text length to meet requirements".to_string()] 
-        },
-        metadata: serde_json::Value::Null,
-    })
+    crate::oulipo::validators::validate_text_length(&text, min_length.unwrap_or(0), max_length)
+        .map_err(|e| e.to_string())
 }
 
 /// Validate word count constraint
+///
+/// Violations span the whole text, so the editor can highlight it.
 #[tauri::command]
 pub fn validate_word_count(
     text: String,
     min_words: Option<usize>,
     max_words: Option<usize>,
 ) -> Result<ConstraintResult, String> {
-    let word_count = text.split_whitespace().count();
-    
-    let mut violations = Vec::new();
-    let mut is_valid = true;
-    
-    if let Some(min) = min_words {
-        if word_count < min {
-            violations.push(format!("Too few words: {} (minimum: {})", word_count, min));
-            is_valid = false;
-        }
-    }
-    
-    if let Some(max) = max_words {
-        if word_count > max {
-            violations.push(format!("Too many words: {} (maximum: {})", word_count, max));
-            is_valid = false;
-        }
-    }
-    
-    Ok(ConstraintResult {
-        success: is_valid,
-        result: Some(format!("Word count: {}", word_count)),
-        violations: violations.into_iter().map(|msg| Violation {
-            position: 0,
-            length: 0,
-            issue: msg,
-            suggestion: Some("Adjust word count to meet requirements".to_string()),
-        }).collect(),
-        suggestions: if is_valid { 
-            Vec::new() 
-        } else { 
-            vec!["Adjust word count to meet requirements".to_string()] 
-        },
-        metadata: serde_json::Value::Null,
-    })
+    crate::oulipo::validators::validate_word_count(&text, min_words.unwrap_or(0), max_words)
+        .map_err(|e| e.to_string())
 }
 
 /// Generate lipogram suggestions
@@ -277,61 +555,468 @@ pub fn lipogram_suggestions(
     forbidden_letter: String,
 ) -> Result<Vec<String>, String> {
     state.services()
-        .oulipo_service()
+        .oulipo_service()?
         .generate_lipogram_suggestions(&text, &forbidden_letter)
         .map_err(|e| e.to_string())
 }
 
-/// Generate palindrome suggestions
+/// Propose ranked synonym swaps avoiding every letter in `forbidden_letters` (e.g. "ea")
+///
+/// The edits are returned in `metadata.edits` as byte ranges and replacements;
+/// with `options.full_rewrite` the rewritten passage is the result.
+#[tauri::command]
+pub fn lipogram_rewrite(
+    state: State<'_, AppState>,
+    text: String,
+    forbidden_letters: String,
+    options: Option<LipogramRewriteOptions>,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .rewrite_lipogram(&text, &forbidden_letters, &options.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Build palindromes outward from the given seed word
+///
+/// `options` can set the number of words added around the seed, a minimum
+/// word length and excluded words.
 #[tauri::command]
 pub fn palindrome_suggestions(
     state: State<'_, AppState>,
     text: String,
+    max_results: Option<usize>,
+    options: Option<PalindromeOptions>,
+    seed: Option<u64>,
 ) -> Result<Vec<String>, String> {
+    let mut options = options.unwrap_or_default();
+    options.max_results = max_results.unwrap_or(options.max_results);
+    options.seed = seed.or(options.seed);
+    
     state.services()
-        .oulipo_service()
-        .generate_palindrome_suggestions(&text)
+        .oulipo_service()?
+        .generate_palindromes(&text, &options)
         .map_err(|e| e.to_string())
 }
 
 /// Advanced constraint workflow creation
+///
+/// Each entry needs a `type`. The shorthand types `univocalic`, `length` and
+/// `words` are kept for compatibility; any other type is resolved through the
+/// constraint registry, with its configuration taken from `config` (or from the
+/// entry itself when `config` is absent). An optional `weight` sets the
+/// constraint's share of the workflow score.
 #[tauri::command]
 pub fn create_constraint_workflow(
     state: State<'_, AppState>,
     constraints: Vec<serde_json::Value>,
 ) -> Result<serde_json::Value, String> {
-    let oulipo = state.services().oulipo_service();
+    let oulipo = state.services().oulipo_service()?;
+    let config = parse_workflow(&oulipo, constraints)?;
+    
+    Ok(serde_json::json!({
+        "success": true,
+        "message": "Workflow created successfully",
+        "constraints": config.constraints
+            .iter()
+            .enumerate()
+            .map(|(index, (name, constraint_config))| serde_json::json!({
+                "name": name,
+                "config": constraint_config,
+                "weight": config.weight(index)
+            }))
+            .collect::<Vec<_>>()
+    }))
+}
+
+/// Check text against a constraint workflow
+///
+/// Takes the same entries as `create_constraint_workflow`. Besides the
+/// pass/fail verdict, the result carries each constraint's adherence score
+/// and their weighted mean.
+#[tauri::command]
+pub fn check_constraint_workflow(
+    state: State<'_, AppState>,
+    text: String,
+    constraints: Vec<serde_json::Value>,
+) -> Result<WorkflowResult, String> {
+    let oulipo = state.services().oulipo_service()?;
+    let config = parse_workflow(&oulipo, constraints)?;
+    
+    oulipo.check_with_workflow(&text, &config).map_err(|e| e.to_string())
+}
+
+/// Generate text on a theme, checking each draft against the given constraints
+///
+/// `constraints` takes the entries of `create_constraint_workflow`. Drafts come
+/// from `source`: an Oulipo generator (`haiku` or `snowball`, chosen from the
+/// constraints by default) or `ai` for the configured AI provider, which is
+/// shown the violations of the best draft so far. A draft whose violations
+/// carry fixes is tried again with them applied before a new one is drafted.
+#[tauri::command]
+pub async fn generate_constrained_text(
+    state: State<'_, AppState>,
+    theme: String,
+    constraints: Vec<serde_json::Value>,
+    source: Option<String>,
+    max_attempts: Option<usize>,
+    seed: Option<u64>,
+) -> Result<GenerationOutcome, String> {
+    let generator = match source.as_deref() {
+        None | Some("ai") => None,
+        Some(name) => Some(CandidateGenerator::parse(name).ok_or_else(|| format!("Unknown generation source: {}", name))?),
+    };
+    let mut run = {
+        let oulipo = state.services().oulipo_service()?;
+        let workflow = parse_workflow(&oulipo, constraints)?;
+        GenerationRun::new(&theme, workflow, max_attempts.unwrap_or(10), seed, &oulipo).map_err(|e| e.to_string())?
+    };
+    
+    if source.as_deref() != Some("ai") {
+        let generator = generator.unwrap_or_else(|| CandidateGenerator::for_constraints(&run.workflow().constraints));
+        return state.services()
+            .oulipo_service()?
+            .run_generation(run, generator)
+            .map_err(|e| e.to_string());
+    }
+    
+    // The AI provider is asynchronous, so the service is only locked to check each draft
+    let ai_service = state.services().ai_service()?.clone();
+    while let Some(request) = run.next_request() {
+        let (source, candidate) = match request.repair.clone() {
+            Some(repaired) => ("fixes", Ok(repaired)),
+            None => {
+                let response = ai_service.generate_text(&request.prompt()).await;
+                ("ai", response.and_then(|response| {
+                    response.data.ok_or_else(|| {
+                        anyhow::anyhow!(response.error.unwrap_or_else(|| "The AI provider returned no text".to_string()))
+                    })
+                }))
+            }
+        };
+        let oulipo = state.services().oulipo_service()?;
+        run.record(source, candidate, &oulipo).map_err(|e| e.to_string())?;
+    }
+    
+    Ok(run.finish())
+}
+
+/// Build a workflow from command entries, checking every configuration with its factory
+fn parse_workflow(oulipo: &OulipoService, constraints: Vec<serde_json::Value>) -> Result<ConstraintWorkflowConfig, String> {
+    let registry = oulipo.constraint_registry();
     let mut workflow = oulipo.create_workflow();
     
     // Parse constraints and add them to workflow
     for constraint in constraints {
-        if let Some(constraint_type) = constraint.get("type").and_then(|v| v.as_str()) {
-            match constraint_type {
-                "univocalic" => {
-                    if let Some(vowel) = constraint.get("vowel").and_then(|v| v.as_str()).and_then(|s| s.chars().next()) {
-                        workflow = workflow.with_univocalic(vowel);
-                    }
-                }
-                "length" => {
-                    let min = constraint.get("min").and_then(|v| v.as_u64()).map(|v| v as usize);
-                    let max = constraint.get("max").and_then(|v| v.as_u64()).map(|v| v as usize);
-                    workflow = workflow.with_length_limits(min, max);
+        let constraint_type = constraint.get("type")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "Constraint entry is missing 'type'".to_string())?;
+        
+        match constraint_type {
+            "univocalic" if constraint.get("vowel").is_some() => {
+                if let Some(vowel) = constraint.get("vowel").and_then(|v| v.as_str()).and_then(|s| s.chars().next()) {
+                    workflow = workflow.with_univocalic(vowel);
                 }
-                "words" => {
-                    let min = constraint.get("min").and_then(|v| v.as_u64()).map(|v| v as usize);
-                    let max = constraint.get("max").and_then(|v| v.as_u64()).map(|v| v as usize);
-                    workflow = workflow.with_word_limits(min, max);
-                }
-                _ => {}
             }
+            "length" => {
+                let min = constraint.get("min").and_then(|v| v.as_u64()).map(|v| v as usize);
+                let max = constraint.get("max").and_then(|v| v.as_u64()).map(|v| v as usize);
+                workflow = workflow.with_length_limits(min, max);
+            }
+            "words" => {
+                let min = constraint.get("min").and_then(|v| v.as_u64()).map(|v| v as usize);
+                let max = constraint.get("max").and_then(|v| v.as_u64()).map(|v| v as usize);
+                workflow = workflow.with_word_limits(min, max);
+            }
+            name if registry.contains(name) => {
+                let config = constraint.get("config").cloned().unwrap_or_else(|| constraint.clone());
+                workflow = workflow.with_constraint(name, config);
+            }
+            other => return Err(format!("Unknown constraint: {}", other)),
+        }
+        
+        if let Some(weight) = constraint.get("weight") {
+            let weight = weight.as_f64()
+                .filter(|w| *w >= 0.0)
+                .ok_or_else(|| format!("The weight of '{}' must be a non-negative number", constraint_type))?;
+            workflow = workflow.with_weight(weight);
         }
     }
     
-    match workflow.build() {
-        Ok(_config) => Ok(serde_json::json!({
-            "success": true,
-            "message": "Workflow created successfully"
-        })),
-        Err(e) => Err(format!("Failed to create workflow: {}", e))
+    let config = workflow.build()
+        .map_err(|e| format!("Failed to create workflow: {}", e))?;
+    
+    // Make sure every configuration is accepted by its factory
+    for (name, constraint_config) in &config.constraints {
+        registry.create_constraint(name, constraint_config)
+            .map_err(|e| format!("Failed to create workflow: {}", e))?;
     }
+    
+    Ok(config)
+}
+
+/// Check text against a composition of registry constraints
+///
+/// The composition is an expression tree combining constraints with `and`,
+/// `or`, `not`, `at_least_k` and `sequence`, each optionally scoped to lines,
+/// stanzas, sentences, paragraphs or a named range.
+#[tauri::command]
+pub fn composition_check(
+    state: State<'_, AppState>,
+    text: String,
+    composition: Composition,
+) -> Result<ConstraintResult, String> {
+    state.services()
+        .oulipo_service()?
+        .check_composition(&text, &composition)
+        .map_err(|e| e.to_string())
+}
+
+/// Apply the chosen quick fixes and re-check the edited text
+///
+/// Fixes overlapping an earlier chosen fix are skipped and returned as such;
+/// `constraint` and `config` name the registry constraint to re-check with.
+#[tauri::command]
+pub fn apply_oulipo_fixes(
+    state: State<'_, AppState>,
+    text: String,
+    fixes: Vec<Fix>,
+    constraint: String,
+    config: serde_json::Value,
+) -> Result<FixOutcome, String> {
+    state.services()
+        .oulipo_service()?
+        .apply_fixes(&text, &fixes, &constraint, &config)
+        .map_err(|e| e.to_string())
+}
+
+/// Open an incremental checking session on a document
+///
+/// Returns the session id with every violation as `added`. Without a scope
+/// the constraint's own unit is used: lines for letter-level constraints,
+/// the whole text for forms.
+#[tauri::command]
+pub fn open_check_session(
+    state: State<'_, AppState>,
+    text: String,
+    constraint: String,
+    config: serde_json::Value,
+    scope: Option<Scope>,
+) -> Result<SessionUpdate, String> {
+    state.services()
+        .oulipo_service()?
+        .open_check_session(&text, &constraint, config, scope)
+        .map_err(|e| e.to_string())
+}
+
+/// Feed a checking session the editor's text deltas
+///
+/// Deltas apply in order, each to the text left by the one before; send
+/// `"unit": "utf16"` with JavaScript string offsets. Only violations that
+/// appeared or went away are returned.
+#[tauri::command]
+pub fn update_check_session(
+    state: State<'_, AppState>,
+    session: u64,
+    deltas: Vec<TextDelta>,
+) -> Result<SessionUpdate, String> {
+    state.services()
+        .oulipo_service()?
+        .update_check_session(session, &deltas)
+        .map_err(|e| e.to_string())
+}
+
+/// Every current violation of a checking session, for resynchronising the editor
+#[tauri::command]
+pub fn check_session_violations(
+    state: State<'_, AppState>,
+    session: u64,
+) -> Result<Vec<TrackedViolation>, String> {
+    let oulipo = state.services().oulipo_service()?;
+    let session = oulipo
+        .check_session(session)
+        .ok_or_else(|| format!("No checking session {}", session))?;
+    Ok(session.violations().to_vec())
+}
+
+/// Close a checking session when its document is closed
+#[tauri::command]
+pub fn close_check_session(
+    state: State<'_, AppState>,
+    session: u64,
+) -> Result<bool, String> {
+    Ok(state.services().oulipo_service()?.close_check_session(session))
+}
+
+/// Define user rules as constraints, replacing rules of the same names
+///
+/// Rules are saved with the app's configuration and registered again on the
+/// next start. Returns the names the rules can be checked under.
+#[tauri::command]
+pub fn define_oulipo_rules(
+    state: State<'_, AppState>,
+    rules: Vec<RuleDefinition>,
+) -> Result<Vec<String>, String> {
+    state.services()
+        .oulipo_service()?
+        .define_rules(rules)
+        .map_err(|e| e.to_string())
+}
+
+/// Define user rules from a JSON or TOML document with a top-level `rules` list
+#[tauri::command]
+pub fn import_oulipo_rules(
+    state: State<'_, AppState>,
+    source: String,
+    format: Option<String>,
+) -> Result<Vec<String>, String> {
+    let format = match format {
+        Some(name) => RuleFormat::parse(&name).ok_or_else(|| format!("Unknown rule format: {}", name))?,
+        None => RuleFormat::default(),
+    };
+    state.services()
+        .oulipo_service()?
+        .import_rules(&source, format)
+        .map_err(|e| e.to_string())
+}
+
+/// Every user rule, in the order it was defined
+#[tauri::command]
+pub fn list_oulipo_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<RuleDefinition>, String> {
+    Ok(state.services().oulipo_service()?.user_rules().to_vec())
+}
+
+/// Remove a user rule; returns whether there was one by that name
+#[tauri::command]
+pub fn remove_oulipo_rule(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<bool, String> {
+    state.services()
+        .oulipo_service()?
+        .remove_rule(&name)
+        .map_err(|e| e.to_string())
+}
+
+/// List every constraint registered with the Oulipo service
+#[tauri::command]
+pub fn list_oulipo_constraints(
+    state: State<'_, AppState>,
+) -> Result<Vec<serde_json::Value>, String> {
+    Ok(state.services()
+        .oulipo_service()?
+        .list_available_constraints()
+        .into_iter()
+        .map(|info| serde_json::json!({
+            "name": info.name,
+            "description": info.description,
+            "schema": info.schema
+        }))
+        .collect())
+}
+
+/// Load a word list from disk as a named dictionary
+///
+/// Plain one-word-per-line files are read from `path`; when `aff_path` is given,
+/// `path` is treated as a Hunspell `.dic` file and affixes are expanded.
+#[tauri::command]
+pub fn load_oulipo_dictionary(
+    state: State<'_, AppState>,
+    name: String,
+    path: String,
+    aff_path: Option<String>,
+    select: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let mut oulipo = state.services().oulipo_service()?;
+    
+    let loaded = match aff_path {
+        Some(aff_path) => oulipo.load_hunspell_dictionary(&name, &path, &aff_path),
+        None => oulipo.load_word_list(&name, &path),
+    };
+    loaded.map_err(|e| e.to_string())?;
+    
+    if select.unwrap_or(false) {
+        oulipo.select_dictionary(&name).map_err(|e| e.to_string())?;
+    }
+    
+    let word_count = oulipo.dictionaries().get(&name).map(|d| d.word_count()).unwrap_or(0);
+    Ok(serde_json::json!({
+        "name": name,
+        "word_count": word_count,
+        "active": oulipo.dictionaries().active_name() == name
+    }))
+}
+
+/// Look up synonyms, antonyms and hypernyms of a word, optionally for one part of speech
+#[tauri::command]
+pub fn thesaurus_lookup(
+    state: State<'_, AppState>,
+    word: String,
+    part_of_speech: Option<PartOfSpeech>,
+) -> Result<ThesaurusEntry, String> {
+    Ok(state.services()
+        .oulipo_service()?
+        .lookup_word(&word, part_of_speech))
+}
+
+/// Load thesaurus data on top of the built-in thesaurus
+///
+/// `format` is `"moby"` (the default) for synonym lists, or `"wordnet"` for
+/// WordNet data files (`data.noun`, `data.verb`, ...). Each load replaces
+/// any previously loaded files.
+#[tauri::command]
+pub fn load_oulipo_thesaurus(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    format: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let mut oulipo = state.services().oulipo_service()?;
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    
+    let loaded = match format.as_deref().unwrap_or("moby") {
+        "moby" => oulipo.load_moby_thesaurus(&paths),
+        "wordnet" => oulipo.load_wordnet(&paths),
+        other => return Err(format!("Unknown thesaurus format: {}", other)),
+    };
+    loaded.map_err(|e| e.to_string())?;
+    
+    Ok(serde_json::json!({
+        "headwords": oulipo.thesaurus().len()
+    }))
+}
+
+/// Select the dictionary used by Oulipo generators and transformers
+#[tauri::command]
+pub fn select_oulipo_dictionary(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), String> {
+    state.services()
+        .oulipo_service()?
+        .select_dictionary(&name)
+        .map_err(|e| e.to_string())
+}
+
+/// List loaded dictionaries and the active one
+#[tauri::command]
+pub fn list_oulipo_dictionaries(
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let oulipo = state.services().oulipo_service()?;
+    let dictionaries = oulipo.dictionaries();
+    
+    Ok(serde_json::json!({
+        "active": dictionaries.active_name(),
+        "dictionaries": dictionaries.names()
+            .into_iter()
+            .filter_map(|name| dictionaries.get(name).map(|d| serde_json::json!({
+                "name": name,
+                "language": d.language(),
+                "word_count": d.word_count(),
+                "noun_count": d.nouns().len()
+            })))
+            .collect::<Vec<_>>()
+    }))
 }
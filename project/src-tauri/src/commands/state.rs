@@ -17,7 +17,7 @@ pub struct AppServices {
     pub ai: Mutex<AIService>,
     pub export: ExportService,
     pub analytics: AnalyticsService,
-    pub oulipo: OulipoService,
+    pub oulipo: Mutex<OulipoService>,
 }
 
 impl AppState {
@@ -41,7 +41,7 @@ impl AppServices {
             ai: Mutex::new(AIService::new()),
             export: ExportService::new(),
             analytics: AnalyticsService::new(),
-            oulipo: OulipoService::new(),
+            oulipo: Mutex::new(OulipoService::new()),
         }
     }
     
@@ -60,9 +60,9 @@ impl AppServices {
         &self.analytics
     }
     
-    /// Get oulipo service
-    pub fn oulipo_service(&self) -> &OulipoService {
-        &self.oulipo
+    /// Get oulipo service (thread-safe)
+    pub fn oulipo_service(&self) -> Result<std::sync::MutexGuard<OulipoService>, String> {
+        self.oulipo.lock().map_err(|e| format!("Failed to lock Oulipo service: {}", e))
     }
 }
 
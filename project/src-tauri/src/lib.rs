@@ -5,6 +5,7 @@ mod analytics;
 mod oulipo;
 
 use commands::AppState;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -37,21 +38,64 @@ pub fn run() {
       commands::optimize_text_performance,
       // Oulipo constraint commands
       commands::lipogram_check,
+      commands::beau_present_check,
+      commands::belle_absente_check,
       commands::n_plus_7_transform,
       commands::palindrome_check,
       commands::snowball_check,
       commands::generate_haiku,
+      commands::generate_snowball,
+      commands::generate_nina_skeleton,
       commands::prisoners_constraint_check,
       commands::univocalic_check,
       commands::sestina_check,
+      commands::nina_check,
+      commands::pangram_check,
+      commands::heterogram_check,
+      commands::isogram_check,
+      commands::letter_quota_check,
+      commands::tautogram_check,
+      commands::alliteration_check,
+      commands::villanelle_check,
+      commands::pantoum_check,
+      commands::terza_rima_check,
+      commands::rondeau_check,
+      commands::ghazal_check,
+      commands::haiku_check,
+      commands::meter_check,
+      commands::rhyme_scheme_check,
+      commands::count_syllables,
       commands::generate_anagrams,
       commands::check_anagram,
       commands::generate_combinatorial_poem,
+      commands::combinatorial_book_check,
+      commands::combinatorial_book_poem,
+      commands::combinatorial_book_poems,
       commands::validate_text_length,
       commands::validate_word_count,
       commands::check_character_frequency,
       commands::lipogram_suggestions,
+      commands::lipogram_rewrite,
       commands::palindrome_suggestions,
+      commands::create_constraint_workflow,
+      commands::check_constraint_workflow,
+      commands::generate_constrained_text,
+      commands::composition_check,
+      commands::apply_oulipo_fixes,
+      commands::open_check_session,
+      commands::update_check_session,
+      commands::check_session_violations,
+      commands::close_check_session,
+      commands::define_oulipo_rules,
+      commands::import_oulipo_rules,
+      commands::list_oulipo_rules,
+      commands::remove_oulipo_rule,
+      commands::list_oulipo_constraints,
+      commands::load_oulipo_dictionary,
+      commands::select_oulipo_dictionary,
+      commands::thesaurus_lookup,
+      commands::load_oulipo_thesaurus,
+      commands::list_oulipo_dictionaries,
       // commands::get_user_credits,
       // commands::deduct_credits,
     ])
@@ -63,6 +107,15 @@ pub fn run() {
             .build(),
         )?;
       }
+      // User-defined Oulipo rules persist in the app's config directory
+      let rules_file = app.path().app_config_dir()?.join("oulipo_rules.toml");
+      let state = app.state::<AppState>();
+      let attached = state.services()
+        .oulipo_service()
+        .and_then(|mut oulipo| oulipo.attach_rule_file(&rules_file).map_err(|e| e.to_string()));
+      if let Err(e) = attached {
+        log::warn!("Could not load Oulipo rules from {}: {}", rules_file.display(), e);
+      }
       Ok(())
     })
     .run(tauri::generate_context!())
@@ -2,16 +2,24 @@
 //! Builder patterns for constructing constraint configurations and complex operations.
 
 use crate::oulipo::{
-    types::{ConstraintResult, ValidationConfig},
-    errors::{OulipoError, OulipoResult},
+    types::ConstraintResult,
+    composition::{Composition, ConstraintExpression},
+    constraints::{PalindromeMode, SnowballVariant},
+    errors::OulipoResult,
+    generation::{CandidateGenerator, GenerationOutcome},
+    OulipoService,
 };
 use anyhow::Result;
+use serde::Serialize;
 use serde_json::json;
 
 /// Builder for creating complex constraint checking workflows
+///
+/// Every constraint is stored by its registry name and configuration, so any
+/// constraint registered in the `ConstraintRegistry` can be combined in a workflow.
 pub struct ConstraintWorkflowBuilder {
     constraints: Vec<(String, serde_json::Value)>,
-    validation_config: ValidationConfig,
+    weights: Vec<f64>,
 }
 
 impl Default for ConstraintWorkflowBuilder {
@@ -25,36 +33,105 @@ impl ConstraintWorkflowBuilder {
     pub fn new() -> Self {
         Self {
             constraints: Vec::new(),
-            validation_config: ValidationConfig::default(),
+            weights: Vec::new(),
         }
     }
     
     /// Add a univocalic constraint
     pub fn with_univocalic(mut self, allowed_vowel: char) -> Self {
-        self.constraints.push((
-            "univocalic".to_string(),
-            json!({ "allowed_vowel": allowed_vowel.to_string() })
-        ));
+        self.push("univocalic", json!({ "allowed_vowel": allowed_vowel.to_string() }));
         self
     }
     
-    /// Add text length validation
-    pub fn with_length_limits(mut self, min: Option<usize>, max: Option<usize>) -> Self {
-        self.validation_config.min_length = min;
-        self.validation_config.max_length = max;
+    /// Add a lipogram constraint
+    pub fn with_lipogram(mut self, forbidden_letter: char) -> Self {
+        self.push("lipogram", json!({ "forbidden_letter": forbidden_letter.to_string() }));
         self
     }
     
-    /// Add word count validation
-    pub fn with_word_limits(mut self, min: Option<usize>, max: Option<usize>) -> Self {
-        self.validation_config.min_words = min;
-        self.validation_config.max_words = max;
+    /// Add a palindrome constraint
+    pub fn with_palindrome(mut self) -> Self {
+        self.push("palindrome", json!({}));
+        self
+    }
+    
+    /// Add a word-unit or line-unit palindrome constraint
+    pub fn with_palindrome_mode(mut self, mode: PalindromeMode) -> Self {
+        self.push("palindrome", json!({ "mode": mode }));
+        self
+    }
+    
+    /// Add a snowball constraint
+    pub fn with_snowball(mut self) -> Self {
+        self.push("snowball", json!({}));
+        self
+    }
+    
+    /// Add a melting, diamond or line-level snowball constraint
+    pub fn with_snowball_variant(mut self, variant: SnowballVariant) -> Self {
+        self.push("snowball", json!({ "variant": variant }));
+        self
+    }
+    
+    /// Add a tautogram constraint: every word begins with the given letter
+    pub fn with_tautogram(mut self, letter: char) -> Self {
+        self.push("tautogram", json!({ "letter": letter.to_string() }));
+        self
+    }
+    
+    /// Add a tautogram constraint that lets function words begin with any letter
+    pub fn with_loose_tautogram(mut self, letter: char) -> Self {
+        self.push("tautogram", json!({ "letter": letter.to_string(), "allow_function_words": true }));
+        self
+    }
+    
+    /// Add an alliteration constraint: every stressed word begins with the given sound
+    pub fn with_alliteration(mut self, letter: char) -> Self {
+        self.push("alliteration", json!({ "letter": letter.to_string() }));
+        self
+    }
+    
+    /// Add a prisoner's constraint
+    pub fn with_prisoners(mut self) -> Self {
+        self.push("prisoners", json!({}));
+        self
+    }
+    
+    /// Add a sestina constraint with its six end words
+    pub fn with_sestina(mut self, end_words: &[&str]) -> Self {
+        self.push("sestina", json!({ "end_words": end_words }));
         self
     }
     
+    /// Add text length validation
+    pub fn with_length_limits(self, min: Option<usize>, max: Option<usize>) -> Self {
+        self.replace_constraint(
+            "text_length",
+            json!({ "min_length": min, "max_length": max })
+        )
+    }
+    
+    /// Add word count validation
+    pub fn with_word_limits(self, min: Option<usize>, max: Option<usize>) -> Self {
+        self.replace_constraint(
+            "word_count",
+            json!({ "min_words": min, "max_words": max })
+        )
+    }
+    
     /// Add a custom constraint by name and configuration
     pub fn with_constraint(mut self, name: &str, config: serde_json::Value) -> Self {
-        self.constraints.push((name.to_string(), config));
+        self.push(name, config);
+        self
+    }
+    
+    /// Weigh the constraint added last in the workflow's score; constraints weigh 1 by default
+    ///
+    /// A weight of 0 keeps the constraint in the pass/fail verdict but out of the score.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        if let Some(last) = self.weights.last_mut() {
+            *last = weight.max(0.0);
+        }
         self
     }
     
@@ -62,31 +139,76 @@ impl ConstraintWorkflowBuilder {
     pub fn build(self) -> OulipoResult<ConstraintWorkflowConfig> {
         Ok(ConstraintWorkflowConfig {
             constraints: self.constraints,
-            validation_config: self.validation_config,
+            weights: self.weights,
         })
     }
+    
+    fn push(&mut self, name: &str, config: serde_json::Value) {
+        self.constraints.push((name.to_string(), config));
+        self.weights.push(1.0);
+    }
+    
+    /// Set a constraint that may only appear once, replacing any earlier configuration
+    fn replace_constraint(mut self, name: &str, config: serde_json::Value) -> Self {
+        while let Some(index) = self.constraints.iter().position(|(existing, _)| existing == name) {
+            self.constraints.remove(index);
+            self.weights.remove(index);
+        }
+        self.push(name, config);
+        self
+    }
 }
 
 /// Configuration for a constraint workflow
 #[derive(Debug, Clone)]
 pub struct ConstraintWorkflowConfig {
+    /// Registry names and configurations of the constraints to check
     pub constraints: Vec<(String, serde_json::Value)>,
-    pub validation_config: ValidationConfig,
+    /// The weight of each constraint in the score, in the same order; missing weights count as 1
+    pub weights: Vec<f64>,
+}
+
+impl ConstraintWorkflowConfig {
+    /// Check text against this workflow using the given service
+    pub fn check(&self, text: &str, service: &OulipoService) -> Result<WorkflowResult> {
+        service.check_with_workflow(text, self)
+    }
+    
+    /// The weight of the constraint at `index`
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights.get(index).copied().unwrap_or(1.0)
+    }
+    
+    /// The workflow as a composition: every constraint must hold on the whole text
+    pub fn to_composition(&self) -> Composition {
+        let children: Vec<ConstraintExpression> = self
+            .constraints
+            .iter()
+            .map(|(name, config)| ConstraintExpression::constraint(name, config.clone()))
+            .collect();
+        Composition::new(ConstraintExpression::and(children))
+    }
 }
 
 /// Result of running a complete workflow
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct WorkflowResult {
     pub success: bool,
     pub constraint_results: Vec<ConstraintResult>,
+    /// The weight each result carried in `score`, in the same order
+    pub weights: Vec<f64>,
+    /// The weighted mean of the results' scores, from 0 to 1
+    pub score: f64,
     pub summary: String,
 }
 
 /// Builder for creating text generation workflows
 pub struct GenerationWorkflowBuilder {
     theme: Option<String>,
-    constraints: Vec<String>,
+    constraints: Vec<(String, serde_json::Value)>,
     max_attempts: usize,
+    generator: Option<CandidateGenerator>,
+    seed: Option<u64>,
 }
 
 impl Default for GenerationWorkflowBuilder {
@@ -102,6 +224,8 @@ impl GenerationWorkflowBuilder {
             theme: None,
             constraints: Vec::new(),
             max_attempts: 10,
+            generator: None,
+            seed: None,
         }
     }
     
@@ -111,9 +235,15 @@ impl GenerationWorkflowBuilder {
         self
     }
     
-    /// Add a constraint that generated text must satisfy
+    /// Add a constraint that generated text must satisfy: a registry name with its default configuration, or a preset name
     pub fn with_constraint(mut self, constraint: &str) -> Self {
-        self.constraints.push(constraint.to_string());
+        self.constraints.push((constraint.to_string(), json!({})));
+        self
+    }
+    
+    /// Add a registry constraint with its configuration
+    pub fn with_constraint_config(mut self, name: &str, config: serde_json::Value) -> Self {
+        self.constraints.push((name.to_string(), config));
         self
     }
     
@@ -123,12 +253,26 @@ impl GenerationWorkflowBuilder {
         self
     }
     
+    /// Draft candidates with a specific generator instead of the one the constraints suggest
+    pub fn with_generator(mut self, generator: CandidateGenerator) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+    
+    /// Seed the attempts; the same seed gives the same run
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
     /// Build a generation workflow
     pub fn build(self) -> GenerationWorkflowConfig {
         GenerationWorkflowConfig {
             theme: self.theme.unwrap_or_else(|| "creative writing".to_string()),
             constraints: self.constraints,
             max_attempts: self.max_attempts,
+            generator: self.generator,
+            seed: self.seed,
         }
     }
 }
@@ -137,8 +281,19 @@ impl GenerationWorkflowBuilder {
 #[derive(Debug, Clone)]
 pub struct GenerationWorkflowConfig {
     pub theme: String,
-    pub constraints: Vec<String>,
+    /// Registry or preset names with their configurations
+    pub constraints: Vec<(String, serde_json::Value)>,
     pub max_attempts: usize,
+    /// The generator drafting candidates; chosen from the constraints when unset
+    pub generator: Option<CandidateGenerator>,
+    pub seed: Option<u64>,
+}
+
+impl GenerationWorkflowConfig {
+    /// Draft and check candidates until one satisfies the constraints; see `generation`
+    pub fn generate(&self, service: &OulipoService) -> Result<GenerationOutcome> {
+        service.generate_with_workflow(self)
+    }
 }
 
 /// Predefined constraint combinations for common use cases
@@ -159,10 +314,35 @@ impl ConstraintPresets {
             .with_word_limits(Some(3), Some(20))
     }
     
+    /// Create a short-form constraint: a handful of words, as in a haiku
+    pub fn short() -> ConstraintWorkflowBuilder {
+        ConstraintWorkflowBuilder::new()
+            .with_word_limits(Some(3), Some(20))
+    }
+    
     /// Create an experimental constraint combination
     pub fn experimental() -> ConstraintWorkflowBuilder {
         ConstraintWorkflowBuilder::new()
             .with_univocalic('e')
             .with_length_limits(Some(50), None)
     }
+    
+    /// Create a classic Perec-style lipogram without the letter 'e'
+    pub fn perec() -> ConstraintWorkflowBuilder {
+        ConstraintWorkflowBuilder::new()
+            .with_lipogram('e')
+            .with_word_limits(Some(1), None)
+    }
+    
+    /// Look up a preset by name
+    pub fn by_name(name: &str) -> Option<ConstraintWorkflowBuilder> {
+        match name {
+            "strict" => Some(Self::strict_writing()),
+            "minimal" => Some(Self::minimal()),
+            "short" => Some(Self::short()),
+            "experimental" => Some(Self::experimental()),
+            "perec" => Some(Self::perec()),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,573 @@
+//! Boolean and scoped composition of constraints.
+//!
+//! A composition is an expression tree whose leaves are registry constraints
+//! and whose branches combine them with AND, OR, NOT and AT_LEAST_K. Any node
+//! can be scoped to each line, stanza, sentence or paragraph, or to a named
+//! range of the text. A sequence gives each unit of its scope its own rule, so
+//! "each stanza is a lipogram in a different vowel" is a sequence of five
+//! lipograms over stanzas. Violations found inside a unit are mapped back to
+//! positions in the full text.
+
+use crate::oulipo::{
+    types::{Constraint, ConstraintResult, Violation},
+    errors::{OulipoError, OulipoResult},
+    registry::ConstraintRegistry,
+    utils::{line_spans, paragraph_spans, sentence_spans, stanza_spans},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The units of text a node applies to
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// The whole text the node receives
+    #[default]
+    Whole,
+    /// Each non-empty line
+    Line,
+    /// Each blank-line separated stanza
+    Stanza,
+    /// Each sentence, up to its closing punctuation
+    Sentence,
+    /// Each paragraph; see `utils::paragraph_spans`
+    Paragraph,
+    /// The part of the text covered by a named range of the composition
+    Range(String),
+}
+
+impl Scope {
+    /// Parse a scope name; named ranges are written `range:name`
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if let Some(range) = name.strip_prefix("range:") {
+            return Some(Scope::Range(range.trim().to_string()));
+        }
+        match name.to_lowercase().as_str() {
+            "whole" | "text" => Some(Scope::Whole),
+            "line" | "lines" => Some(Scope::Line),
+            "stanza" | "stanzas" => Some(Scope::Stanza),
+            "sentence" | "sentences" => Some(Scope::Sentence),
+            "paragraph" | "paragraphs" => Some(Scope::Paragraph),
+            _ => None,
+        }
+    }
+
+    /// Whether the scope is the whole text
+    pub fn is_whole(&self) -> bool {
+        *self == Scope::Whole
+    }
+
+    /// The units of the scope in `text` with their byte offsets; `None` for named ranges
+    pub fn units<'t>(&self, text: &'t str) -> Option<Vec<(usize, &'t str)>> {
+        Some(match self {
+            Scope::Whole => vec![(0, text)],
+            Scope::Line => line_spans(text),
+            Scope::Stanza => stanza_spans(text)
+                .into_iter()
+                .filter_map(|lines| {
+                    let &(start, _) = lines.first()?;
+                    let &(last, content) = lines.last()?;
+                    Some((start, &text[start..last + content.len()]))
+                })
+                .collect(),
+            Scope::Sentence => sentence_spans(text),
+            Scope::Paragraph => paragraph_spans(text),
+            Scope::Range(_) => return None,
+        })
+    }
+
+    /// Name of the `index`th unit (0-based), used to prefix violations
+    pub fn unit_label(&self, index: usize) -> String {
+        match self {
+            Scope::Whole => "Text".to_string(),
+            Scope::Line => format!("Line {}", index + 1),
+            Scope::Stanza => format!("Stanza {}", index + 1),
+            Scope::Sentence => format!("Sentence {}", index + 1),
+            Scope::Paragraph => format!("Paragraph {}", index + 1),
+            Scope::Range(name) => format!("Range '{}'", name),
+        }
+    }
+
+    /// Plural unit name for counts in messages
+    fn plural(&self) -> &'static str {
+        match self {
+            Scope::Whole => "texts",
+            Scope::Line => "lines",
+            Scope::Stanza => "stanzas",
+            Scope::Sentence => "sentences",
+            Scope::Paragraph => "paragraphs",
+            Scope::Range(_) => "ranges",
+        }
+    }
+}
+
+/// A byte range of the text, referred to by name from `Scope::Range`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A node of a composition: a constraint or a combinator over child nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ConstraintExpression {
+    /// A registry constraint by name and configuration
+    Constraint {
+        name: String,
+        #[serde(default)]
+        config: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Scope::is_whole")]
+        scope: Scope,
+    },
+    /// Every child must pass
+    And {
+        children: Vec<ConstraintExpression>,
+        #[serde(default, skip_serializing_if = "Scope::is_whole")]
+        scope: Scope,
+    },
+    /// At least one child must pass
+    Or {
+        children: Vec<ConstraintExpression>,
+        #[serde(default, skip_serializing_if = "Scope::is_whole")]
+        scope: Scope,
+    },
+    /// The child must fail
+    Not {
+        child: Box<ConstraintExpression>,
+        #[serde(default, skip_serializing_if = "Scope::is_whole")]
+        scope: Scope,
+    },
+    /// At least `k` children must pass
+    AtLeastK {
+        k: usize,
+        children: Vec<ConstraintExpression>,
+        #[serde(default, skip_serializing_if = "Scope::is_whole")]
+        scope: Scope,
+    },
+    /// The `i`th child applies to the `i`th unit of the scope, which may not be `whole`
+    Sequence {
+        children: Vec<ConstraintExpression>,
+        scope: Scope,
+    },
+}
+
+impl ConstraintExpression {
+    /// A registry constraint applied to the whole text
+    pub fn constraint(name: &str, config: serde_json::Value) -> Self {
+        ConstraintExpression::Constraint { name: name.to_string(), config, scope: Scope::Whole }
+    }
+
+    /// Every child must pass
+    pub fn and(children: Vec<ConstraintExpression>) -> Self {
+        ConstraintExpression::And { children, scope: Scope::Whole }
+    }
+
+    /// At least one child must pass
+    pub fn or(children: Vec<ConstraintExpression>) -> Self {
+        ConstraintExpression::Or { children, scope: Scope::Whole }
+    }
+
+    /// The child must fail
+    pub fn negate(child: ConstraintExpression) -> Self {
+        ConstraintExpression::Not { child: Box::new(child), scope: Scope::Whole }
+    }
+
+    /// At least `k` children must pass
+    pub fn at_least(k: usize, children: Vec<ConstraintExpression>) -> Self {
+        ConstraintExpression::AtLeastK { k, children, scope: Scope::Whole }
+    }
+
+    /// One child per unit of the scope, in order
+    pub fn sequence(scope: Scope, children: Vec<ConstraintExpression>) -> Self {
+        ConstraintExpression::Sequence { children, scope }
+    }
+
+    /// Apply this node to each unit of `scope` instead of the whole text
+    pub fn scoped(mut self, new_scope: Scope) -> Self {
+        match &mut self {
+            ConstraintExpression::Constraint { scope, .. }
+            | ConstraintExpression::And { scope, .. }
+            | ConstraintExpression::Or { scope, .. }
+            | ConstraintExpression::Not { scope, .. }
+            | ConstraintExpression::AtLeastK { scope, .. }
+            | ConstraintExpression::Sequence { scope, .. } => *scope = new_scope,
+        }
+        self
+    }
+
+    /// The node's scope
+    pub fn scope(&self) -> &Scope {
+        match self {
+            ConstraintExpression::Constraint { scope, .. }
+            | ConstraintExpression::And { scope, .. }
+            | ConstraintExpression::Or { scope, .. }
+            | ConstraintExpression::Not { scope, .. }
+            | ConstraintExpression::AtLeastK { scope, .. }
+            | ConstraintExpression::Sequence { scope, .. } => scope,
+        }
+    }
+
+    /// Short name of the node: the constraint name or the operator
+    pub fn label(&self) -> String {
+        match self {
+            ConstraintExpression::Constraint { name, .. } => name.clone(),
+            ConstraintExpression::And { .. } => "and".to_string(),
+            ConstraintExpression::Or { .. } => "or".to_string(),
+            ConstraintExpression::Not { .. } => "not".to_string(),
+            ConstraintExpression::AtLeastK { k, .. } => format!("at_least_k({})", k),
+            ConstraintExpression::Sequence { .. } => "sequence".to_string(),
+        }
+    }
+}
+
+/// An expression with the named ranges its scopes refer to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Composition {
+    pub expression: ConstraintExpression,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ranges: BTreeMap<String, NamedRange>,
+}
+
+impl Composition {
+    /// Create a composition without named ranges
+    pub fn new(expression: ConstraintExpression) -> Self {
+        Self { expression, ranges: BTreeMap::new() }
+    }
+
+    /// Name the bytes `start..end` of the text for `Scope::Range`
+    pub fn with_range(mut self, name: &str, start: usize, end: usize) -> Self {
+        self.ranges.insert(name.to_string(), NamedRange { start, end });
+        self
+    }
+
+    /// Check text against the composition, creating constraints from the registry
+    ///
+    /// Every constraint is created before any checking, so an unknown name or
+    /// bad configuration anywhere in the tree is an error. The result's
+    /// metadata holds an `outcome` tree mirroring the expression.
+    pub fn check(&self, text: &str, registry: &ConstraintRegistry) -> Result<ConstraintResult> {
+        let compiled = self.compile(&self.expression, registry)?;
+        let evaluation = Evaluator { ranges: &self.ranges, text }.scoped(&compiled, 0, text)?;
+
+        let success = evaluation.success;
+        let mut failing = Vec::new();
+        evaluation.outcome.failing_constraints(&mut failing);
+        let violation_count = evaluation.violations.len();
+
+        Ok(ConstraintResult {
+            success,
+            result: Some(if success {
+                "Composition satisfied".to_string()
+            } else {
+                format!("{} violations found", violation_count)
+            }),
+            violations: evaluation.violations,
+            suggestions: if success {
+                vec!["Every part of the composition holds".to_string()]
+            } else {
+                vec![
+                    format!("Revise the parts failing {}", failing.join(", ")),
+                    "Check each unit on its own: violations are prefixed with the unit they occur in".to_string(),
+                ]
+            },
+            metadata: serde_json::json!({
+                "constraint_type": "composition",
+                "outcome": evaluation.outcome,
+                "violation_count": violation_count
+            }),
+        }.located(text))
+    }
+
+    /// Create every constraint of the tree and validate its structure
+    fn compile<'a>(&self, expression: &'a ConstraintExpression, registry: &ConstraintRegistry) -> OulipoResult<Compiled<'a>> {
+        if let Scope::Range(name) = expression.scope() {
+            if !self.ranges.contains_key(name) {
+                return Err(OulipoError::InvalidConfig(format!("Unknown range '{}'", name)));
+            }
+        }
+        let children = |children: &'a [ConstraintExpression]| -> OulipoResult<Vec<Compiled<'a>>> {
+            if children.is_empty() {
+                return Err(OulipoError::InvalidConfig(format!("'{}' needs at least one child", expression.label())));
+            }
+            children.iter().map(|child| self.compile(child, registry)).collect()
+        };
+
+        let kind = match expression {
+            ConstraintExpression::Constraint { name, config, .. } => Kind::Leaf(registry.create_constraint(name, config)?),
+            ConstraintExpression::And { children: c, .. } => Kind::And(children(c)?),
+            ConstraintExpression::Or { children: c, .. } => Kind::Or(children(c)?),
+            ConstraintExpression::Not { child, .. } => Kind::Not(Box::new(self.compile(child, registry)?)),
+            ConstraintExpression::AtLeastK { k, children: c, .. } => {
+                if *k > c.len() {
+                    return Err(OulipoError::InvalidConfig(format!(
+                        "at_least_k needs {} passing children but has only {}",
+                        k,
+                        c.len()
+                    )));
+                }
+                Kind::AtLeastK(*k, children(c)?)
+            }
+            ConstraintExpression::Sequence { children: c, scope } => {
+                if scope.is_whole() {
+                    return Err(OulipoError::InvalidConfig("A sequence needs a scope such as 'line' or 'stanza'".to_string()));
+                }
+                Kind::Sequence(children(c)?)
+            }
+        };
+        Ok(Compiled { expression, kind })
+    }
+}
+
+/// How one node of a composition fared, summed over the units it applied to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeOutcome {
+    /// The constraint name or operator
+    pub label: String,
+    pub scope: Scope,
+    pub success: bool,
+    /// Units the node was checked on, and how many of them failed
+    pub units: usize,
+    pub failed_units: usize,
+    pub violation_count: usize,
+    pub children: Vec<NodeOutcome>,
+}
+
+impl NodeOutcome {
+    fn new(expression: &ConstraintExpression) -> Self {
+        Self {
+            label: expression.label(),
+            scope: expression.scope().clone(),
+            success: true,
+            units: 0,
+            failed_units: 0,
+            violation_count: 0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Add the outcome of the same node on another unit
+    fn merge(&mut self, other: NodeOutcome) {
+        self.success &= other.success;
+        self.units += other.units;
+        self.failed_units += other.failed_units;
+        self.violation_count += other.violation_count;
+        if self.children.is_empty() {
+            self.children = other.children;
+        } else {
+            for (child, other) in self.children.iter_mut().zip(other.children) {
+                child.merge(other);
+            }
+        }
+    }
+
+    /// Names of the failing constraints, each once
+    fn failing_constraints(&self, names: &mut Vec<String>) {
+        if self.success {
+            return;
+        }
+        if self.children.is_empty() {
+            if !names.contains(&self.label) {
+                names.push(self.label.clone());
+            }
+        } else {
+            for child in &self.children {
+                child.failing_constraints(names);
+            }
+        }
+    }
+}
+
+/// An expression node with its constraint created
+struct Compiled<'a> {
+    expression: &'a ConstraintExpression,
+    kind: Kind<'a>,
+}
+
+enum Kind<'a> {
+    Leaf(Box<dyn Constraint>),
+    And(Vec<Compiled<'a>>),
+    Or(Vec<Compiled<'a>>),
+    Not(Box<Compiled<'a>>),
+    AtLeastK(usize, Vec<Compiled<'a>>),
+    Sequence(Vec<Compiled<'a>>),
+}
+
+/// The result of a node on one piece of text, with absolute positions
+struct Evaluation {
+    success: bool,
+    violations: Vec<Violation>,
+    outcome: NodeOutcome,
+}
+
+struct Evaluator<'a> {
+    ranges: &'a BTreeMap<String, NamedRange>,
+    /// The full text, for named ranges
+    text: &'a str,
+}
+
+impl Evaluator<'_> {
+    /// The units of `scope` within the piece of text at `offset`
+    fn units<'t>(&self, scope: &Scope, offset: usize, text: &'t str) -> OulipoResult<Vec<(usize, &'t str)>> {
+        let spans = match scope {
+            Scope::Range(name) => {
+                let range = self.ranges[name];
+                if range.start > range.end || range.end > self.text.len() {
+                    return Err(OulipoError::InvalidConfig(format!(
+                        "Range '{}' ({}..{}) is outside the text",
+                        name, range.start, range.end
+                    )));
+                }
+                let start = range.start.clamp(offset, offset + text.len()) - offset;
+                let end = range.end.clamp(offset, offset + text.len()) - offset;
+                if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                    return Err(OulipoError::InvalidConfig(format!("Range '{}' splits a character", name)));
+                }
+                if start == end {
+                    Vec::new()
+                } else {
+                    vec![(start, &text[start..end])]
+                }
+            }
+            _ => scope.units(text).unwrap_or_default(),
+        };
+        Ok(spans.into_iter().map(|(start, unit)| (offset + start, unit)).collect())
+    }
+
+    /// Apply a node to each unit of its scope; it passes when every unit passes
+    fn scoped(&self, node: &Compiled, offset: usize, text: &str) -> Result<Evaluation> {
+        let scope = node.expression.scope();
+        let units = self.units(scope, offset, text)?;
+        let mut outcome = NodeOutcome::new(node.expression);
+        let mut violations = Vec::new();
+
+        if let Kind::Sequence(children) = &node.kind {
+            outcome.children = children.iter().map(|child| NodeOutcome::new(child.expression)).collect();
+            for (i, &(unit_offset, unit)) in units.iter().enumerate() {
+                let label = scope.unit_label(i);
+                let Some(child) = children.get(i) else {
+                    violations.push(Violation {
+                        position: unit_offset,
+                        length: unit.len(),
+                        span: None,
+                        issue: format!("{} is beyond the {} the sequence describes", label, children.len()),
+                        suggestion: Some(format!("End the text after {} {}", children.len(), scope.plural())),
+                        fixes: Vec::new(),
+                    });
+                    continue;
+                };
+                let evaluation = self.scoped(child, unit_offset, unit)?;
+                outcome.children[i] = evaluation.outcome;
+                violations.extend(evaluation.violations.into_iter().map(|v| prefixed(&label, v)));
+            }
+            if units.len() < children.len() {
+                violations.push(Violation {
+                    position: offset + text.len(),
+                    length: 0,
+                    span: None,
+                    issue: format!("The sequence needs {} {}, but the text has {}", children.len(), scope.plural(), units.len()),
+                    suggestion: Some(format!("Add {} more {}", children.len() - units.len(), scope.plural())),
+                    fixes: Vec::new(),
+                });
+            }
+            outcome.units = units.len();
+            outcome.failed_units = outcome.children.iter().filter(|c| !c.success).count();
+        } else {
+            for (i, &(unit_offset, unit)) in units.iter().enumerate() {
+                let evaluation = self.node(node, unit_offset, unit)?;
+                outcome.merge(evaluation.outcome);
+                if scope.is_whole() {
+                    violations.extend(evaluation.violations);
+                } else {
+                    let label = scope.unit_label(i);
+                    violations.extend(evaluation.violations.into_iter().map(|v| prefixed(&label, v)));
+                }
+            }
+        }
+
+        violations.sort_by_key(|v| v.position);
+        let success = violations.is_empty() && outcome.failed_units == 0;
+        outcome.success = success;
+        outcome.violation_count = violations.len();
+        Ok(Evaluation { success, violations, outcome })
+    }
+
+    /// Apply a node's rule to one unit
+    fn node(&self, node: &Compiled, offset: usize, text: &str) -> Result<Evaluation> {
+        let mut outcome = NodeOutcome::new(node.expression);
+        let mut children = |nodes: &[Compiled]| -> Result<Vec<Evaluation>> {
+            let evaluations = nodes.iter().map(|child| self.scoped(child, offset, text)).collect::<Result<Vec<_>>>()?;
+            outcome.children = evaluations.iter().map(|e| e.outcome.clone()).collect();
+            Ok(evaluations)
+        };
+
+        let (success, violations) = match &node.kind {
+            Kind::Leaf(constraint) => {
+                let result = constraint.check(text)?;
+                let mut violations: Vec<Violation> = result
+                    .violations
+                    .into_iter()
+                    .map(|v| v.shifted(offset))
+                    .collect();
+                if !result.success && violations.is_empty() {
+                    violations.push(Violation {
+                        position: offset,
+                        length: text.len(),
+                        span: None,
+                        issue: result.result.unwrap_or_else(|| format!("{} is not satisfied", constraint.name())),
+                        suggestion: result.suggestions.into_iter().next(),
+                        fixes: Vec::new(),
+                    });
+                }
+                (result.success, violations)
+            }
+            Kind::And(nodes) => {
+                let evaluations = children(nodes)?;
+                (evaluations.iter().all(|e| e.success), evaluations.into_iter().flat_map(|e| e.violations).collect())
+            }
+            Kind::Or(nodes) => closest(children(nodes)?, 1),
+            Kind::AtLeastK(k, nodes) => closest(children(nodes)?, *k),
+            Kind::Not(child) => {
+                let evaluation = children(std::slice::from_ref(child.as_ref()))?.remove(0);
+                let violations = if evaluation.success {
+                    vec![Violation {
+                        position: offset,
+                        length: text.len(),
+                        span: None,
+                        issue: format!("Satisfies '{}', which is not allowed here", child.expression.label()),
+                        suggestion: Some(format!("Break the '{}' constraint", child.expression.label())),
+                        fixes: Vec::new(),
+                    }]
+                } else {
+                    Vec::new()
+                };
+                (!evaluation.success, violations)
+            }
+            Kind::Sequence(_) => unreachable!("sequences are applied unit by unit in `scoped`"),
+        };
+
+        outcome.units = 1;
+        outcome.failed_units = usize::from(!success);
+        outcome.success = success;
+        outcome.violation_count = violations.len();
+        Ok(Evaluation { success, violations, outcome })
+    }
+}
+
+/// Pass when `k` children pass; otherwise report the failing children nearest to passing
+fn closest(evaluations: Vec<Evaluation>, k: usize) -> (bool, Vec<Violation>) {
+    let passed = evaluations.iter().filter(|e| e.success).count();
+    if passed >= k {
+        return (true, Vec::new());
+    }
+    let mut failing: Vec<Evaluation> = evaluations.into_iter().filter(|e| !e.success).collect();
+    failing.sort_by_key(|e| e.violations.len());
+    (false, failing.into_iter().take(k - passed).flat_map(|e| e.violations).collect())
+}
+
+/// Name the unit a violation was found in
+fn prefixed(label: &str, violation: Violation) -> Violation {
+    Violation { issue: format!("{}: {}", label, violation.issue), ..violation }
+}
@@ -0,0 +1,133 @@
+//! Shared checks for fixed poetic forms: stanza layout, repeated lines and
+//! rhyme groups. Used by the villanelle, pantoum, terza rima, rondeau and
+//! ghazal validators.
+
+use crate::oulipo::{
+    types::Violation,
+    rhyme::{end_word, RhymeEngine, RhymeLevel},
+    utils::normalize_line,
+};
+
+/// Report stanzas whose length differs from the form's
+///
+/// Poems written without blank lines between stanzas are accepted as is; the
+/// layout is only checked when the text is actually broken into stanzas.
+pub fn stanza_layout_violations(stanzas: &[Vec<(usize, &str)>], expected: &[usize], form: &str) -> Vec<Violation> {
+    if stanzas.len() <= 1 {
+        return Vec::new();
+    }
+
+    let mut violations = Vec::new();
+    for (idx, stanza) in stanzas.iter().enumerate() {
+        let (start, _) = stanza[0];
+        let (last_offset, last_line) = stanza[stanza.len() - 1];
+        let span = last_offset + last_line.len() - start;
+
+        match expected.get(idx) {
+            Some(&size) if size != stanza.len() => violations.push(Violation {
+                position: start,
+                length: span,
+                span: None,
+                issue: format!("Stanza {} has {} lines; a {} expects {}", idx + 1, stanza.len(), form, size),
+                suggestion: Some(format!("Rework stanza {} into {} lines", idx + 1, size)),
+                fixes: Vec::new(),
+            }),
+            None => violations.push(Violation {
+                position: start,
+                length: span,
+                span: None,
+                issue: format!("Stanza {} is extra; a {} has {} stanzas", idx + 1, form, expected.len()),
+                suggestion: Some("Remove or merge the extra stanza".to_string()),
+                fixes: Vec::new(),
+            }),
+            _ => {}
+        }
+    }
+
+    if stanzas.len() < expected.len() {
+        let (offset, line) = *stanzas.last().and_then(|s| s.last()).expect("stanzas are non-empty");
+        violations.push(Violation {
+            position: offset,
+            length: line.len(),
+            span: None,
+            issue: format!("Only {} stanzas; a {} has {}", stanzas.len(), form, expected.len()),
+            suggestion: Some(format!("Add {} more stanzas", expected.len() - stanzas.len())),
+            fixes: Vec::new(),
+        });
+    }
+
+    violations
+}
+
+/// Report a line that should repeat an earlier one word for word
+///
+/// Case, punctuation and spacing are ignored when comparing.
+pub fn repeated_line_violation(lines: &[(usize, &str)], source: usize, target: usize, label: &str) -> Option<Violation> {
+    let (&(_, original), &(offset, repeat)) = (lines.get(source)?, lines.get(target)?);
+    if normalize_line(original) == normalize_line(repeat) {
+        return None;
+    }
+
+    Some(Violation {
+        position: offset,
+        length: repeat.len(),
+        span: None,
+        issue: format!("Line {} should repeat {} from line {}: \"{}\"", target + 1, label, source + 1, original.trim()),
+        suggestion: Some(format!("Replace line {} with \"{}\"", target + 1, original.trim())),
+        fixes: Vec::new(),
+    })
+}
+
+/// Report lines whose end words fail to rhyme with the rest of their group
+///
+/// Each group lists line indexes that must rhyme; a line passes when it rhymes
+/// with any earlier line of its group at `min_level` or better.
+pub fn rhyme_group_violations(
+    lines: &[(usize, &str)],
+    groups: &[Vec<usize>],
+    min_level: RhymeLevel,
+    engine: &RhymeEngine,
+) -> Vec<Violation> {
+    let end_of = |idx: usize| {
+        lines
+            .get(idx)
+            .and_then(|&(offset, line)| end_word(line).map(|(pos, word)| (offset + pos, word)))
+    };
+
+    let mut violations = Vec::new();
+    for group in groups {
+        for (member_idx, &line_idx) in group.iter().enumerate().skip(1) {
+            let Some((position, word)) = end_of(line_idx) else {
+                continue;
+            };
+            let earlier: Vec<(usize, &str)> = group[..member_idx]
+                .iter()
+                .filter_map(|&prev| end_of(prev).map(|(_, w)| (prev, w)))
+                .collect();
+            let Some(&(anchor_idx, anchor)) = earlier.first() else {
+                continue;
+            };
+
+            if !earlier.iter().any(|&(_, w)| engine.rhymes(w, word, min_level)) {
+                violations.push(Violation {
+                    position,
+                    length: word.len(),
+                    span: None,
+                    issue: format!(
+                        "Line {} ends with '{}', which does not rhyme with '{}' (line {})",
+                        line_idx + 1, word, anchor, anchor_idx + 1
+                    ),
+                    suggestion: Some(format!("End the line with a word that rhymes with '{}'", anchor)),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Sizes of each stanza, for metadata
+pub fn stanza_sizes(stanzas: &[Vec<(usize, &str)>]) -> Vec<usize> {
+    stanzas.iter().map(Vec::len).collect()
+}
@@ -0,0 +1,212 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::fixed_form::{stanza_layout_violations, stanza_sizes},
+    rhyme::{RhymeEngine, RhymeLevel},
+    utils::{line_spans, normalize_line, stanza_spans, word_spans},
+};
+use anyhow::Result;
+
+/// Fewest couplets (shers) a ghazal traditionally has
+const MIN_COUPLETS: usize = 5;
+
+/// Ghazal constraint - couplets bound by a radif (refrain) and qafia (rhyme before it)
+pub struct GhazalConstraint {
+    radif: Option<String>,
+    min_level: RhymeLevel,
+    engine: RhymeEngine,
+}
+
+impl GhazalConstraint {
+    /// Create a ghazal constraint that detects the radif from the opening couplet
+    pub fn new() -> Self {
+        Self {
+            radif: None,
+            min_level: RhymeLevel::Slant,
+            engine: RhymeEngine::new(),
+        }
+    }
+
+    /// Require a specific radif instead of detecting it
+    pub fn with_radif(mut self, radif: &str) -> Self {
+        self.radif = Some(radif.to_string());
+        self
+    }
+
+    /// Weakest rhyme accepted for the qafia, slant by default
+    pub fn with_min_level(mut self, min_level: RhymeLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+}
+
+impl Default for GhazalConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for GhazalConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, self.radif.as_deref(), self.min_level, &self.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Ghazal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Couplets where both opening lines and every second line end in the same radif"
+    }
+}
+
+/// Check if text is a ghazal
+///
+/// Both lines of the opening couplet (matla) and the second line of every
+/// later couplet must end with the radif, and the word just before it (qafia)
+/// must rhyme across those lines. Without an explicit radif, the words shared
+/// at the end of the matla are used.
+pub fn check(text: &str, radif: Option<&str>, min_level: RhymeLevel, engine: &RhymeEngine) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+    let couplets = lines.len().div_ceil(2);
+
+    if lines.len() < 2 {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: "Ghazal needs at least an opening couplet".to_string(),
+                suggestion: Some("Open with two lines ending in the same refrain (radif)".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec![format!("A ghazal has at least {} couplets", MIN_COUPLETS)],
+            metadata: serde_json::json!({
+                "constraint_type": "ghazal",
+                "line_count": lines.len()
+            }),
+        }.located(text));
+    }
+
+    let mut violations = stanza_layout_violations(&stanzas, &vec![2; stanzas.len().max(couplets)], "ghazal");
+    if couplets < MIN_COUPLETS || !lines.len().is_multiple_of(2) {
+        let (offset, line) = lines[lines.len() - 1];
+        violations.push(Violation {
+            position: offset,
+            length: line.len(),
+            span: None,
+            issue: format!("Ghazal has {} lines; it needs at least {} complete couplets", lines.len(), MIN_COUPLETS),
+            suggestion: Some("Write whole couplets, each closing on the radif".to_string()),
+            fixes: Vec::new(),
+        });
+    }
+
+    let radif_words: Vec<String> = match radif {
+        Some(radif) => normalize_line(radif).split(' ').filter(|w| !w.is_empty()).map(str::to_string).collect(),
+        None => common_ending(&normalize_line(lines[0].1), &normalize_line(lines[1].1)),
+    };
+    let radif_text = radif_words.join(" ");
+
+    // Lines that carry the radif: both lines of the matla, then every second line
+    let refrain_lines: Vec<usize> = [0].into_iter().chain((1..lines.len()).step_by(2)).collect();
+    let mut qafia: Vec<(usize, usize, &str)> = Vec::new();
+
+    if radif_words.is_empty() {
+        let (offset, line) = lines[1];
+        violations.push(Violation {
+            position: offset,
+            length: line.len(),
+            span: None,
+            issue: "The opening couplet does not share a radif".to_string(),
+            suggestion: Some("End both lines of the first couplet with the same word or phrase".to_string()),
+            fixes: Vec::new(),
+        });
+    } else {
+        for &idx in &refrain_lines {
+            let (offset, line) = lines[idx];
+            let words = word_spans(line);
+            let ends_with_radif = words.len() >= radif_words.len()
+                && words[words.len() - radif_words.len()..]
+                    .iter()
+                    .zip(&radif_words)
+                    .all(|((_, w), r)| w.to_lowercase().replace('’', "'") == *r);
+
+            if !ends_with_radif {
+                violations.push(Violation {
+                    position: offset,
+                    length: line.len(),
+                    span: None,
+                    issue: format!("Couplet {}, line {} should end with the radif '{}'", idx / 2 + 1, idx % 2 + 1, radif_text),
+                    suggestion: Some(format!("Close the line on '{}'", radif_text)),
+                    fixes: Vec::new(),
+                });
+            } else if let Some(&(pos, word)) = words.len().checked_sub(radif_words.len() + 1).and_then(|i| words.get(i)) {
+                qafia.push((idx, offset + pos, word));
+            }
+        }
+    }
+
+    if let Some(&(anchor_line, _, anchor)) = qafia.first() {
+        for (i, &(idx, position, word)) in qafia.iter().enumerate().skip(1) {
+            if !qafia[..i].iter().any(|&(_, _, prev)| engine.rhymes(prev, word, min_level)) {
+                violations.push(Violation {
+                    position,
+                    length: word.len(),
+                    span: None,
+                    issue: format!(
+                        "Line {}: '{}' before the radif does not rhyme with '{}' (line {})",
+                        idx + 1, word, anchor, anchor_line + 1
+                    ),
+                    suggestion: Some(format!("Place a word rhyming with '{}' just before the radif", anchor)),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+    }
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            "Valid ghazal".to_string()
+        } else {
+            format!("Ghazal has {} violations", violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec!["Perfect ghazal!".to_string()]
+        } else {
+            vec![
+                "Each couplet should stand alone as a complete thought".to_string(),
+                "Keep the radif identical and rhyme the word before it".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "ghazal",
+            "radif": radif_text,
+            "qafia": qafia.iter().map(|&(_, _, word)| word).collect::<Vec<_>>(),
+            "couplets": couplets,
+            "min_level": min_level,
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count
+        }),
+    }.located(text))
+}
+
+/// Longest run of words two normalised lines end with
+fn common_ending(first: &str, second: &str) -> Vec<String> {
+    let mut shared: Vec<String> = first
+        .split(' ')
+        .rev()
+        .zip(second.split(' ').rev())
+        .take_while(|(a, b)| !a.is_empty() && a == b)
+        .map(|(a, _)| a.to_string())
+        .collect();
+    shared.reverse();
+    shared
+}
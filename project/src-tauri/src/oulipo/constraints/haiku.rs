@@ -0,0 +1,161 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    errors::{OulipoError, OulipoResult},
+    phonetics::{PronunciationDictionary, SyllableSource},
+    utils::{line_spans, word_spans},
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// The traditional haiku syllable pattern
+pub const HAIKU_PATTERN: [usize; 3] = [5, 7, 5];
+
+/// Haiku constraint - each line must have the expected number of syllables
+pub struct HaikuConstraint {
+    pattern: Vec<usize>,
+    pronunciations: Arc<PronunciationDictionary>,
+}
+
+impl HaikuConstraint {
+    /// Create a 5-7-5 haiku constraint using the built-in pronunciation lexicon
+    pub fn new() -> Self {
+        Self {
+            pattern: HAIKU_PATTERN.to_vec(),
+            pronunciations: PronunciationDictionary::shared(),
+        }
+    }
+
+    /// Use a custom syllable pattern, e.g. `[5, 7, 5, 7, 7]` for a tanka
+    pub fn with_pattern(mut self, pattern: Vec<usize>) -> OulipoResult<Self> {
+        if pattern.is_empty() || pattern.contains(&0) {
+            return Err(OulipoError::InvalidConfig(
+                "Syllable pattern must list at least one positive count".to_string()
+            ));
+        }
+        self.pattern = pattern;
+        Ok(self)
+    }
+
+    /// Use a specific pronunciation dictionary for syllable counting
+    pub fn with_pronunciations(mut self, pronunciations: Arc<PronunciationDictionary>) -> Self {
+        self.pronunciations = pronunciations;
+        self
+    }
+}
+
+impl Default for HaikuConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for HaikuConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_with_pattern(text, &self.pattern, &self.pronunciations)
+    }
+
+    fn name(&self) -> &'static str {
+        "Haiku"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lines must follow a syllable pattern, 5-7-5 by default"
+    }
+}
+
+/// Check if text is a 5-7-5 haiku
+pub fn check(text: &str, pronunciations: &PronunciationDictionary) -> Result<ConstraintResult> {
+    check_with_pattern(text, &HAIKU_PATTERN, pronunciations)
+}
+
+/// Check each non-empty line against a syllable pattern
+pub fn check_with_pattern(
+    text: &str,
+    pattern: &[usize],
+    pronunciations: &PronunciationDictionary,
+) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let mut violations = Vec::new();
+    let mut line_reports = Vec::new();
+
+    if lines.len() != pattern.len() {
+        violations.push(Violation {
+            position: 0,
+            length: text.len(),
+            span: None,
+            issue: format!("Expected {} lines, found {}", pattern.len(), lines.len()),
+            suggestion: Some(format!("Write {} lines with {} syllables",
+                pattern.len(),
+                pattern.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("-"))),
+            fixes: Vec::new(),
+        });
+    }
+
+    for (idx, &(offset, line)) in lines.iter().enumerate() {
+        let words: Vec<serde_json::Value> = word_spans(line)
+            .into_iter()
+            .map(|(_, word)| {
+                let (count, source) = pronunciations.syllables(word);
+                serde_json::json!({
+                    "word": word,
+                    "syllables": count,
+                    "source": source
+                })
+            })
+            .collect();
+        let count: usize = words.iter().filter_map(|w| w["syllables"].as_u64()).sum::<u64>() as usize;
+        let estimated = words.iter().any(|w| w["source"] == serde_json::json!(SyllableSource::Estimated));
+        let expected = pattern.get(idx).copied();
+
+        if let Some(expected) = expected {
+            if count != expected {
+                let (verb, amount) = if count > expected { ("Remove", count - expected) } else { ("Add", expected - count) };
+                violations.push(Violation {
+                    position: offset,
+                    length: line.len(),
+                    span: None,
+                    issue: format!("Line {} has {} syllables (expected {})", idx + 1, count, expected),
+                    suggestion: Some(format!("{} {} syllable{}", verb, amount, if amount == 1 { "" } else { "s" })),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+
+        line_reports.push(serde_json::json!({
+            "line": idx + 1,
+            "syllables": count,
+            "expected": expected,
+            "estimated": estimated,
+            "words": words
+        }));
+    }
+
+    let success = violations.is_empty();
+    let violation_count = violations.len();
+    let counts: Vec<usize> = line_reports.iter().filter_map(|r| r["syllables"].as_u64().map(|n| n as usize)).collect();
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success {
+            "Valid haiku".to_string()
+        } else {
+            format!("{} syllable pattern violations", violation_count)
+        }),
+        violations,
+        suggestions: if success {
+            vec!["Perfect syllable pattern!".to_string()]
+        } else {
+            vec![
+                "Swap words for shorter or longer synonyms to adjust syllables".to_string(),
+                "Drop or add articles and adjectives for fine tuning".to_string(),
+                "Read each line aloud and tap out the syllables".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "haiku",
+            "syllable_pattern": pattern,
+            "line_syllables": counts,
+            "lines": line_reports
+        }),
+    }.located(text))
+}
@@ -0,0 +1,387 @@
+//! Letter-inventory constraints: pangram, heterogram, n-isogram and per-letter quotas.
+//!
+//! All four count the letters of the text, case-insensitively, and compare
+//! each count with a minimum and maximum. Over-used letters are reported at
+//! every occurrence past the maximum, with a fix deleting it; under-used
+//! letters at their last occurrence, and missing letters at the end of the text.
+//! The score is the share of quotas the text meets.
+
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Fix, Constraint},
+    errors::{OulipoError, OulipoResult},
+    utils::{match_case, share},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The letters a pangram must use
+pub const ENGLISH_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// How often a letter may appear
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LetterQuota {
+    #[serde(default)]
+    pub min: Option<usize>,
+    #[serde(default)]
+    pub max: Option<usize>,
+}
+
+impl LetterQuota {
+    /// Exactly `count` occurrences
+    pub fn exactly(count: usize) -> Self {
+        Self { min: Some(count), max: Some(count) }
+    }
+
+    fn allows(&self, count: usize) -> bool {
+        self.min.is_none_or(|min| count >= min) && self.max.is_none_or(|max| count <= max)
+    }
+
+    fn describe(&self) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => format!("exactly {}", min),
+            (Some(min), Some(max)) => format!("between {} and {}", min, max),
+            (Some(min), None) => format!("at least {}", min),
+            (None, Some(max)) => format!("at most {}", max),
+            (None, None) => "any number of times".to_string(),
+        }
+    }
+}
+
+/// Pangram constraint - every letter of the alphabet must appear
+pub struct PangramConstraint {
+    alphabet: Vec<char>,
+}
+
+impl PangramConstraint {
+    /// Create a pangram constraint over the English alphabet
+    pub fn new() -> Self {
+        Self { alphabet: ENGLISH_ALPHABET.chars().collect() }
+    }
+
+    /// Require a different set of letters, e.g. with accented letters
+    pub fn with_alphabet(mut self, alphabet: &str) -> OulipoResult<Self> {
+        self.alphabet = parse_alphabet(alphabet)?;
+        Ok(self)
+    }
+}
+
+impl Default for PangramConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for PangramConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_pangram_with_alphabet(text, &self.alphabet)
+    }
+
+    fn name(&self) -> &'static str {
+        "Pangram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every letter of the alphabet must appear"
+    }
+}
+
+/// Heterogram constraint - no letter may appear more than once
+#[derive(Debug, Clone, Default)]
+pub struct HeterogramConstraint;
+
+impl HeterogramConstraint {
+    /// Create a new heterogram constraint
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Constraint for HeterogramConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_heterogram(text)
+    }
+
+    fn name(&self) -> &'static str {
+        "Heterogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "No letter may appear more than once"
+    }
+}
+
+/// N-isogram constraint - every letter used must appear exactly n times
+pub struct IsogramConstraint {
+    n: usize,
+}
+
+impl IsogramConstraint {
+    /// Create an n-isogram constraint; n must be at least 1
+    pub fn new(n: usize) -> OulipoResult<Self> {
+        if n == 0 {
+            return Err(OulipoError::InvalidConfig("An isogram needs each letter at least once (n >= 1)".to_string()));
+        }
+        Ok(Self { n })
+    }
+}
+
+impl Constraint for IsogramConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_isogram(text, self.n)
+    }
+
+    fn name(&self) -> &'static str {
+        "Isogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every letter used must appear the same number of times"
+    }
+}
+
+/// Letter quota constraint - per-letter minimum and maximum counts
+pub struct LetterQuotaConstraint {
+    quotas: BTreeMap<char, LetterQuota>,
+}
+
+impl LetterQuotaConstraint {
+    /// Create a quota constraint; every quota must have its minimum within its maximum
+    pub fn new(quotas: BTreeMap<char, LetterQuota>) -> OulipoResult<Self> {
+        for (letter, quota) in &quotas {
+            if !letter.is_alphabetic() {
+                return Err(OulipoError::InvalidConfig(format!("'{}' is not a letter", letter)));
+            }
+            if let (Some(min), Some(max)) = (quota.min, quota.max) {
+                if min > max {
+                    return Err(OulipoError::InvalidConfig(format!(
+                        "Quota for '{}' has a minimum ({}) above its maximum ({})",
+                        letter, min, max
+                    )));
+                }
+            }
+        }
+        let quotas = quotas.into_iter().flat_map(|(letter, quota)| letter.to_lowercase().map(move |l| (l, quota))).collect();
+        Ok(Self { quotas })
+    }
+
+    /// Create a quota constraint from single-letter string keys, as found in JSON
+    pub fn from_letter_map(quotas: HashMap<String, LetterQuota>) -> OulipoResult<Self> {
+        let quotas = quotas
+            .into_iter()
+            .map(|(key, quota)| {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(letter), None) => Ok((letter, quota)),
+                    _ => Err(OulipoError::InvalidConfig(format!("'{}' is not a single letter", key))),
+                }
+            })
+            .collect::<OulipoResult<BTreeMap<_, _>>>()?;
+        Self::new(quotas)
+    }
+}
+
+impl Constraint for LetterQuotaConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_letter_quotas(text, &self.quotas)
+    }
+
+    fn name(&self) -> &'static str {
+        "Letter Quota"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each listed letter must appear within its minimum and maximum count"
+    }
+}
+
+/// Check that every letter of the English alphabet appears
+pub fn check_pangram(text: &str) -> Result<ConstraintResult> {
+    check_pangram_with_alphabet(text, &ENGLISH_ALPHABET.chars().collect::<Vec<_>>())
+}
+
+/// Check that every letter of the given alphabet appears
+pub fn check_pangram_with_alphabet(text: &str, alphabet: &[char]) -> Result<ConstraintResult> {
+    let quotas: BTreeMap<char, LetterQuota> = alphabet.iter().map(|&c| (c, LetterQuota { min: Some(1), max: None })).collect();
+    let inventory = LetterInventory::of(text);
+    let mut result = evaluate(text, &inventory, &quotas, "pangram");
+    result.suggestions = if result.success {
+        vec!["Perfect pangram!".to_string()]
+    } else {
+        vec![
+            "Rare letters (j, q, x, z) are easiest to place in short words".to_string(),
+            "Reword to keep the text short once every letter is in".to_string(),
+        ]
+    };
+    Ok(result)
+}
+
+/// Check that no letter appears more than once
+pub fn check_heterogram(text: &str) -> Result<ConstraintResult> {
+    let inventory = LetterInventory::of(text);
+    let quotas = inventory.letters().map(|c| (c, LetterQuota { min: None, max: Some(1) })).collect();
+    let mut result = evaluate(text, &inventory, &quotas, "heterogram");
+    result.suggestions = if result.success {
+        vec!["Perfect heterogram!".to_string()]
+    } else {
+        vec![
+            "Replace words that repeat a letter already used".to_string(),
+            "Short words with distinct letters (fjord, nymph) help".to_string(),
+        ]
+    };
+    Ok(result)
+}
+
+/// Check that every letter used appears exactly `n` times
+pub fn check_isogram(text: &str, n: usize) -> Result<ConstraintResult> {
+    let inventory = LetterInventory::of(text);
+    let quotas = inventory.letters().map(|c| (c, LetterQuota::exactly(n))).collect();
+    let mut result = evaluate(text, &inventory, &quotas, "isogram");
+    result.metadata["n"] = serde_json::json!(n);
+    result.suggestions = if result.success {
+        vec![format!("Perfect {}-isogram!", n)]
+    } else {
+        vec![
+            format!("Every letter you use must appear exactly {} times", n),
+            "Balance over-used letters by dropping words, under-used ones by adding them".to_string(),
+        ]
+    };
+    Ok(result)
+}
+
+/// Check per-letter minimum and maximum counts
+pub fn check_letter_quotas(text: &str, quotas: &BTreeMap<char, LetterQuota>) -> Result<ConstraintResult> {
+    let inventory = LetterInventory::of(text);
+    let mut result = evaluate(text, &inventory, quotas, "letter_quota");
+    result.metadata["quotas"] = serde_json::json!(quotas);
+    result.suggestions = if result.success {
+        vec!["Every letter is within its quota".to_string()]
+    } else {
+        vec![
+            "Swap words to trade over-used letters for under-used ones".to_string(),
+            "Check the letter counts in the metadata".to_string(),
+        ]
+    };
+    Ok(result)
+}
+
+/// Where each letter occurs, lowercased, as byte offset and length
+struct LetterInventory(BTreeMap<char, Vec<(usize, usize)>>);
+
+impl LetterInventory {
+    fn of(text: &str) -> Self {
+        let mut occurrences: BTreeMap<char, Vec<(usize, usize)>> = BTreeMap::new();
+        for (pos, ch) in text.char_indices().filter(|(_, c)| c.is_alphabetic()) {
+            for lower in ch.to_lowercase() {
+                occurrences.entry(lower).or_default().push((pos, ch.len_utf8()));
+            }
+        }
+        Self(occurrences)
+    }
+
+    fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.0.keys().copied()
+    }
+
+    fn positions(&self, letter: char) -> &[(usize, usize)] {
+        self.0.get(&letter).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn evaluate(
+    text: &str,
+    inventory: &LetterInventory,
+    quotas: &BTreeMap<char, LetterQuota>,
+    constraint_type: &str,
+) -> ConstraintResult {
+    let mut violations = Vec::new();
+    let mut missing = Vec::new();
+    let mut over_used = Vec::new();
+    let mut under_used = Vec::new();
+
+    for (&letter, quota) in quotas {
+        let positions = inventory.positions(letter);
+        let count = positions.len();
+        if quota.allows(count) {
+            continue;
+        }
+
+        if let Some(max) = quota.max.filter(|&max| count > max) {
+            over_used.push(letter);
+            for &(position, length) in &positions[max..] {
+                violations.push(Violation {
+                    position,
+                    length,
+                    span: None,
+                    issue: format!("Letter '{}' used {} times (allowed {})", letter, count, quota.describe()),
+                    suggestion: Some(format!("Replace this word with one without '{}'", letter)),
+                    fixes: vec![Fix::new(format!("Delete this '{}'", letter), position, length, "", 0.3)],
+                });
+            }
+        } else if let Some(&(position, length)) = positions.last() {
+            under_used.push(letter);
+            violations.push(Violation {
+                position,
+                length,
+                span: None,
+                issue: format!("Letter '{}' used {} times (needs {})", letter, count, quota.describe()),
+                suggestion: Some(format!("Add words containing '{}'", letter)),
+                fixes: Vec::new(),
+            });
+        } else {
+            missing.push(letter);
+            violations.push(Violation {
+                position: text.len(),
+                length: 0,
+                span: None,
+                issue: format!("Letter '{}' is missing", letter),
+                suggestion: Some(format!("Add a word containing '{}'", letter)),
+                fixes: Vec::new(),
+            });
+        }
+    }
+    violations.sort_by_key(|v| v.position);
+
+    let success = violations.is_empty();
+    let violation_count = violations.len();
+    let counts: BTreeMap<char, usize> = inventory.0.iter().map(|(&c, p)| (c, p.len())).collect();
+    let positions: BTreeMap<char, Vec<usize>> = missing
+        .iter()
+        .chain(&over_used)
+        .chain(&under_used)
+        .map(|&c| (c, inventory.positions(c).iter().map(|&(p, _)| p).collect()))
+        .collect();
+    let label = constraint_type.replace('_', " ");
+
+    ConstraintResult {
+        success,
+        result: Some(if success {
+            format!("Valid {}", label)
+        } else {
+            format!("{} has {} violations", match_case("A", &label), violation_count)
+        }),
+        violations,
+        suggestions: Vec::new(),
+        metadata: serde_json::json!({
+            "constraint_type": constraint_type,
+            "letter_counts": counts,
+            "missing_letters": missing,
+            "over_used_letters": over_used,
+            "under_used_letters": under_used,
+            "letter_positions": positions,
+            "violations_count": violation_count
+        }),
+    }
+    .with_score(share(quotas.len() - missing.len() - over_used.len() - under_used.len(), quotas.len()))
+    .located(text)
+}
+
+fn parse_alphabet(alphabet: &str) -> OulipoResult<Vec<char>> {
+    let mut letters: Vec<char> = alphabet.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if letters.is_empty() || letters.iter().any(|c| !c.is_alphabetic()) {
+        return Err(OulipoError::InvalidConfig(format!("'{}' is not a list of letters", alphabet)));
+    }
+    letters.sort_unstable();
+    letters.dedup();
+    Ok(letters)
+}
@@ -1,8 +1,55 @@
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    errors::{OulipoError, OulipoResult},
+    utils::share,
+};
 use anyhow::Result;
 
+/// Lipogram constraint - text must avoid a specific letter
+pub struct LipogramConstraint {
+    forbidden_letter: String,
+}
+
+impl LipogramConstraint {
+    /// Create a new lipogram constraint forbidding the given letter
+    pub fn new(forbidden_letter: &str) -> OulipoResult<Self> {
+        let mut chars = forbidden_letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) if ch.is_alphabetic() => Ok(Self {
+                forbidden_letter: forbidden_letter.to_string(),
+            }),
+            _ => Err(OulipoError::InvalidConfig(
+                format!("'{}' is not a single letter", forbidden_letter)
+            )),
+        }
+    }
+}
+
+impl Constraint for LipogramConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, &self.forbidden_letter)
+    }
+
+    fn name(&self) -> &'static str {
+        "Lipogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text must avoid a specific letter"
+    }
+}
+
 /// Check if text follows lipogram constraint (avoids a specific letter)
+///
+/// The score is the share of letters that are not the forbidden one.
 pub fn check(text: &str, forbidden_letter: &str) -> Result<ConstraintResult> {
+    Ok(evaluate(text, forbidden_letter).located(text))
+}
+
+/// The lipogram check with its violations not yet located
+///
+/// For callers that add to the violations, e.g. fixes, and locate the result once.
+pub fn evaluate(text: &str, forbidden_letter: &str) -> ConstraintResult {
     let forbidden = forbidden_letter.to_lowercase();
     let mut violations = Vec::new();
     
@@ -10,9 +57,11 @@ pub fn check(text: &str, forbidden_letter: &str) -> Result<ConstraintResult> {
         if ch.to_lowercase().to_string() == forbidden {
             violations.push(Violation {
                 position: pos,
-                length: 1,
+                length: ch.len_utf8(),
+                span: None,
                 issue: format!("Forbidden letter '{}' found", forbidden_letter),
                 suggestion: Some("Replace with alternative word".to_string()),
+                fixes: Vec::new(),
             });
         }
     }
@@ -25,8 +74,9 @@ pub fn check(text: &str, forbidden_letter: &str) -> Result<ConstraintResult> {
     };
     
     let violation_count = violations.len();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
     
-    Ok(ConstraintResult {
+    ConstraintResult {
         success,
         result: Some(if success { "Valid lipogram" } else { "Violations found" }.to_string()),
         violations,
@@ -36,7 +86,7 @@ pub fn check(text: &str, forbidden_letter: &str) -> Result<ConstraintResult> {
             "violation_count": violation_count,
             "text_length": text.len()
         }),
-    })
+    }.with_score(share(letters - violation_count.min(letters), letters))
 }
 
 fn generate_suggestions(forbidden_letter: &str) -> Vec<String> {
@@ -0,0 +1,501 @@
+//! Metrical scansion against a foot type and foot count.
+//!
+//! Each line is scanned into syllables using the pronunciation lexicon. Lexical
+//! stress decides polysyllables; monosyllables and secondary stresses are
+//! treated as metrically flexible unless strict scansion is requested, since
+//! their stress depends on context ("the CAT", "IN the house").
+
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    errors::{OulipoError, OulipoResult},
+    phonetics::{PronunciationDictionary, Stress},
+    utils::{line_spans, share, word_spans},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Metrical foot, as a pattern of unstressed and stressed positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Foot {
+    /// x /
+    Iamb,
+    /// / x
+    Trochee,
+    /// x x /
+    Anapest,
+    /// / x x
+    Dactyl,
+    /// / /
+    Spondee,
+    /// x / x
+    Amphibrach,
+}
+
+impl Foot {
+    /// Stress pattern of the foot, `true` for a stressed position
+    pub fn pattern(self) -> &'static [bool] {
+        match self {
+            Foot::Iamb => &[false, true],
+            Foot::Trochee => &[true, false],
+            Foot::Anapest => &[false, false, true],
+            Foot::Dactyl => &[true, false, false],
+            Foot::Spondee => &[true, true],
+            Foot::Amphibrach => &[false, true, false],
+        }
+    }
+
+    /// Adjective used when naming a meter ("iambic")
+    pub fn adjective(self) -> &'static str {
+        match self {
+            Foot::Iamb => "iambic",
+            Foot::Trochee => "trochaic",
+            Foot::Anapest => "anapestic",
+            Foot::Dactyl => "dactylic",
+            Foot::Spondee => "spondaic",
+            Foot::Amphibrach => "amphibrachic",
+        }
+    }
+
+    /// Parse a foot from its noun or adjective ("iamb", "iambic")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "iamb" | "iambic" | "iambus" => Some(Foot::Iamb),
+            "trochee" | "trochaic" => Some(Foot::Trochee),
+            "anapest" | "anapaest" | "anapestic" | "anapaestic" => Some(Foot::Anapest),
+            "dactyl" | "dactylic" => Some(Foot::Dactyl),
+            "spondee" | "spondaic" => Some(Foot::Spondee),
+            "amphibrach" | "amphibrachic" => Some(Foot::Amphibrach),
+            _ => None,
+        }
+    }
+}
+
+/// Names for lines of one to eight feet
+const LINE_LENGTHS: [&str; 8] = [
+    "monometer", "dimeter", "trimeter", "tetrameter", "pentameter", "hexameter", "heptameter", "octameter",
+];
+
+/// A meter: a foot type repeated a number of times per line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meter {
+    pub foot: Foot,
+    pub feet: usize,
+}
+
+impl Meter {
+    /// Create a meter, rejecting lines without feet
+    pub fn new(foot: Foot, feet: usize) -> OulipoResult<Self> {
+        if feet == 0 {
+            return Err(OulipoError::InvalidConfig("A meter needs at least one foot per line".to_string()));
+        }
+        Ok(Self { foot, feet })
+    }
+
+    /// Iambic pentameter, the default meter
+    pub fn iambic_pentameter() -> Self {
+        Self { foot: Foot::Iamb, feet: 5 }
+    }
+
+    /// Parse a meter name such as "iambic pentameter" or "trochaic tetrameter"
+    pub fn parse(name: &str) -> OulipoResult<Self> {
+        let words: Vec<&str> = name.split_whitespace().collect();
+        let (foot, length) = match words.as_slice() {
+            [foot, length] => (foot, length),
+            _ => return Err(OulipoError::InvalidConfig(format!("Unrecognised meter: '{}'", name))),
+        };
+
+        let foot = Foot::parse(foot)
+            .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown foot: '{}'", foot)))?;
+        let length = length.to_lowercase();
+        let feet = LINE_LENGTHS
+            .iter()
+            .position(|&l| l == length)
+            .map(|idx| idx + 1)
+            .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown line length: '{}'", length)))?;
+
+        Self::new(foot, feet)
+    }
+
+    /// Stress pattern of a regular line in this meter
+    pub fn pattern(&self) -> Vec<bool> {
+        self.foot.pattern().repeat(self.feet)
+    }
+}
+
+impl fmt::Display for Meter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.feet.checked_sub(1).and_then(|idx| LINE_LENGTHS.get(idx)) {
+            Some(length) => write!(f, "{} {}", self.foot.adjective(), length),
+            None => write!(f, "{} ({} feet)", self.foot.adjective(), self.feet),
+        }
+    }
+}
+
+/// Accepted variations on the regular pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Substitution {
+    /// An extra unstressed syllable after the final stress
+    FeminineEnding,
+    /// The first foot reversed: a trochee opening an iambic line or vice versa
+    InitialInversion,
+    /// The opening unstressed syllable dropped (headless or acephalous line)
+    Headless,
+    /// The final unstressed syllable dropped (catalectic line)
+    Catalexis,
+}
+
+impl Substitution {
+    /// Apply the substitution to a stress pattern, if it fits the pattern
+    fn apply(self, pattern: &[bool], foot: Foot) -> Option<Vec<bool>> {
+        match self {
+            Substitution::FeminineEnding => {
+                let mut varied = pattern.to_vec();
+                varied.push(false);
+                Some(varied)
+            }
+            Substitution::InitialInversion => match foot {
+                Foot::Iamb | Foot::Trochee if pattern.len() >= 2 => {
+                    let mut varied = pattern.to_vec();
+                    varied.swap(0, 1);
+                    Some(varied)
+                }
+                _ => None,
+            },
+            Substitution::Headless => match pattern.first() {
+                Some(false) if pattern.len() > 1 => Some(pattern[1..].to_vec()),
+                _ => None,
+            },
+            Substitution::Catalexis => match pattern.last() {
+                Some(false) if pattern.len() > 1 => Some(pattern[..pattern.len() - 1].to_vec()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// How a scanned syllable may be placed in the meter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyllableWeight {
+    Stressed,
+    Unstressed,
+    /// Monosyllables, secondary stresses and unknown words fit either position
+    Flexible,
+}
+
+impl SyllableWeight {
+    fn fits(self, stressed: bool) -> bool {
+        match self {
+            SyllableWeight::Stressed => stressed,
+            SyllableWeight::Unstressed => !stressed,
+            SyllableWeight::Flexible => true,
+        }
+    }
+
+    fn mark(self) -> char {
+        match self {
+            SyllableWeight::Stressed => '/',
+            SyllableWeight::Unstressed => 'x',
+            SyllableWeight::Flexible => '~',
+        }
+    }
+}
+
+/// A syllable and the word it belongs to
+struct ScannedSyllable<'a> {
+    word: &'a str,
+    /// Byte offset of the word in the checked text
+    position: usize,
+    index_in_word: usize,
+    word_syllables: usize,
+    weight: SyllableWeight,
+}
+
+/// Metrical constraint - every line must scan in the given meter
+pub struct MeterConstraint {
+    meter: Meter,
+    substitutions: Vec<Substitution>,
+    strict_monosyllables: bool,
+    pronunciations: Arc<PronunciationDictionary>,
+}
+
+impl MeterConstraint {
+    /// Create a meter constraint without any allowed substitutions
+    pub fn new(meter: Meter) -> Self {
+        Self {
+            meter,
+            substitutions: Vec::new(),
+            strict_monosyllables: false,
+            pronunciations: PronunciationDictionary::shared(),
+        }
+    }
+
+    /// Allow the given substitutions (feminine ending, initial inversion, ...)
+    pub fn with_substitutions(mut self, substitutions: Vec<Substitution>) -> Self {
+        self.substitutions = substitutions;
+        self
+    }
+
+    /// Take monosyllable stress from the lexicon instead of treating it as flexible
+    pub fn with_strict_monosyllables(mut self, strict: bool) -> Self {
+        self.strict_monosyllables = strict;
+        self
+    }
+
+    /// Use a specific pronunciation dictionary for scansion
+    pub fn with_pronunciations(mut self, pronunciations: Arc<PronunciationDictionary>) -> Self {
+        self.pronunciations = pronunciations;
+        self
+    }
+}
+
+impl Default for MeterConstraint {
+    fn default() -> Self {
+        Self::new(Meter::iambic_pentameter())
+    }
+}
+
+impl Constraint for MeterConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_scansion(text, &self.meter, &self.substitutions, self.strict_monosyllables, &self.pronunciations)
+    }
+
+    fn name(&self) -> &'static str {
+        "Meter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every line must scan in the given meter, e.g. iambic pentameter"
+    }
+}
+
+/// Check that every line scans in the given meter
+///
+/// The score averages the lines: the share of syllables that fit, or nothing
+/// for a line of the wrong length.
+pub fn check(
+    text: &str,
+    meter: &Meter,
+    substitutions: &[Substitution],
+    pronunciations: &PronunciationDictionary,
+) -> Result<ConstraintResult> {
+    check_scansion(text, meter, substitutions, false, pronunciations)
+}
+
+fn check_scansion(
+    text: &str,
+    meter: &Meter,
+    substitutions: &[Substitution],
+    strict_monosyllables: bool,
+    pronunciations: &PronunciationDictionary,
+) -> Result<ConstraintResult> {
+    let variants = pattern_variants(meter, substitutions);
+    let mut violations = Vec::new();
+    let mut line_reports = Vec::new();
+    let mut line_scores = Vec::new();
+
+    for (line_idx, (offset, line)) in line_spans(text).into_iter().enumerate() {
+        let syllables = scan_line(line, offset, strict_monosyllables, pronunciations);
+        let scansion: String = syllables.iter().map(|s| s.weight.mark()).collect();
+
+        // Prefer the variant with fewest mismatches, then the fewest substitutions
+        let best = variants
+            .iter()
+            .filter(|(pattern, _)| pattern.len() == syllables.len())
+            .map(|(pattern, used)| {
+                let mismatches: Vec<usize> = syllables
+                    .iter()
+                    .zip(pattern)
+                    .enumerate()
+                    .filter(|(_, (syllable, &stressed))| !syllable.weight.fits(stressed))
+                    .map(|(idx, _)| idx)
+                    .collect();
+                (pattern, used, mismatches)
+            })
+            .min_by_key(|(_, used, mismatches)| (mismatches.len(), used.len()));
+
+        match best {
+            Some((pattern, used, mismatches)) => {
+                line_scores.push(share(syllables.len() - mismatches.len(), syllables.len()));
+                for idx in &mismatches {
+                    let syllable = &syllables[*idx];
+                    let (found, expected) = if pattern[*idx] {
+                        ("unstressed", "stressed")
+                    } else {
+                        ("stressed", "unstressed")
+                    };
+                    violations.push(Violation {
+                        position: syllable.position,
+                        length: syllable.word.len(),
+                        span: None,
+                        issue: format!(
+                            "Line {}, syllable {}: {} is {} where the meter expects a {} syllable",
+                            line_idx + 1,
+                            idx + 1,
+                            describe_syllable(syllable),
+                            found,
+                            expected
+                        ),
+                        suggestion: Some(format!("Replace '{}' with a word stressed to fit the {}", syllable.word, meter)),
+                        fixes: Vec::new(),
+                    });
+                }
+
+                line_reports.push(serde_json::json!({
+                    "line": line_idx + 1,
+                    "syllables": syllables.len(),
+                    "scansion": scansion,
+                    "expected": render_pattern(pattern),
+                    "substitutions": used,
+                    "mismatches": mismatches.iter().map(|idx| idx + 1).collect::<Vec<_>>()
+                }));
+            }
+            None => {
+                line_scores.push(0.0);
+                let mut allowed: Vec<usize> = variants.iter().map(|(p, _)| p.len()).collect();
+                allowed.sort_unstable();
+                allowed.dedup();
+                let allowed = allowed.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" or ");
+                violations.push(Violation {
+                    position: offset,
+                    length: line.len(),
+                    span: None,
+                    issue: format!(
+                        "Line {} has {} syllables; {} needs {}",
+                        line_idx + 1,
+                        syllables.len(),
+                        meter,
+                        allowed
+                    ),
+                    suggestion: Some(if syllables.len() > meter.pattern().len() {
+                        "Cut syllables or contract words".to_string()
+                    } else {
+                        "Add syllables or expand contractions".to_string()
+                    }),
+                    fixes: Vec::new(),
+                });
+
+                line_reports.push(serde_json::json!({
+                    "line": line_idx + 1,
+                    "syllables": syllables.len(),
+                    "scansion": scansion,
+                    "expected": render_pattern(&meter.pattern()),
+                    "substitutions": [],
+                    "mismatches": []
+                }));
+            }
+        }
+    }
+
+    let success = violations.is_empty();
+    let violation_count = violations.len();
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success {
+            format!("Scans as {}", meter)
+        } else {
+            format!("{} metrical violations", violation_count)
+        }),
+        violations,
+        suggestions: if success {
+            vec!["Every line scans!".to_string()]
+        } else {
+            vec![
+                "Read the line aloud and mark where your voice rises".to_string(),
+                "Swap words for synonyms with a different stress pattern".to_string(),
+                "Reorder phrases so stressed syllables fall on the beat".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "meter",
+            "meter": meter.to_string(),
+            "foot": meter.foot,
+            "feet": meter.feet,
+            "allowed_substitutions": substitutions,
+            "notation": {"stressed": "/", "unstressed": "x", "flexible": "~"},
+            "lines": line_reports
+        }),
+    }
+    .with_score(if line_scores.is_empty() { 1.0 } else { line_scores.iter().sum::<f64>() / line_scores.len() as f64 })
+    .located(text))
+}
+
+/// The regular pattern plus every combination of applicable substitutions
+fn pattern_variants(meter: &Meter, substitutions: &[Substitution]) -> Vec<(Vec<bool>, Vec<Substitution>)> {
+    let mut variants = vec![(meter.pattern(), Vec::new())];
+
+    for &substitution in substitutions {
+        let extended: Vec<_> = variants
+            .iter()
+            .filter_map(|(pattern, used)| {
+                substitution.apply(pattern, meter.foot).map(|varied| {
+                    let mut used = used.clone();
+                    used.push(substitution);
+                    (varied, used)
+                })
+            })
+            .collect();
+        variants.extend(extended);
+    }
+
+    variants
+}
+
+/// Break a line into syllables with their metrical weight
+fn scan_line<'a>(
+    line: &'a str,
+    offset: usize,
+    strict_monosyllables: bool,
+    pronunciations: &PronunciationDictionary,
+) -> Vec<ScannedSyllable<'a>> {
+    let mut syllables = Vec::new();
+
+    for (position, word) in word_spans(line) {
+        let weights: Vec<SyllableWeight> = match pronunciations.lookup(word) {
+            Some(pronunciation) => {
+                let stresses = pronunciation.stresses();
+                if stresses.len() == 1 && !strict_monosyllables {
+                    vec![SyllableWeight::Flexible]
+                } else {
+                    stresses
+                        .into_iter()
+                        .map(|stress| match stress {
+                            Stress::Primary => SyllableWeight::Stressed,
+                            Stress::Unstressed => SyllableWeight::Unstressed,
+                            Stress::Secondary => SyllableWeight::Flexible,
+                        })
+                        .collect()
+                }
+            }
+            // Without a pronunciation only the syllable count can be estimated
+            None => vec![SyllableWeight::Flexible; pronunciations.syllable_count(word)],
+        };
+
+        let word_syllables = weights.len();
+        syllables.extend(weights.into_iter().enumerate().map(|(index_in_word, weight)| ScannedSyllable {
+            word,
+            position: offset + position,
+            index_in_word,
+            word_syllables,
+            weight,
+        }));
+    }
+
+    syllables
+}
+
+fn describe_syllable(syllable: &ScannedSyllable) -> String {
+    if syllable.word_syllables == 1 {
+        format!("'{}'", syllable.word)
+    } else {
+        format!("syllable {} of '{}'", syllable.index_in_word + 1, syllable.word)
+    }
+}
+
+fn render_pattern(pattern: &[bool]) -> String {
+    pattern.iter().map(|&stressed| if stressed { '/' } else { 'x' }).collect()
+}
@@ -10,12 +10,44 @@ pub mod n_plus_7;
 pub mod palindrome;
 pub mod snowball;
 pub mod sestina;
+pub mod nina;
+pub mod villanelle;
+pub mod pantoum;
+pub mod terza_rima;
+pub mod rondeau;
+pub mod ghazal;
+pub mod haiku;
+pub mod meter;
+pub mod rhyme_scheme;
+pub mod letter_inventory;
+pub mod tautogram;
+pub mod name_letters;
+
+// Shared checks for fixed forms
+pub mod fixed_form;
 
 // Extended experimental constraints
 pub mod prisoners;
 pub mod univocalic;
 
 // Re-export constraint structs for trait-based usage
+pub use lipogram::LipogramConstraint;
+pub use palindrome::{PalindromeConstraint, PalindromeMode};
+pub use snowball::{SnowballConstraint, SnowballVariant};
+pub use sestina::SestinaConstraint;
+pub use nina::NinaConstraint;
+pub use villanelle::VillanelleConstraint;
+pub use pantoum::PantoumConstraint;
+pub use terza_rima::TerzaRimaConstraint;
+pub use rondeau::RondeauConstraint;
+pub use ghazal::GhazalConstraint;
+pub use haiku::HaikuConstraint;
+pub use meter::MeterConstraint;
+pub use rhyme_scheme::RhymeSchemeConstraint;
+pub use letter_inventory::{HeterogramConstraint, IsogramConstraint, LetterQuotaConstraint, PangramConstraint};
+pub use tautogram::{AlliterationConstraint, InitialMatch, TautogramConstraint};
+pub use name_letters::{BeauPresentConstraint, BelleAbsenteConstraint, BelleAbsenteUnit};
+pub use prisoners::PrisonersConstraint;
 pub use univocalic::UnivocalicConstraint;
 
 // Re-export common constraint functions for backward compatibility
@@ -25,4 +57,6 @@ pub use snowball::check as check_snowball;
 pub use prisoners::check as check_prisoners;
 pub use univocalic::check as check_univocalic;
 pub use sestina::check as check_sestina;
+pub use letter_inventory::{check_heterogram, check_pangram};
+pub use tautogram::{check_alliteration, check_tautogram};
 pub use n_plus_7::transform as n_plus_7_transform;
@@ -1,24 +1,89 @@
-use crate::oulipo::{ConstraintResult, dictionary::Dictionary};
+use crate::oulipo::{
+    ConstraintResult,
+    dictionary::Dictionary,
+    utils::{match_case, pluralize, singular_candidates},
+};
 use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Words after which a lexicon noun is more likely being used as a verb ("to walk", "they dance")
+const VERB_CONTEXT: &[&str] = &[
+    "to", "i", "you", "we", "they", "he", "she", "will", "would", "can", "could",
+    "shall", "should", "may", "might", "must", "do", "does", "did", "don't", "doesn't",
+    "didn't", "won't", "can't", "not", "never",
+];
+
+fn word_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\p{L}+(?:['’]\p{L}+)*").expect("valid word pattern"))
+}
+
+/// A single noun replacement made by the transform
+struct Substitution {
+    position: usize,
+    original: String,
+    replacement: String,
+    lemma: String,
+    target: String,
+    plural: bool,
+}
 
 /// Transform text using N+7 method (replace each noun with the 7th noun following it)
+///
+/// Nouns are found by looking each word (or its singular form) up in the
+/// dictionary's alphabetical noun lexicon and skipping words whose context marks
+/// them as verbs. Punctuation, spacing, capitalisation and plurals are preserved.
 pub fn transform(text: &str, offset: i32, dictionary: &Dictionary) -> Result<ConstraintResult> {
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let word_count = words.len();
-    let mut transformed_words = Vec::new();
-    let mut replacements_made = 0;
-    
-    for word in words {
-        if let Some(replacement) = dictionary.get_n_plus_word(word, offset) {
-            transformed_words.push(replacement);
-            replacements_made += 1;
+    let mut result_text = String::with_capacity(text.len());
+    let mut substitutions = Vec::new();
+    let mut word_count = 0;
+    let mut previous_word: Option<String> = None;
+    let mut last_end = 0;
+
+    for word_match in word_pattern().find_iter(text) {
+        word_count += 1;
+        result_text.push_str(&text[last_end..word_match.start()]);
+        last_end = word_match.end();
+
+        let token = word_match.as_str();
+        // Keep possessive and contraction suffixes ("dog's") attached to the replacement
+        let (core, suffix) = match token.find(['\'', '’']) {
+            Some(idx) => token.split_at(idx),
+            None => (token, ""),
+        };
+
+        let in_verb_context = previous_word
+            .as_deref()
+            .is_some_and(|prev| VERB_CONTEXT.contains(&prev));
+        previous_word = Some(token.to_lowercase());
+
+        let substitution = if in_verb_context {
+            None
         } else {
-            transformed_words.push(word.to_string());
+            substitute_noun(core, offset, dictionary)
+        };
+
+        match substitution {
+            Some((replacement, lemma, target, plural)) => {
+                result_text.push_str(&replacement);
+                result_text.push_str(suffix);
+                substitutions.push(Substitution {
+                    position: word_match.start(),
+                    original: core.to_string(),
+                    replacement,
+                    lemma,
+                    target,
+                    plural,
+                });
+            }
+            None => result_text.push_str(token),
         }
     }
-    
-    let result_text = transformed_words.join(" ");
-    
+    result_text.push_str(&text[last_end..]);
+
+    let replacements_made = substitutions.len();
+
     Ok(ConstraintResult {
         success: true,
         result: Some(result_text),
@@ -31,7 +96,30 @@ pub fn transform(text: &str, offset: i32, dictionary: &Dictionary) -> Result<Con
             "offset": offset,
             "original_words": word_count,
             "replacements_made": replacements_made,
-            "replacement_rate": replacements_made as f64 / word_count as f64
+            "replacement_rate": if word_count == 0 { 0.0 } else { replacements_made as f64 / word_count as f64 },
+            "lexicon_size": dictionary.nouns().len(),
+            "substitutions": substitutions.iter().map(|s| serde_json::json!({
+                "position": s.position,
+                "original": s.original,
+                "replacement": s.replacement,
+                "lemma": s.lemma,
+                "target": s.target,
+                "plural": s.plural
+            })).collect::<Vec<_>>()
         }),
-    })
+    }.located(text))
+}
+
+/// Replace a single word if it is a noun, returning (replacement, lemma, target lemma, plural)
+fn substitute_noun(word: &str, offset: i32, dictionary: &Dictionary) -> Option<(String, String, String, bool)> {
+    let lower = word.to_lowercase();
+    let lemma = singular_candidates(word)
+        .into_iter()
+        .find(|candidate| dictionary.is_noun(candidate))?;
+    let plural = lemma != lower;
+
+    let target = dictionary.get_n_plus_word(&lemma, offset)?;
+    let inflected = if plural { pluralize(&target) } else { target.clone() };
+
+    Some((match_case(word, &inflected), lemma, target, plural))
 }
@@ -0,0 +1,314 @@
+//! Name-based constraints: beau présent and belle absente.
+//!
+//! Both are written for someone: a beau présent uses only the letters of
+//! their name, while a belle absente hides the name in its gaps. Each line
+//! (or stanza) of a belle absente uses every letter of the alphabet except
+//! one, and the missing letters, read in order, spell the name.
+
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::letter_inventory::ENGLISH_ALPHABET,
+    errors::{OulipoError, OulipoResult},
+    utils::{line_spans, share, stanza_spans},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The unit of text that hides one letter of a belle absente's name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BelleAbsenteUnit {
+    #[default]
+    Line,
+    /// Blank-line separated stanzas, as in Perec's originals
+    Stanza,
+}
+
+impl BelleAbsenteUnit {
+    /// Parse a unit name as used in constraint configs
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "line" | "lines" => Some(BelleAbsenteUnit::Line),
+            "stanza" | "stanzas" => Some(BelleAbsenteUnit::Stanza),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            BelleAbsenteUnit::Line => "Line",
+            BelleAbsenteUnit::Stanza => "Stanza",
+        }
+    }
+}
+
+/// Beau présent constraint - only the letters of a name may be used
+pub struct BeauPresentConstraint {
+    name: String,
+}
+
+impl BeauPresentConstraint {
+    /// Create a beau présent for a name, which must contain at least one letter
+    pub fn new(name: &str) -> OulipoResult<Self> {
+        if name_letters(name).is_empty() {
+            return Err(OulipoError::InvalidConfig(format!("'{}' has no letters to write with", name)));
+        }
+        Ok(Self { name: name.to_string() })
+    }
+}
+
+impl Constraint for BeauPresentConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_beau_present(text, &self.name)
+    }
+
+    fn name(&self) -> &'static str {
+        "Beau Présent"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text may only use the letters of a given name"
+    }
+}
+
+/// Belle absente constraint - each unit omits one letter, and the omissions spell a name
+pub struct BelleAbsenteConstraint {
+    name: String,
+    unit: BelleAbsenteUnit,
+}
+
+impl BelleAbsenteConstraint {
+    /// Create a line-by-line belle absente; every letter of the name must be in the alphabet
+    pub fn new(name: &str) -> OulipoResult<Self> {
+        let letters = name_letters(name);
+        if letters.is_empty() {
+            return Err(OulipoError::InvalidConfig(format!("'{}' has no letters to hide", name)));
+        }
+        if let Some(letter) = letters.iter().find(|c| !ENGLISH_ALPHABET.contains(**c)) {
+            return Err(OulipoError::InvalidConfig(format!(
+                "'{}' is not in the alphabet, so it cannot be left out",
+                letter
+            )));
+        }
+        Ok(Self { name: name.to_string(), unit: BelleAbsenteUnit::Line })
+    }
+
+    /// Hide one letter per stanza instead of per line
+    pub fn with_unit(mut self, unit: BelleAbsenteUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+}
+
+impl Constraint for BelleAbsenteConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_belle_absente(text, &self.name, self.unit)
+    }
+
+    fn name(&self) -> &'static str {
+        "Belle Absente"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each line omits one letter of the alphabet, and the omissions spell a name"
+    }
+}
+
+/// The lowercased letters of a name, in order
+fn name_letters(name: &str) -> Vec<char> {
+    name.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase).collect()
+}
+
+fn lower(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
+
+/// Check that the text uses only the letters of `name`
+///
+/// Case, punctuation and spacing are free; every other letter is reported
+/// where it occurs. The score is the share of letters taken from the name.
+pub fn check_beau_present(text: &str, name: &str) -> Result<ConstraintResult> {
+    let allowed: BTreeSet<char> = name_letters(name).into_iter().collect();
+    let mut disallowed = BTreeSet::new();
+    let mut violations = Vec::new();
+
+    for (position, ch) in text.char_indices().filter(|(_, c)| c.is_alphabetic()) {
+        let letter = lower(ch);
+        if !allowed.contains(&letter) {
+            disallowed.insert(letter);
+            violations.push(Violation {
+                position,
+                length: ch.len_utf8(),
+                span: None,
+                issue: format!("'{}' is not a letter of {}", ch, name.trim()),
+                suggestion: Some(format!(
+                    "Use only {}",
+                    allowed.iter().map(|c| format!("'{}'", c)).collect::<Vec<_>>().join(", ")
+                )),
+                fixes: Vec::new(),
+            });
+        }
+    }
+
+    let success = violations.is_empty();
+    let suggestions = if success {
+        vec![format!("A perfect beau présent for {}", name.trim())]
+    } else {
+        vec![
+            "List the words that can be spelled from the name before writing".to_string(),
+            "Short function words are often the first to break the constraint".to_string(),
+        ]
+    };
+    let violation_count = violations.len();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success {
+            "Valid beau présent".to_string()
+        } else {
+            format!("{} disallowed letters found", violation_count)
+        }),
+        violations,
+        suggestions,
+        metadata: serde_json::json!({
+            "constraint_type": "beau_present",
+            "name": name,
+            "allowed_letters": allowed.iter().map(char::to_string).collect::<Vec<_>>(),
+            "disallowed_letters": disallowed.iter().map(char::to_string).collect::<Vec<_>>(),
+            "violation_count": violation_count
+        }),
+    }.with_score(share(letters - violation_count, letters)).located(text))
+}
+
+/// Check that each unit omits exactly the next letter of `name` from the alphabet
+///
+/// Unit `i` must use every letter of the alphabet except the `i`th letter of
+/// the name. Uses of the hidden letter are reported where they occur; other
+/// missing letters, and units beyond the name's length, are reported on the
+/// whole unit. The score is the share of units, out of the name's length or
+/// the text's if longer, that hide exactly the right letter.
+pub fn check_belle_absente(text: &str, name: &str, unit: BelleAbsenteUnit) -> Result<ConstraintResult> {
+    let hidden = name_letters(name);
+    let units: Vec<(usize, &str)> = match unit {
+        BelleAbsenteUnit::Line => line_spans(text),
+        BelleAbsenteUnit::Stanza => stanza_spans(text)
+            .into_iter()
+            .filter_map(|lines| {
+                let &(start, _) = lines.first()?;
+                let &(last, content) = lines.last()?;
+                Some((start, &text[start..last + content.len()]))
+            })
+            .collect(),
+    };
+    let label = unit.label();
+
+    let mut violations = Vec::new();
+    let mut spelled = String::new();
+    let mut hiding = 0;
+
+    for (i, &(offset, content)) in units.iter().enumerate() {
+        let used: BTreeSet<char> = content.chars().filter(|c| c.is_alphabetic()).map(lower).collect();
+        let missing: Vec<char> = ENGLISH_ALPHABET.chars().filter(|c| !used.contains(c)).collect();
+        spelled.push(match missing.as_slice() {
+            [only] => *only,
+            _ => '?',
+        });
+
+        let Some(&letter) = hidden.get(i) else {
+            violations.push(Violation {
+                position: offset,
+                length: content.len(),
+                span: None,
+                issue: format!("{} {} comes after the hidden name '{}' is complete", label, i + 1, name.trim()),
+                suggestion: Some(format!("End the poem after {} {}s", hidden.len(), label.to_lowercase())),
+                fixes: Vec::new(),
+            });
+            continue;
+        };
+
+        for (position, ch) in content.char_indices().filter(|&(_, c)| lower(c) == letter) {
+            violations.push(Violation {
+                position: offset + position,
+                length: ch.len_utf8(),
+                span: None,
+                issue: format!(
+                    "{} {} should leave out '{}' (letter {} of the hidden name), but uses it",
+                    label, i + 1, letter, i + 1
+                ),
+                suggestion: Some(format!("Replace words containing '{}'", letter)),
+                fixes: Vec::new(),
+            });
+        }
+
+        if missing == [letter] {
+            hiding += 1;
+        }
+
+        let also_missing: Vec<String> = missing.iter().filter(|&&c| c != letter).map(|c| format!("'{}'", c)).collect();
+        if !also_missing.is_empty() {
+            violations.push(Violation {
+                position: offset,
+                length: content.len(),
+                span: None,
+                issue: format!(
+                    "{} {} breaks the hidden-name sequence: it should lack only '{}' but also lacks {}",
+                    label, i + 1, letter, also_missing.join(", ")
+                ),
+                suggestion: Some(format!("Work {} into the {}", also_missing.join(", "), label.to_lowercase())),
+                fixes: Vec::new(),
+            });
+        }
+    }
+
+    if units.len() < hidden.len() {
+        violations.push(Violation {
+            position: text.len(),
+            length: 0,
+            span: None,
+            issue: format!(
+                "The hidden name '{}' needs {} {}s, but the text has {}",
+                name.trim(), hidden.len(), label.to_lowercase(), units.len()
+            ),
+            suggestion: Some(format!(
+                "Add {}s leaving out {}",
+                label.to_lowercase(),
+                hidden[units.len()..].iter().map(|c| format!("'{}'", c)).collect::<Vec<_>>().join(", ")
+            )),
+            fixes: Vec::new(),
+        });
+    }
+    violations.sort_by_key(|v| v.position);
+
+    let success = violations.is_empty();
+    let suggestions = if success {
+        vec![format!("The missing letters spell '{}'", name.trim())]
+    } else {
+        vec![
+            "Pangrammatic words (quiz, jinx, vex) carry the rare letters cheaply".to_string(),
+            format!("Check each {} against the full alphabet before moving on", label.to_lowercase()),
+        ]
+    };
+    let violation_count = violations.len();
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success {
+            "Valid belle absente".to_string()
+        } else {
+            format!("{} violations found", violation_count)
+        }),
+        violations,
+        suggestions,
+        metadata: serde_json::json!({
+            "constraint_type": "belle_absente",
+            "name": name,
+            "unit": unit,
+            "hidden_letters": hidden.iter().map(char::to_string).collect::<Vec<_>>(),
+            "spelled": spelled,
+            "unit_count": units.len(),
+            "violation_count": violation_count
+        }),
+    }.with_score(share(hiding, hidden.len().max(units.len()))).located(text))
+}
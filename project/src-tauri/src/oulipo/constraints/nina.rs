@@ -0,0 +1,338 @@
+//! Queneau's n-ina (quenine): the sestina generalised to n end words.
+//!
+//! Each stanza's end words are the previous stanza's, reordered by the spiral
+//! permutation: last, first, second-to-last, second, ... For the sestina this
+//! gives 6 1 5 2 4 3. The form only works when n applications of the
+//! permutation are needed to return to the start, which holds for n = 1, 2, 3,
+//! 5, 6, 9, 11, 14, 18, ...
+
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Fix, Constraint},
+    errors::{OulipoError, OulipoResult},
+    constraints::fixed_form::{stanza_layout_violations, stanza_sizes},
+    rhyme::{end_word, normalize_word},
+    utils::{is_plural_of, line_spans, match_case, share, stanza_spans, word_spans},
+};
+use anyhow::Result;
+
+/// N-ina constraint - n end words rotating through n stanzas by the spiral permutation
+pub struct NinaConstraint {
+    end_words: Vec<String>,
+    require_envoi: bool,
+}
+
+impl NinaConstraint {
+    /// Create an n-ina constraint; the number of end words must be a valid n
+    pub fn new(end_words: Vec<String>) -> OulipoResult<Self> {
+        validate_end_words(&end_words)?;
+        Ok(Self { end_words, require_envoi: true })
+    }
+
+    /// Whether the closing envoi is required (it is by default)
+    pub fn with_envoi(mut self, require_envoi: bool) -> Self {
+        self.require_envoi = require_envoi;
+        self
+    }
+}
+
+impl Constraint for NinaConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, &self.end_words, self.require_envoi)
+    }
+
+    fn name(&self) -> &'static str {
+        "N-ina"
+    }
+
+    fn description(&self) -> &'static str {
+        "n end words rotating through n stanzas by the spiral permutation, closed by an envoi"
+    }
+}
+
+/// The spiral permutation: position i of the next stanza takes the word at
+/// `permutation[i]` of the current one
+pub fn spiral_permutation(n: usize) -> Vec<usize> {
+    let mut permutation = Vec::with_capacity(n);
+    let (mut low, mut high) = (0, n);
+    while low < high {
+        high -= 1;
+        permutation.push(high);
+        if low < high {
+            permutation.push(low);
+            low += 1;
+        }
+    }
+    permutation
+}
+
+/// Whether an n-ina exists for n: the spiral permutation must be a single n-cycle
+pub fn is_valid_n(n: usize) -> bool {
+    if n == 0 {
+        return false;
+    }
+    let permutation = spiral_permutation(n);
+    let mut position = 0;
+    for step in 1..=n {
+        position = permutation[position];
+        if position == 0 {
+            return step == n;
+        }
+    }
+    false
+}
+
+/// End word indexes for every stanza, starting from the identity order
+pub fn stanza_orders(n: usize) -> Vec<Vec<usize>> {
+    let permutation = spiral_permutation(n);
+    let mut orders: Vec<Vec<usize>> = vec![(0..n).collect()];
+    while orders.len() < n {
+        let previous = &orders[orders.len() - 1];
+        let next = permutation.iter().map(|&i| previous[i]).collect();
+        orders.push(next);
+    }
+    orders
+}
+
+/// Number of lines in the envoi: two end words per line
+pub fn envoi_length(n: usize) -> usize {
+    n.div_ceil(2)
+}
+
+/// Check that the end words give a valid n-ina
+pub fn validate_end_words(end_words: &[String]) -> OulipoResult<()> {
+    let n = end_words.len();
+    if !is_valid_n(n) {
+        let nearby: Vec<String> = (1..=n + 10).filter(|&k| is_valid_n(k)).map(|k| k.to_string()).collect();
+        return Err(OulipoError::InvalidConfig(format!(
+            "No n-ina exists for {} end words; valid counts up to {} are {}",
+            n, n + 10, nearby.join(", ")
+        )));
+    }
+
+    let mut seen: Vec<String> = Vec::with_capacity(n);
+    for word in end_words {
+        let normalized = normalize_word(word);
+        if normalized.is_empty() || seen.contains(&normalized) {
+            return Err(OulipoError::InvalidConfig(format!("End words must be distinct and non-empty, got '{}'", word)));
+        }
+        seen.push(normalized);
+    }
+    Ok(())
+}
+
+/// Check if text is an n-ina on the given end words
+///
+/// The n stanzas of n lines must end on the spiral rotation of the end words.
+/// The envoi, when present or required, has ceil(n/2) lines that each end on
+/// an end word and together use all n of them. A wrong end word comes with a
+/// fix that swaps in the expected one. The score is the share of the n * n
+/// stanza lines that end on the right word.
+pub fn check(text: &str, end_words: &[String], require_envoi: bool) -> Result<ConstraintResult> {
+    let n = end_words.len();
+    let form = if n == 6 { "sestina".to_string() } else { format!("{}-ina", n) };
+    let constraint_type = if n == 6 { "sestina" } else { "nina" };
+    validate_end_words(end_words)?;
+
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+    let body_lines = n * n;
+    let envoi_lines = envoi_length(n);
+    let expected_total = body_lines + if require_envoi { envoi_lines } else { 0 };
+    let normalized: Vec<String> = end_words.iter().map(|w| normalize_word(w)).collect();
+    let orders = stanza_orders(n);
+    let expected_ends = orders.iter().flatten().map(|&word_idx| normalized[word_idx].as_str());
+    let right_ends = lines
+        .iter()
+        .zip(expected_ends)
+        .filter(|((_, line), expected)| end_word(line).map(|(_, word)| normalize_word(word)).as_deref() == Some(*expected))
+        .count();
+    let score = share(right_ends, body_lines);
+
+    if lines.len() < body_lines || lines.len() > body_lines + envoi_lines {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: format!(
+                    "A {} should have {} lines ({} stanzas of {} lines + {}-line envoi), found {}",
+                    form, body_lines + envoi_lines, n, n, envoi_lines, lines.len()
+                ),
+                suggestion: Some(format!("Structure: {} stanzas of {} lines each, plus a {}-line envoi", n, n, envoi_lines)),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec![
+                format!("Each stanza should have {} lines", n),
+                format!("End with a {}-line envoi using all {} end words", envoi_lines, n),
+                format!("Each line should end with one of the {} designated words", n),
+            ],
+            metadata: serde_json::json!({
+                "constraint_type": constraint_type,
+                "n": n,
+                "line_count": lines.len(),
+                "expected_lines": expected_total
+            }),
+        }.with_score(score).located(text));
+    }
+
+    let mut layout = vec![n; n];
+    if lines.len() > body_lines {
+        layout.push(lines.len() - body_lines);
+    }
+    let mut violations = stanza_layout_violations(&stanzas, &layout, &form);
+
+    for (stanza_idx, order) in orders.iter().enumerate() {
+        for (line_idx, &word_idx) in order.iter().enumerate() {
+            let overall_line_idx = stanza_idx * n + line_idx;
+            let (offset, line) = lines[overall_line_idx];
+            let actual = end_word(line);
+
+            if actual.map(|(_, word)| normalize_word(word)).as_deref() != Some(normalized[word_idx].as_str()) {
+                let (position, length) = match actual {
+                    Some((pos, word)) => (offset + pos, word.len()),
+                    None => (offset, line.len()),
+                };
+                violations.push(Violation {
+                    position,
+                    length,
+                    span: None,
+                    issue: format!("Stanza {}, line {} should end with '{}', but ends with '{}'",
+                        stanza_idx + 1, line_idx + 1, end_words[word_idx],
+                        actual.map(|(_, word)| word).unwrap_or("")),
+                    suggestion: Some(format!("Rewrite line {} to end with '{}'", overall_line_idx + 1, end_words[word_idx])),
+                    fixes: actual.map(|(_, word)| end_word_fix(word, &end_words[word_idx], position)).into_iter().collect(),
+                });
+            }
+        }
+    }
+
+    let envoi = &lines[body_lines..];
+    if envoi.is_empty() {
+        if require_envoi {
+            let (offset, line) = lines[lines.len() - 1];
+            violations.push(Violation {
+                position: offset,
+                length: line.len(),
+                span: None,
+                issue: format!("Missing the {}-line envoi", envoi_lines),
+                suggestion: Some(format!("Close with {} lines that together use all {} end words", envoi_lines, n)),
+                fixes: Vec::new(),
+            });
+        }
+    } else {
+        violations.extend(envoi_violations(envoi, end_words, &normalized, envoi_lines));
+    }
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            format!("Valid {} structure", form)
+        } else {
+            format!("{} structure has {} violations", form, violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec![format!("Perfect {} structure!", form)]
+        } else {
+            vec![
+                format!("Check line endings match the {} pattern", form),
+                "Ensure each stanza follows the spiral word rotation".to_string(),
+                format!("Verify the envoi uses all {} words", n),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": constraint_type,
+            "n": n,
+            "end_words": end_words,
+            "permutation": spiral_permutation(n).iter().map(|i| i + 1).collect::<Vec<_>>(),
+            "stanza_orders": orders.iter().map(|o| o.iter().map(|i| i + 1).collect::<Vec<_>>()).collect::<Vec<_>>(),
+            "envoi_lines": envoi.len(),
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count,
+            "line_count": lines.len()
+        }),
+    }.with_score(score).located(text))
+}
+
+/// Swap a wrong end word for the expected one, keeping the line's capitalisation
+///
+/// Another form of the expected word ("stones" for "stone") is a likely slip;
+/// any other word is probably deliberate, so its fix is less confident.
+fn end_word_fix(actual: &str, expected: &str, position: usize) -> Fix {
+    let replacement = match_case(actual, expected);
+    let inflected = is_plural_of(actual, expected) || is_plural_of(expected, actual);
+    Fix::new(
+        format!("End the line with '{}'", replacement),
+        position,
+        actual.len(),
+        replacement,
+        if inflected { 0.9 } else { 0.5 },
+    )
+}
+
+fn envoi_violations(
+    envoi: &[(usize, &str)],
+    end_words: &[String],
+    normalized: &[String],
+    expected_lines: usize,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let start = envoi[0].0;
+    let span = envoi[envoi.len() - 1].0 + envoi[envoi.len() - 1].1.len() - start;
+
+    if envoi.len() != expected_lines {
+        violations.push(Violation {
+            position: start,
+            length: span,
+            span: None,
+            issue: format!("Envoi has {} lines, expected {}", envoi.len(), expected_lines),
+            suggestion: Some(format!("Rework the envoi into {} lines", expected_lines)),
+            fixes: Vec::new(),
+        });
+    }
+
+    for (idx, &(offset, line)) in envoi.iter().enumerate() {
+        if let Some((pos, word)) = end_word(line) {
+            if !normalized.contains(&normalize_word(word)) {
+                violations.push(Violation {
+                    position: offset + pos,
+                    length: word.len(),
+                    span: None,
+                    issue: format!("Envoi line {} ends with '{}', which is not an end word", idx + 1, word),
+                    suggestion: Some("End each envoi line on one of the end words".to_string()),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let used: Vec<String> = envoi
+        .iter()
+        .flat_map(|&(_, line)| word_spans(line))
+        .map(|(_, w)| normalize_word(w))
+        .collect();
+    let missing: Vec<&str> = end_words
+        .iter()
+        .zip(normalized)
+        .filter(|(_, n)| !used.contains(n))
+        .map(|(w, _)| w.as_str())
+        .collect();
+    if !missing.is_empty() {
+        violations.push(Violation {
+            position: start,
+            length: span,
+            span: None,
+            issue: format!("Envoi does not use the end word(s) {}", missing.iter().map(|w| format!("'{}'", w)).collect::<Vec<_>>().join(", ")),
+            suggestion: Some("Place the remaining end words inside the envoi lines".to_string()),
+            fixes: Vec::new(),
+        });
+    }
+
+    violations
+}
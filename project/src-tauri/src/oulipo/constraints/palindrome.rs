@@ -1,71 +1,216 @@
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    utils::{line_spans, normalize_line, word_spans},
+};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// The unit a palindrome is mirrored on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PalindromeMode {
+    /// Letters read the same both ways ("A man, a plan, a canal: Panama")
+    #[default]
+    Letter,
+    /// Words read the same both ways ("Fall leaves after leaves fall")
+    Word,
+    /// Lines read the same from the last line up
+    Line,
+}
+
+impl PalindromeMode {
+    /// Parse a mode name as used in constraint configs
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "letter" | "letters" | "character" => Some(PalindromeMode::Letter),
+            "word" | "words" | "word_unit" => Some(PalindromeMode::Word),
+            "line" | "lines" | "line_unit" => Some(PalindromeMode::Line),
+            _ => None,
+        }
+    }
+
+    fn unit_name(self) -> &'static str {
+        match self {
+            PalindromeMode::Letter => "letter",
+            PalindromeMode::Word => "word",
+            PalindromeMode::Line => "line",
+        }
+    }
+}
+
+/// Palindrome constraint - text must read the same forwards and backwards
+#[derive(Debug, Clone, Default)]
+pub struct PalindromeConstraint {
+    mode: PalindromeMode,
+}
+
+impl PalindromeConstraint {
+    /// Create a new letter-level palindrome constraint
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mirror words or lines instead of letters
+    pub fn with_mode(mut self, mode: PalindromeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+impl Constraint for PalindromeConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_with_mode(text, self.mode)
+    }
+
+    fn name(&self) -> &'static str {
+        "Palindrome"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text must read the same forwards and backwards"
+    }
+}
 
 /// Check if text is a palindrome (reads the same forwards and backwards)
 pub fn check(text: &str) -> Result<ConstraintResult> {
-    let cleaned: String = text
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .map(|c| c.to_lowercase().next().unwrap())
-        .collect();
-    
-    let reversed: String = cleaned.chars().rev().collect();
-    let is_palindrome = cleaned == reversed;
-    
+    check_with_mode(text, PalindromeMode::Letter)
+}
+
+/// Check if text is a palindrome on letters, words or lines
+///
+/// Case and punctuation are ignored. When the text is not a palindrome, the
+/// violations point at the outermost pair of units that fail to mirror.
+pub fn check_with_mode(text: &str, mode: PalindromeMode) -> Result<ConstraintResult> {
+    let units = palindrome_units(text, mode);
+    let unit = mode.unit_name();
+
+    // Pairs that mirror, counted from the outside in
+    let mirrored = (0..units.len() / 2)
+        .take_while(|&i| units[i].key == units[units.len() - 1 - i].key)
+        .count();
+    let is_palindrome = mirrored == units.len() / 2;
+
     let violations = if is_palindrome {
         Vec::new()
     } else {
-        find_palindrome_violations(&cleaned, &reversed)
+        let (left, right) = (&units[mirrored], &units[units.len() - 1 - mirrored]);
+        vec![
+            Violation {
+                position: left.position,
+                length: left.length,
+                span: None,
+                issue: format!(
+                    "{} '{}' doesn't match its mirror '{}'",
+                    capitalize(unit), left.key, right.key
+                ),
+                suggestion: Some(format!("Change one of the pair so '{}' mirrors '{}'", left.key, right.key)),
+                fixes: Vec::new(),
+            },
+            Violation {
+                position: right.position,
+                length: right.length,
+                span: None,
+                issue: format!(
+                    "{} '{}' is the mirror of '{}' but differs from it",
+                    capitalize(unit), right.key, left.key
+                ),
+                suggestion: Some(format!("Consider changing to '{}'", left.key)),
+                fixes: Vec::new(),
+            },
+        ]
     };
-    
+
     let suggestions = if is_palindrome {
         vec!["Perfect palindrome!".to_string()]
     } else {
-        generate_palindrome_suggestions()
+        match mode {
+            PalindromeMode::Letter => vec![
+                "Fix the outermost mismatch first, then work inwards".to_string(),
+                "Build from the centre outward, one word at a time".to_string(),
+            ],
+            PalindromeMode::Word => vec![
+                "The second half should repeat the first half's words in reverse order".to_string(),
+                "Words may change meaning as they return (\"Fall leaves after leaves fall\")".to_string(),
+            ],
+            PalindromeMode::Line => vec![
+                "The second half should repeat the first half's lines in reverse order".to_string(),
+                "A single middle line may stand alone as the pivot".to_string(),
+            ],
+        }
     };
-    
+
+    let mut metadata = serde_json::json!({
+        "constraint_type": "palindrome",
+        "mode": mode,
+        "original_length": text.len(),
+        "unit_count": units.len(),
+        "mirrored_pairs": mirrored,
+        "is_palindrome": is_palindrome
+    });
+    if mode == PalindromeMode::Letter {
+        let cleaned: String = units.iter().map(|u| u.key.as_str()).collect();
+        metadata["cleaned_length"] = serde_json::json!(cleaned.len());
+        metadata["cleaned_text"] = serde_json::json!(cleaned);
+    }
+
     Ok(ConstraintResult {
         success: is_palindrome,
-        result: Some(if is_palindrome { 
-            "Valid palindrome" 
-        } else { 
-            "Not a palindrome" 
-        }.to_string()),
+        result: Some(if is_palindrome {
+            format!("Valid {} palindrome", unit)
+        } else {
+            format!("Not a {} palindrome", unit)
+        }),
         violations,
         suggestions,
-        metadata: serde_json::json!({
-            "original_length": text.len(),
-            "cleaned_length": cleaned.len(),
-            "is_palindrome": is_palindrome,
-            "cleaned_text": cleaned
-        }),
-    })
+        metadata,
+    }.located(text))
 }
 
-fn find_palindrome_violations(text: &str, reversed: &str) -> Vec<Violation> {
-    let chars: Vec<char> = text.chars().collect();
-    let rev_chars: Vec<char> = reversed.chars().collect();
-    let mut violations = Vec::new();
-    
-    for (i, (&ch, &rev_ch)) in chars.iter().zip(rev_chars.iter()).enumerate() {
-        if ch != rev_ch {
-            violations.push(Violation {
-                position: i,
-                length: 1,
-                issue: format!("Character '{}' doesn't match its mirror '{}'", ch, rev_ch),
-                suggestion: Some(format!("Consider changing to '{}'", rev_ch)),
-            });
-        }
+/// A mirrored unit with its normalised form and span in the original text
+struct Unit {
+    key: String,
+    position: usize,
+    length: usize,
+}
+
+fn palindrome_units(text: &str, mode: PalindromeMode) -> Vec<Unit> {
+    match mode {
+        PalindromeMode::Letter => text
+            .char_indices()
+            .filter(|(_, c)| c.is_alphanumeric())
+            .map(|(position, c)| Unit {
+                key: c.to_lowercase().next().unwrap().to_string(),
+                position,
+                length: c.len_utf8(),
+            })
+            .collect(),
+        PalindromeMode::Word => word_spans(text)
+            .into_iter()
+            .map(|(position, word)| Unit {
+                key: word.to_lowercase().replace('’', "'"),
+                position,
+                length: word.len(),
+            })
+            .collect(),
+        PalindromeMode::Line => line_spans(text)
+            .into_iter()
+            .map(|(offset, line)| {
+                let trimmed = line.trim();
+                Unit {
+                    key: normalize_line(line),
+                    position: offset + (line.len() - line.trim_start().len()),
+                    length: trimmed.len(),
+                }
+            })
+            .filter(|unit| !unit.key.is_empty())
+            .collect(),
     }
-    
-    violations
 }
 
-fn generate_palindrome_suggestions() -> Vec<String> {
-    vec![
-        "Add mirroring words at the end".to_string(),
-        "Remove or modify middle words".to_string(),
-        "Try single-word palindromes first".to_string(),
-        "Consider phrase-level palindromes".to_string(),
-    ]
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
@@ -0,0 +1,130 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::fixed_form::{repeated_line_violation, stanza_layout_violations, stanza_sizes},
+    utils::{line_spans, stanza_spans},
+};
+use anyhow::Result;
+
+/// Pantoum constraint - lines 2 and 4 of each quatrain return as lines 1 and 3 of the next
+pub struct PantoumConstraint {
+    circular_ending: bool,
+}
+
+impl PantoumConstraint {
+    /// Create a pantoum constraint that does not require a circular ending
+    pub fn new() -> Self {
+        Self { circular_ending: false }
+    }
+
+    /// Require the final quatrain to close the circle with lines 3 and 1 of the first
+    pub fn with_circular_ending(mut self, circular_ending: bool) -> Self {
+        self.circular_ending = circular_ending;
+        self
+    }
+}
+
+impl Default for PantoumConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for PantoumConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, self.circular_ending)
+    }
+
+    fn name(&self) -> &'static str {
+        "Pantoum"
+    }
+
+    fn description(&self) -> &'static str {
+        "Quatrains whose second and fourth lines become the first and third of the next"
+    }
+}
+
+/// Check if text is a pantoum
+///
+/// Unbroken text is read as consecutive quatrains. With `circular_ending`,
+/// the last quatrain's second and fourth lines must repeat the first
+/// quatrain's third and first lines.
+pub fn check(text: &str, circular_ending: bool) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+    let quatrains = lines.len().div_ceil(4);
+
+    if quatrains < 2 {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: format!("Pantoum needs at least two quatrains, found {} lines", lines.len()),
+                suggestion: Some("Carry lines 2 and 4 of the first quatrain into a second one".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec!["A pantoum is built from interlocking quatrains".to_string()],
+            metadata: serde_json::json!({
+                "constraint_type": "pantoum",
+                "line_count": lines.len()
+            }),
+        }.located(text));
+    }
+
+    let mut violations = stanza_layout_violations(&stanzas, &vec![4; stanzas.len().max(quatrains)], "pantoum");
+    if !lines.len().is_multiple_of(4) {
+        let (offset, line) = lines[lines.len() - 1];
+        violations.push(Violation {
+            position: offset,
+            length: line.len(),
+            span: None,
+            issue: format!("Pantoum has {} lines, which is not a whole number of quatrains", lines.len()),
+            suggestion: Some(format!("Complete the last quatrain with {} more lines", 4 - lines.len() % 4)),
+            fixes: Vec::new(),
+        });
+    }
+
+    // Lines are addressed as consecutive quatrains, whatever the stanza breaks say
+    for stanza in 0..quatrains - 1 {
+        let (this, next) = (stanza * 4, (stanza + 1) * 4);
+        violations.extend(repeated_line_violation(&lines, this + 1, next, "the second line of the previous stanza"));
+        violations.extend(repeated_line_violation(&lines, this + 3, next + 2, "the fourth line of the previous stanza"));
+    }
+
+    if circular_ending {
+        let last = (quatrains - 1) * 4;
+        violations.extend(repeated_line_violation(&lines, 2, last + 1, "the third line of the first stanza"));
+        violations.extend(repeated_line_violation(&lines, 0, last + 3, "the opening line"));
+    }
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            "Valid pantoum".to_string()
+        } else {
+            format!("Pantoum has {} violations", violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec!["Perfect pantoum!".to_string()]
+        } else {
+            vec![
+                "Lines 2 and 4 of each stanza become lines 1 and 3 of the next".to_string(),
+                "Close by repeating the opening line as the very last".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "pantoum",
+            "quatrains": quatrains,
+            "circular_ending": circular_ending,
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count
+        }),
+    }.located(text))
+}
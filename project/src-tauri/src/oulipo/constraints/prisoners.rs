@@ -1,6 +1,34 @@
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    utils::share,
+};
 use anyhow::Result;
 
+/// Prisoner's constraint - text may only use letters without loops
+#[derive(Debug, Clone, Default)]
+pub struct PrisonersConstraint;
+
+impl PrisonersConstraint {
+    /// Create a new prisoner's constraint
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Constraint for PrisonersConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text)
+    }
+
+    fn name(&self) -> &'static str {
+        "Prisoner's Constraint"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text may only use letters without loops"
+    }
+}
+
 /// Check prisoner's constraint (only letters without loops: c, f, h, i, j, k, l, m, n, s, t, u, v, w, x, y, z)
 pub fn check(text: &str) -> Result<ConstraintResult> {
     let allowed_letters = "cfhijklmnstuvwxyz";
@@ -12,9 +40,11 @@ pub fn check(text: &str) -> Result<ConstraintResult> {
             if !allowed_letters.contains(&lower_ch) {
                 violations.push(Violation {
                     position: pos,
-                    length: 1,
+                    length: ch.len_utf8(),
+                    span: None,
                     issue: format!("Letter '{}' contains loops and is forbidden", ch),
                     suggestion: Some("Replace with a letter without loops".to_string()),
+                    fixes: Vec::new(),
                 });
             }
         }
@@ -28,6 +58,7 @@ pub fn check(text: &str) -> Result<ConstraintResult> {
     };
     
     let violation_count = violations.len();
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
     
     Ok(ConstraintResult {
         success,
@@ -44,7 +75,7 @@ pub fn check(text: &str) -> Result<ConstraintResult> {
             "violation_count": violation_count,
             "text_length": text.len()
         }),
-    })
+    }.with_score(share(letters - violation_count, letters)).located(text))
 }
 
 fn generate_prisoners_suggestions() -> Vec<String> {
@@ -0,0 +1,248 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    errors::{OulipoError, OulipoResult},
+    phonetics::PronunciationDictionary,
+    rhyme::{self, RhymeEngine, RhymeLevel},
+    utils::{line_spans, share},
+};
+use anyhow::Result;
+use std::fmt;
+use std::sync::Arc;
+
+/// A declared rhyme scheme such as `ABAB CDCD EFEF GG`
+///
+/// Each letter stands for one line; lines sharing a letter must rhyme.
+/// Whitespace marks stanza breaks and is only used for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RhymeScheme {
+    letters: Vec<char>,
+    stanzas: Vec<usize>,
+}
+
+impl RhymeScheme {
+    /// Parse a scheme string; letters are case-insensitive
+    pub fn parse(scheme: &str) -> OulipoResult<Self> {
+        let mut letters = Vec::new();
+        let mut stanzas = Vec::new();
+
+        for group in scheme.split_whitespace() {
+            if let Some(bad) = group.chars().find(|c| !c.is_ascii_alphabetic()) {
+                return Err(OulipoError::InvalidConfig(
+                    format!("Rhyme scheme may only contain letters and spaces, found '{}'", bad)
+                ));
+            }
+            letters.extend(group.chars().map(|c| c.to_ascii_uppercase()));
+            stanzas.push(group.len());
+        }
+
+        if letters.is_empty() {
+            return Err(OulipoError::InvalidConfig("Rhyme scheme must not be empty".to_string()));
+        }
+
+        Ok(Self { letters, stanzas })
+    }
+
+    /// Scheme letter for each line
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Number of lines the scheme describes
+    pub fn line_count(&self) -> usize {
+        self.letters.len()
+    }
+}
+
+impl fmt::Display for RhymeScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&group_letters(&self.letters, &self.stanzas))
+    }
+}
+
+/// Rhyme scheme constraint - lines sharing a scheme letter must rhyme
+pub struct RhymeSchemeConstraint {
+    scheme: RhymeScheme,
+    min_level: RhymeLevel,
+    engine: RhymeEngine,
+}
+
+impl RhymeSchemeConstraint {
+    /// Create a rhyme scheme constraint requiring perfect rhymes
+    pub fn new(scheme: &str) -> OulipoResult<Self> {
+        Ok(Self {
+            scheme: RhymeScheme::parse(scheme)?,
+            min_level: RhymeLevel::Perfect,
+            engine: RhymeEngine::new(),
+        })
+    }
+
+    /// Accept weaker rhymes, e.g. slant rhymes for modern verse
+    pub fn with_min_level(mut self, min_level: RhymeLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+
+    /// Use a specific pronunciation dictionary for rhyme comparison
+    pub fn with_pronunciations(mut self, pronunciations: Arc<PronunciationDictionary>) -> Self {
+        self.engine = RhymeEngine::with_pronunciations(pronunciations);
+        self
+    }
+}
+
+impl Constraint for RhymeSchemeConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, &self.scheme, self.min_level, &self.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Rhyme Scheme"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lines must rhyme according to a declared scheme such as ABAB CDCD EFEF GG"
+    }
+}
+
+/// Check a poem against a rhyme scheme
+///
+/// Each line is compared with the earlier lines of its letter and passes if it
+/// rhymes with any of them at `min_level` or better. The score is the share
+/// of such lines that rhyme, scaled down when the poem has the wrong length.
+pub fn check(text: &str, scheme: &RhymeScheme, min_level: RhymeLevel, engine: &RhymeEngine) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let mut violations = Vec::new();
+
+    // End word of each line with its absolute byte offset
+    let end_words: Vec<Option<(usize, &str)>> = lines
+        .iter()
+        .map(|&(offset, line)| rhyme::end_word(line).map(|(pos, word)| (offset + pos, word)))
+        .collect();
+
+    if lines.len() != scheme.line_count() {
+        violations.push(Violation {
+            position: 0,
+            length: text.len(),
+            span: None,
+            issue: format!("Rhyme scheme {} has {} lines, found {}", scheme, scheme.line_count(), lines.len()),
+            suggestion: Some(format!("Write {} lines", scheme.line_count())),
+            fixes: Vec::new(),
+        });
+    }
+
+    let mut line_reports = Vec::new();
+    let mut paired = 0;
+    let mut rhymed = 0;
+    for (idx, end) in end_words.iter().enumerate() {
+        let letter = scheme.letters.get(idx).copied();
+        let word = end.map(|(_, w)| w).unwrap_or("");
+
+        // Compare against earlier lines sharing this letter
+        let partners: Vec<usize> = match letter {
+            Some(letter) => (0..idx).filter(|&prev| scheme.letters[prev] == letter).collect(),
+            None => Vec::new(),
+        };
+        let best = partners
+            .iter()
+            .filter_map(|&prev| end_words[prev].map(|(_, w)| (prev, engine.compare(w, word))))
+            .max_by_key(|&(_, level)| level);
+
+        if let (Some(letter), Some(&first)) = (letter, partners.first()) {
+            let matched = best.is_some_and(|(_, level)| level >= min_level);
+            paired += 1;
+            if matched {
+                rhymed += 1;
+            } else {
+                let anchor = end_words[first].map(|(_, w)| w).unwrap_or("");
+                let (position, length) = match end {
+                    Some((pos, w)) => (*pos, w.len()),
+                    None => (lines[idx].0, lines[idx].1.len()),
+                };
+                violations.push(Violation {
+                    position,
+                    length,
+                    span: None,
+                    issue: format!(
+                        "Line {} ends with '{}', which does not rhyme with '{}' (line {}, rhyme {})",
+                        idx + 1, word, anchor, first + 1, letter
+                    ),
+                    suggestion: Some(format!("End the line with a word that rhymes with '{}'", anchor)),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+
+        line_reports.push(serde_json::json!({
+            "line": idx + 1,
+            "end_word": word,
+            "expected_letter": letter.map(String::from),
+            "rhymes_with": best.map(|(prev, _)| prev + 1),
+            "level": best.map(|(_, level)| level)
+        }));
+    }
+
+    let words: Vec<&str> = end_words.iter().map(|e| e.map(|(_, w)| w).unwrap_or("")).collect();
+    let detected = group_letters(&engine.detect_scheme(&words, min_level), &stanza_sizes(text));
+
+    let success = violations.is_empty();
+    let violation_count = violations.len();
+    let length_share = share(lines.len().min(scheme.line_count()), lines.len().max(scheme.line_count()));
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success {
+            format!("Follows rhyme scheme {}", scheme)
+        } else {
+            format!("{} rhyme scheme violations", violation_count)
+        }),
+        violations,
+        suggestions: if success {
+            vec!["Rhyme scheme satisfied!".to_string()]
+        } else {
+            vec![
+                "Rearrange clauses so a rhyming word falls at the line end".to_string(),
+                "Consider slant rhymes if perfect rhymes feel forced".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "rhyme_scheme",
+            "scheme": scheme.to_string(),
+            "detected_scheme": detected,
+            "min_level": min_level,
+            "lines": line_reports
+        }),
+    }.with_score(share(rhymed, paired) * length_share).located(text))
+}
+
+/// Number of non-empty lines in each blank-line separated stanza
+fn stanza_sizes(text: &str) -> Vec<usize> {
+    let mut sizes = vec![0];
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if sizes.last() != Some(&0) {
+                sizes.push(0);
+            }
+        } else if let Some(last) = sizes.last_mut() {
+            *last += 1;
+        }
+    }
+    sizes.retain(|&size| size > 0);
+    sizes
+}
+
+/// Join letters into space-separated stanza groups
+fn group_letters(letters: &[char], stanzas: &[usize]) -> String {
+    let mut groups = Vec::new();
+    let mut rest = letters;
+    for &size in stanzas {
+        if rest.is_empty() {
+            break;
+        }
+        let (group, tail) = rest.split_at(size.min(rest.len()));
+        groups.push(group.iter().collect::<String>());
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        groups.push(rest.iter().collect());
+    }
+    groups.join(" ")
+}
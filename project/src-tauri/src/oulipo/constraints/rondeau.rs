@@ -0,0 +1,157 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::fixed_form::{rhyme_group_violations, stanza_layout_violations, stanza_sizes},
+    rhyme::{RhymeEngine, RhymeLevel},
+    utils::{line_spans, normalize_line, stanza_spans},
+};
+use anyhow::Result;
+
+/// Quintet, quatrain and sestet
+const STANZAS: [usize; 3] = [5, 4, 6];
+/// Lines (0-based) carrying the rentrement
+const RENTREMENT_LINES: [usize; 2] = [8, 14];
+/// Lines on the A rhyme (aabba aab aabba)
+const A_LINES: [usize; 8] = [0, 1, 4, 5, 6, 9, 10, 13];
+/// Lines on the B rhyme
+const B_LINES: [usize; 5] = [2, 3, 7, 11, 12];
+
+/// Rondeau constraint - 15 lines on two rhymes with a rentrement (aabba aabR aabbaR)
+pub struct RondeauConstraint {
+    min_level: RhymeLevel,
+    engine: RhymeEngine,
+}
+
+impl RondeauConstraint {
+    /// Create a rondeau constraint requiring perfect rhymes
+    pub fn new() -> Self {
+        Self {
+            min_level: RhymeLevel::Perfect,
+            engine: RhymeEngine::new(),
+        }
+    }
+
+    /// Accept weaker rhymes, e.g. slant rhymes
+    pub fn with_min_level(mut self, min_level: RhymeLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+}
+
+impl Default for RondeauConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for RondeauConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, self.min_level, &self.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Rondeau"
+    }
+
+    fn description(&self) -> &'static str {
+        "15 lines on two rhymes, with the opening words returning as a short refrain"
+    }
+}
+
+/// Check if text is a rondeau
+///
+/// The rentrement (lines 9 and 15) must repeat the opening words of line 1,
+/// stopping short of the whole line.
+pub fn check(text: &str, min_level: RhymeLevel, engine: &RhymeEngine) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+
+    if lines.len() != 15 {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: format!("Rondeau should have 15 lines (5 + 4 + 6), found {}", lines.len()),
+                suggestion: Some("Structure: a quintet, a quatrain ending in the rentrement, and a sestet ending in it".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec!["The rentrement is the opening phrase of the first line".to_string()],
+            metadata: serde_json::json!({
+                "constraint_type": "rondeau",
+                "line_count": lines.len(),
+                "expected_lines": 15
+            }),
+        }.located(text));
+    }
+
+    let mut violations = stanza_layout_violations(&stanzas, &STANZAS, "rondeau");
+
+    let opening: Vec<String> = normalize_line(lines[0].1).split(' ').map(str::to_string).collect();
+    let mut rentrement: Option<String> = None;
+    for &idx in &RENTREMENT_LINES {
+        let (offset, line) = lines[idx];
+        let words = normalize_line(line);
+        let word_count = words.split(' ').filter(|w| !w.is_empty()).count();
+        let is_opening_phrase = word_count > 0
+            && word_count < opening.len()
+            && words == opening[..word_count].join(" ");
+
+        if !is_opening_phrase {
+            violations.push(Violation {
+                position: offset,
+                length: line.len(),
+                span: None,
+                issue: format!("Line {} should be the rentrement: the opening words of line 1", idx + 1),
+                suggestion: Some(format!("Use a short phrase from the start of \"{}\"", lines[0].1.trim())),
+                fixes: Vec::new(),
+            });
+        } else if let Some(first) = &rentrement {
+            if *first != words {
+                violations.push(Violation {
+                    position: offset,
+                    length: line.len(),
+                    span: None,
+                    issue: format!("Line {} uses a different rentrement from line 9", idx + 1),
+                    suggestion: Some("Both refrains must repeat the same opening words".to_string()),
+                    fixes: Vec::new(),
+                });
+            }
+        } else {
+            rentrement = Some(words);
+        }
+    }
+
+    violations.extend(rhyme_group_violations(&lines, &[A_LINES.to_vec(), B_LINES.to_vec()], min_level, engine));
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            "Valid rondeau".to_string()
+        } else {
+            format!("Rondeau has {} violations", violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec!["Perfect rondeau!".to_string()]
+        } else {
+            vec![
+                "Only two rhyme sounds run through the whole poem".to_string(),
+                "End the second and third stanzas with the opening words".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "rondeau",
+            "rentrement": rentrement,
+            "rhyme_scheme": "AABBA AABR AABBAR",
+            "min_level": min_level,
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count
+        }),
+    }.located(text))
+}
@@ -1,63 +1,218 @@
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    utils::{line_spans, word_spans},
+};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// The shape a snowball follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnowballVariant {
+    /// Each word one letter longer than the last (the rhopalic form)
+    #[default]
+    Ascending,
+    /// Each word one letter shorter than the last
+    Melting,
+    /// Words grow one letter at a time, then melt back to the starting length
+    Diamond,
+    /// Each line one word longer than the last
+    Lines,
+}
+
+impl SnowballVariant {
+    /// Parse a variant name as used in constraint configs
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "ascending" | "rhopalic" | "growing" => Some(SnowballVariant::Ascending),
+            "melting" | "descending" => Some(SnowballVariant::Melting),
+            "diamond" => Some(SnowballVariant::Diamond),
+            "lines" | "line" => Some(SnowballVariant::Lines),
+            _ => None,
+        }
+    }
+}
+
+/// Snowball constraint - each word must be one letter longer than the previous
+#[derive(Debug, Clone, Default)]
+pub struct SnowballConstraint {
+    variant: SnowballVariant,
+    start: Option<usize>,
+}
+
+impl SnowballConstraint {
+    /// Create a new snowball constraint
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the melting, diamond or line-level form instead
+    pub fn with_variant(mut self, variant: SnowballVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Length of the first unit (letters, or words for line snowballs)
+    pub fn with_start(mut self, start: usize) -> Self {
+        self.start = Some(start);
+        self
+    }
+}
+
+impl Constraint for SnowballConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_variant(text, self.variant, self.start)
+    }
+
+    fn name(&self) -> &'static str {
+        "Snowball"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each word must be one letter longer than the previous"
+    }
+}
 
 /// Check if text follows snowball pattern (each word is one letter longer than the previous)
 pub fn check(text: &str) -> Result<ConstraintResult> {
-    let words: Vec<&str> = text.split_whitespace().collect();
+    check_variant(text, SnowballVariant::Ascending, None)
+}
+
+/// Check a snowball variant, optionally fixing the length of the first unit
+///
+/// Without a start, ascending, diamond and line snowballs start at 1 and a
+/// melting snowball starts at the length of its first word. Words are measured
+/// in letters, so punctuation does not count.
+pub fn check_variant(text: &str, variant: SnowballVariant, start: Option<usize>) -> Result<ConstraintResult> {
+    // (position, span length, measured length) for each unit
+    let units: Vec<(usize, usize, usize)> = match variant {
+        SnowballVariant::Lines => line_spans(text)
+            .into_iter()
+            .map(|(offset, line)| (offset, line.len(), word_spans(line).len()))
+            .collect(),
+        _ => word_spans(text)
+            .into_iter()
+            .map(|(offset, word)| (offset, word.len(), word.chars().filter(|c| c.is_alphabetic()).count()))
+            .collect(),
+    };
+    let unit = if variant == SnowballVariant::Lines { "Line" } else { "Word" };
+    let measure = if variant == SnowballVariant::Lines { "words" } else { "letters" };
+
+    let start = start.unwrap_or(match variant {
+        SnowballVariant::Melting => units.first().map_or(1, |&(_, _, len)| len),
+        _ => 1,
+    });
+
     let mut violations = Vec::new();
-    let mut position = 0;
-    
-    for (i, word) in words.iter().enumerate() {
-        let expected_length = i + 1;
-        let actual_length = word.len();
-        
-        if actual_length != expected_length {
+    let mut expected_pattern = Vec::with_capacity(units.len());
+    let mut melting = variant == SnowballVariant::Melting;
+    let mut previous: Option<usize> = None;
+
+    for (i, &(position, length, actual)) in units.iter().enumerate() {
+        let expected = match previous {
+            None => start,
+            Some(prev) if variant == SnowballVariant::Diamond && !melting && actual + 1 == prev => {
+                // The diamond turns at its widest point
+                melting = true;
+                prev - 1
+            }
+            Some(prev) if melting => prev.saturating_sub(1),
+            Some(prev) => prev + 1,
+        };
+        expected_pattern.push(expected);
+
+        if expected == 0 {
             violations.push(Violation {
                 position,
-                length: word.len(),
+                length,
+                span: None,
+                issue: format!("{} {} comes after the snowball has melted away", unit, i + 1),
+                suggestion: Some("End the text at the one-letter word".to_string()),
+                fixes: Vec::new(),
+            });
+        } else if actual != expected {
+            violations.push(Violation {
+                position,
+                length,
+                span: None,
                 issue: format!(
-                    "Word {} should be {} letters, but is {}", 
-                    i + 1, expected_length, actual_length
+                    "{} {} should be {} {}, but is {}",
+                    unit, i + 1, expected, measure, actual
                 ),
-                suggestion: Some(format!("Replace with a {}-letter word", expected_length)),
+                suggestion: Some(if variant == SnowballVariant::Lines {
+                    format!("Rewrite the line with {} words", expected)
+                } else {
+                    format!("Replace with a {}-letter word", expected)
+                }),
+                fixes: Vec::new(),
             });
         }
-        
-        position += word.len() + 1; // +1 for space
+        previous = Some(expected);
+    }
+
+    if variant == SnowballVariant::Diamond {
+        if let (Some(&(position, length, _)), Some(&last)) = (units.last(), expected_pattern.last()) {
+            if !melting || last != start {
+                violations.push(Violation {
+                    position,
+                    length,
+                    span: None,
+                    issue: format!("A diamond should melt back to {} letters, but ends at {}", start, last),
+                    suggestion: Some("Shorten each word after the widest one until you reach the starting length".to_string()),
+                    fixes: Vec::new(),
+                });
+            }
+        }
     }
-    
+
     let success = violations.is_empty();
     let suggestions = if success {
         vec!["Perfect snowball pattern!".to_string()]
     } else {
-        generate_snowball_suggestions()
+        generate_snowball_suggestions(variant)
     };
-    
+
     let violation_count = violations.len();
-    
+
     Ok(ConstraintResult {
         success,
-        result: Some(if success { 
+        result: Some(if success {
             "Valid snowball pattern".to_string()
-        } else { 
-            format!("{} violations found", violation_count) 
+        } else {
+            format!("{} violations found", violation_count)
         }),
         violations,
         suggestions,
         metadata: serde_json::json!({
-            "word_count": words.len(),
+            "constraint_type": "snowball",
+            "variant": variant,
+            "word_count": word_spans(text).len(),
             "violation_count": violation_count,
-            "expected_pattern": (1..=words.len()).collect::<Vec<_>>(),
-            "actual_lengths": words.iter().map(|w| w.len()).collect::<Vec<_>>()
+            "expected_pattern": expected_pattern,
+            "actual_lengths": units.iter().map(|&(_, _, len)| len).collect::<Vec<_>>()
         }),
-    })
+    }.located(text))
 }
 
-fn generate_snowball_suggestions() -> Vec<String> {
-    vec![
-        "Start with single-letter words (I, a)".to_string(),
-        "Use progressively longer synonyms".to_string(),
-        "Consider compound words for longer positions".to_string(),
-        "Plan the sentence structure in advance".to_string(),
-    ]
+fn generate_snowball_suggestions(variant: SnowballVariant) -> Vec<String> {
+    match variant {
+        SnowballVariant::Ascending => vec![
+            "Start with single-letter words (I, a)".to_string(),
+            "Use progressively longer synonyms".to_string(),
+            "Consider compound words for longer positions".to_string(),
+            "Plan the sentence structure in advance".to_string(),
+        ],
+        SnowballVariant::Melting => vec![
+            "Open with your longest word and end on a single letter".to_string(),
+            "Short function words (of, a) suit the final positions".to_string(),
+        ],
+        SnowballVariant::Diamond => vec![
+            "Grow to a widest word, then melt back one letter at a time".to_string(),
+            "Mirror the growing half's lengths in the melting half".to_string(),
+        ],
+        SnowballVariant::Lines => vec![
+            "Add exactly one word per line".to_string(),
+            "Count words, not letters or syllables".to_string(),
+        ],
+    }
 }
@@ -0,0 +1,320 @@
+//! Tautogram and alliteration: words that all begin with the same letter or sound.
+//!
+//! A tautogram checks every word, optionally letting function words ("the",
+//! "of") through; alliteration checks only stressed words. Either can compare
+//! first letters or first sounds. Sounds come from the pronunciation lexicon,
+//! so "phantom" and "fable" alliterate while "cinder" and "candle" do not.
+
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    phonetics::{spelled_onset, PronunciationDictionary},
+    utils::{is_function_word, share, word_spans},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// What counts as "beginning the same way"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialMatch {
+    /// The first letter of each word
+    #[default]
+    Letter,
+    /// The first sound of each word; all vowel sounds match each other
+    Sound,
+}
+
+impl InitialMatch {
+    /// Parse a matching mode as used in constraint configs
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "letter" | "letters" | "spelling" => Some(InitialMatch::Letter),
+            "sound" | "sounds" | "phoneme" | "phonetic" => Some(InitialMatch::Sound),
+            _ => None,
+        }
+    }
+}
+
+/// Tautogram constraint - every word must begin with the same letter or sound
+pub struct TautogramConstraint {
+    target: Option<String>,
+    matching: InitialMatch,
+    allow_function_words: bool,
+    pronunciations: Arc<PronunciationDictionary>,
+}
+
+impl TautogramConstraint {
+    /// Create a letter tautogram whose target is the text's most common initial
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            matching: InitialMatch::Letter,
+            allow_function_words: false,
+            pronunciations: PronunciationDictionary::shared(),
+        }
+    }
+
+    /// Require a specific initial: a letter, or in sound mode a letter or example word
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Compare first letters or first sounds
+    pub fn with_match(mut self, matching: InitialMatch) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Let function words ("a", "the", "of") begin however they like
+    pub fn with_function_words(mut self, allow: bool) -> Self {
+        self.allow_function_words = allow;
+        self
+    }
+
+    /// Use a specific pronunciation dictionary for sound matching
+    pub fn with_pronunciations(mut self, pronunciations: Arc<PronunciationDictionary>) -> Self {
+        self.pronunciations = pronunciations;
+        self
+    }
+}
+
+impl Default for TautogramConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for TautogramConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_tautogram(text, self.target.as_deref(), self.matching, self.allow_function_words, &self.pronunciations)
+    }
+
+    fn name(&self) -> &'static str {
+        "Tautogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every word must begin with the same letter or sound"
+    }
+}
+
+/// Alliteration constraint - every stressed word must begin with the same sound
+pub struct AlliterationConstraint {
+    target: Option<String>,
+    matching: InitialMatch,
+    pronunciations: Arc<PronunciationDictionary>,
+}
+
+impl AlliterationConstraint {
+    /// Create a sound-matching alliteration constraint using the built-in lexicon
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            matching: InitialMatch::Sound,
+            pronunciations: PronunciationDictionary::shared(),
+        }
+    }
+
+    /// Require a specific initial: a letter, or in sound mode a letter or example word
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    /// Compare first letters or first sounds
+    pub fn with_match(mut self, matching: InitialMatch) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Use a specific pronunciation dictionary for stress and sound lookup
+    pub fn with_pronunciations(mut self, pronunciations: Arc<PronunciationDictionary>) -> Self {
+        self.pronunciations = pronunciations;
+        self
+    }
+}
+
+impl Default for AlliterationConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for AlliterationConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_alliteration(text, self.target.as_deref(), self.matching, &self.pronunciations)
+    }
+
+    fn name(&self) -> &'static str {
+        "Alliteration"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every stressed word must begin with the same sound"
+    }
+}
+
+/// Check that every word begins with the same initial
+///
+/// Without a target, the most common initial among the checked words is used
+/// (the earliest one on a tie). With `allow_function_words`, function words
+/// are skipped rather than checked. The score is the share of checked words
+/// that begin with the target.
+pub fn check_tautogram(
+    text: &str,
+    target: Option<&str>,
+    matching: InitialMatch,
+    allow_function_words: bool,
+    pronunciations: &PronunciationDictionary,
+) -> Result<ConstraintResult> {
+    let (checked, skipped): (Vec<_>, Vec<_>) = word_spans(text)
+        .into_iter()
+        .partition(|(_, word)| !allow_function_words || !is_function_word(word));
+    let mut result = evaluate(text, &checked, &skipped, target, matching, pronunciations, "tautogram");
+    result.metadata["allow_function_words"] = serde_json::json!(allow_function_words);
+    if !result.success && !allow_function_words && checked.iter().any(|(_, word)| is_function_word(word)) {
+        result.suggestions.push("Allow function words to relax the constraint to content words".to_string());
+    }
+    Ok(result)
+}
+
+/// Check that every stressed word begins with the same initial
+///
+/// Function words are never stressed; other words count as stressed when the
+/// lexicon gives them a primary stress, or when they are missing from it.
+pub fn check_alliteration(
+    text: &str,
+    target: Option<&str>,
+    matching: InitialMatch,
+    pronunciations: &PronunciationDictionary,
+) -> Result<ConstraintResult> {
+    let (checked, skipped): (Vec<_>, Vec<_>) = word_spans(text)
+        .into_iter()
+        .partition(|(_, word)| pronunciations.is_stressed_word(word));
+    Ok(evaluate(text, &checked, &skipped, target, matching, pronunciations, "alliteration"))
+}
+
+/// The initial of a word under a matching mode
+fn initial(word: &str, matching: InitialMatch, pronunciations: &PronunciationDictionary) -> String {
+    match matching {
+        InitialMatch::Letter => word.chars().find(|c| c.is_alphabetic()).map(|c| c.to_lowercase().collect()).unwrap_or_default(),
+        InitialMatch::Sound => pronunciations.initial_sound(word).0,
+    }
+}
+
+/// The initial a target names; in sound mode a single letter is read by its spelling
+fn target_initial(target: &str, matching: InitialMatch, pronunciations: &PronunciationDictionary) -> String {
+    let target = target.trim();
+    match matching {
+        InitialMatch::Sound if target.chars().count() == 1 => spelled_onset(target),
+        _ => initial(target, matching, pronunciations),
+    }
+}
+
+fn describe(initial: &str, matching: InitialMatch) -> String {
+    match matching {
+        InitialMatch::Letter => format!("'{}'", initial),
+        InitialMatch::Sound if initial == "vowel" => "a vowel sound".to_string(),
+        InitialMatch::Sound => format!("the sound /{}/", initial),
+    }
+}
+
+fn evaluate(
+    text: &str,
+    checked: &[(usize, &str)],
+    skipped: &[(usize, &str)],
+    target: Option<&str>,
+    matching: InitialMatch,
+    pronunciations: &PronunciationDictionary,
+    constraint_type: &str,
+) -> ConstraintResult {
+    let initials: Vec<String> = checked.iter().map(|(_, word)| initial(word, matching, pronunciations)).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for initial in &initials {
+        *counts.entry(initial.as_str()).or_insert(0) += 1;
+    }
+
+    let target = match target {
+        Some(target) => Some(target_initial(target, matching, pronunciations)),
+        None => {
+            // Most common initial, earliest first on a tie
+            let best = counts.values().copied().max().unwrap_or(0);
+            initials.iter().find(|i| counts[i.as_str()] == best).cloned()
+        }
+    };
+    let Some(target) = target.filter(|t| !t.is_empty()) else {
+        return ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: if checked.is_empty() {
+                    "No words to check".to_string()
+                } else {
+                    "The target initial is empty".to_string()
+                },
+                suggestion: Some("Provide text and a letter or word as the target".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec![],
+            metadata: serde_json::json!({ "constraint_type": constraint_type, "match": matching }),
+        }.located(text);
+    };
+
+    let violations: Vec<Violation> = checked
+        .iter()
+        .zip(&initials)
+        .filter(|(_, initial)| **initial != target)
+        .map(|(&(position, word), initial)| Violation {
+            position,
+            length: word.len(),
+            span: None,
+            issue: format!(
+                "'{}' begins with {} instead of {}",
+                word,
+                describe(initial, matching),
+                describe(&target, matching)
+            ),
+            suggestion: Some(format!("Replace with a word beginning with {}", describe(&target, matching))),
+            fixes: Vec::new(),
+        })
+        .collect();
+
+    let success = violations.is_empty();
+    let suggestions = if success {
+        vec![format!("Every word begins with {}", describe(&target, matching))]
+    } else {
+        vec![
+            "A dictionary filtered by initial is the quickest source of replacements".to_string(),
+            "Reorder clauses so that stray words can be dropped".to_string(),
+        ]
+    };
+    let violation_count = violations.len();
+
+    ConstraintResult {
+        success,
+        result: Some(if success {
+            format!("Every checked word begins with {}", describe(&target, matching))
+        } else {
+            format!("{} of {} words break the pattern", violation_count, checked.len())
+        }),
+        violations,
+        suggestions,
+        metadata: serde_json::json!({
+            "constraint_type": constraint_type,
+            "match": matching,
+            "target": target,
+            "checked_words": checked.len(),
+            "skipped_words": skipped.iter().map(|(_, word)| *word).collect::<Vec<_>>(),
+            "initial_counts": counts,
+            "violation_count": violation_count
+        }),
+    }
+    .with_score(share(checked.len() - violation_count, checked.len()))
+    .located(text)
+}
@@ -0,0 +1,134 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::fixed_form::{rhyme_group_violations, stanza_layout_violations, stanza_sizes},
+    rhyme::{RhymeEngine, RhymeLevel},
+    utils::{line_spans, stanza_spans},
+};
+use anyhow::Result;
+
+/// Terza rima constraint - chained tercets ABA BCB CDC ..., closed by a line or couplet
+pub struct TerzaRimaConstraint {
+    min_level: RhymeLevel,
+    engine: RhymeEngine,
+}
+
+impl TerzaRimaConstraint {
+    /// Create a terza rima constraint requiring perfect rhymes
+    pub fn new() -> Self {
+        Self {
+            min_level: RhymeLevel::Perfect,
+            engine: RhymeEngine::new(),
+        }
+    }
+
+    /// Accept weaker rhymes, e.g. slant rhymes
+    pub fn with_min_level(mut self, min_level: RhymeLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+}
+
+impl Default for TerzaRimaConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for TerzaRimaConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, self.min_level, &self.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Terza Rima"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tercets chained ABA BCB CDC, the middle rhyme of each leading the next"
+    }
+}
+
+/// Check if text is in terza rima
+///
+/// The middle line of each tercet sets the outer rhyme of the next. A final
+/// single line or couplet rhymes with the middle of the last tercet.
+pub fn check(text: &str, min_level: RhymeLevel, engine: &RhymeEngine) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+    let tercets = lines.len() / 3;
+    let coda = lines.len() % 3;
+
+    if tercets < 2 {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: format!("Terza rima needs at least two tercets to chain, found {} lines", lines.len()),
+                suggestion: Some("Write tercets where each middle line sets the next stanza's rhyme".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec!["Terza rima rhymes ABA BCB CDC ...".to_string()],
+            metadata: serde_json::json!({
+                "constraint_type": "terza_rima",
+                "line_count": lines.len()
+            }),
+        }.located(text));
+    }
+
+    let mut expected_layout = vec![3; tercets];
+    if coda > 0 {
+        expected_layout.push(coda);
+    }
+    let mut violations = stanza_layout_violations(&stanzas, &expected_layout, "terza rima");
+
+    // Rhyme k covers the middle of tercet k-1 and the outer lines of tercet k
+    let mut groups: Vec<Vec<usize>> = (0..tercets)
+        .map(|k| {
+            let mut group = Vec::new();
+            if k > 0 {
+                group.push(3 * (k - 1) + 1);
+            }
+            group.extend([3 * k, 3 * k + 2]);
+            group
+        })
+        .collect();
+    // The last middle rhyme is picked up by the closing line or couplet
+    let mut closing = vec![3 * (tercets - 1) + 1];
+    closing.extend(3 * tercets..lines.len());
+    groups.push(closing);
+
+    violations.extend(rhyme_group_violations(&lines, &groups, min_level, engine));
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            "Valid terza rima".to_string()
+        } else {
+            format!("Terza rima has {} violations", violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec!["The chain holds!".to_string()]
+        } else {
+            vec![
+                "The middle line of each tercet sets the outer rhyme of the next".to_string(),
+                "Close with a single line or couplet on the last middle rhyme".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "terza_rima",
+            "tercets": tercets,
+            "closing_lines": coda,
+            "min_level": min_level,
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count
+        }),
+    }.located(text))
+}
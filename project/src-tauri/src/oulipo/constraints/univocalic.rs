@@ -1,7 +1,7 @@
 use crate::oulipo::{
     types::{ConstraintResult, Violation, Constraint},
     errors::{OulipoError, OulipoResult},
-    utils::{is_vowel, VOWELS},
+    utils::{is_vowel, share, VOWELS},
 };
 use anyhow::Result;
 
@@ -42,7 +42,7 @@ pub fn check(text: &str, allowed_vowel: char) -> Result<ConstraintResult> {
     check_univocalic(text, allowed_vowel)
 }
 
-/// Internal implementation of the univocalic check
+/// Internal implementation of the univocalic check; the score is the share of vowels that conform
 fn check_univocalic(text: &str, allowed_vowel: char) -> Result<ConstraintResult> {
     let allowed = allowed_vowel.to_lowercase().to_string();
     let mut violations = Vec::new();
@@ -53,9 +53,11 @@ fn check_univocalic(text: &str, allowed_vowel: char) -> Result<ConstraintResult>
             if lower_ch != allowed {
                 violations.push(Violation {
                     position: pos,
-                    length: 1,
+                    length: ch.len_utf8(),
+                    span: None,
                     issue: format!("Vowel '{}' is not allowed (only '{}' permitted)", ch, allowed_vowel),
                     suggestion: Some(format!("Replace with word containing only '{}'", allowed_vowel)),
+                    fixes: Vec::new(),
                 });
             }
         }
@@ -69,6 +71,7 @@ fn check_univocalic(text: &str, allowed_vowel: char) -> Result<ConstraintResult>
     };
     
     let violation_count = violations.len();
+    let vowels = text.chars().filter(|&c| c.is_alphabetic() && is_vowel(c)).count();
     let forbidden_vowels = VOWELS.replace(&allowed, "");
     
     Ok(ConstraintResult {
@@ -86,7 +89,7 @@ fn check_univocalic(text: &str, allowed_vowel: char) -> Result<ConstraintResult>
             "violation_count": violation_count,
             "text_length": text.len()
         }),
-    })
+    }.with_score(share(vowels - violation_count, vowels)).located(text))
 }
 
 fn generate_univocalic_suggestions(allowed_vowel: &str) -> Vec<String> {
@@ -0,0 +1,131 @@
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Constraint},
+    constraints::fixed_form::{repeated_line_violation, rhyme_group_violations, stanza_layout_violations, stanza_sizes},
+    rhyme::{RhymeEngine, RhymeLevel},
+    utils::{line_spans, stanza_spans},
+};
+use anyhow::Result;
+
+/// Five tercets and a closing quatrain
+const STANZAS: [usize; 6] = [3, 3, 3, 3, 3, 4];
+/// Lines (0-based) where the first refrain A1 returns
+const A1_REPEATS: [usize; 3] = [5, 11, 17];
+/// Lines (0-based) where the second refrain A2 returns
+const A2_REPEATS: [usize; 3] = [8, 14, 18];
+/// Lines on the B rhyme; every other line rhymes with the refrains
+const B_LINES: [usize; 6] = [1, 4, 7, 10, 13, 16];
+
+/// Villanelle constraint - 19 lines, two refrains and two rhymes (ABA ... ABAA)
+pub struct VillanelleConstraint {
+    min_level: RhymeLevel,
+    engine: RhymeEngine,
+}
+
+impl VillanelleConstraint {
+    /// Create a villanelle constraint requiring perfect rhymes
+    pub fn new() -> Self {
+        Self {
+            min_level: RhymeLevel::Perfect,
+            engine: RhymeEngine::new(),
+        }
+    }
+
+    /// Accept weaker rhymes, e.g. slant rhymes
+    pub fn with_min_level(mut self, min_level: RhymeLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+}
+
+impl Default for VillanelleConstraint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Constraint for VillanelleConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check(text, self.min_level, &self.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Villanelle"
+    }
+
+    fn description(&self) -> &'static str {
+        "19 lines in five tercets and a quatrain, with two alternating refrains"
+    }
+}
+
+/// Check if text is a villanelle
+///
+/// Line 1 (A1) must return as lines 6, 12 and 18, line 3 (A2) as lines 9, 15
+/// and 19, and the end words follow ABA ABA ABA ABA ABA ABAA.
+pub fn check(text: &str, min_level: RhymeLevel, engine: &RhymeEngine) -> Result<ConstraintResult> {
+    let lines = line_spans(text);
+    let stanzas = stanza_spans(text);
+
+    if lines.len() != 19 {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: text.len(),
+                span: None,
+                issue: format!("Villanelle should have 19 lines (5 tercets + quatrain), found {}", lines.len()),
+                suggestion: Some("Structure: five 3-line stanzas followed by a 4-line stanza".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec![
+                "Line 1 returns as lines 6, 12 and 18".to_string(),
+                "Line 3 returns as lines 9, 15 and 19".to_string(),
+            ],
+            metadata: serde_json::json!({
+                "constraint_type": "villanelle",
+                "line_count": lines.len(),
+                "expected_lines": 19
+            }),
+        }.located(text));
+    }
+
+    let mut violations = stanza_layout_violations(&stanzas, &STANZAS, "villanelle");
+    violations.extend(A1_REPEATS.iter().filter_map(|&target| repeated_line_violation(&lines, 0, target, "refrain A1")));
+    violations.extend(A2_REPEATS.iter().filter_map(|&target| repeated_line_violation(&lines, 2, target, "refrain A2")));
+
+    let a_lines: Vec<usize> = (0..19).filter(|idx| !B_LINES.contains(idx)).collect();
+    violations.extend(rhyme_group_violations(&lines, &[a_lines, B_LINES.to_vec()], min_level, engine));
+    violations.sort_by_key(|v| v.position);
+
+    let violation_count = violations.len();
+    let is_empty = violations.is_empty();
+
+    Ok(ConstraintResult {
+        success: is_empty,
+        result: Some(if is_empty {
+            "Valid villanelle".to_string()
+        } else {
+            format!("Villanelle has {} violations", violation_count)
+        }),
+        violations,
+        suggestions: if is_empty {
+            vec!["Perfect villanelle!".to_string()]
+        } else {
+            vec![
+                "Refrains may only change in punctuation".to_string(),
+                "Every line except the middle of each stanza rhymes with the refrains".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "villanelle",
+            "refrains": {
+                "a1": lines[0].1.trim(),
+                "a2": lines[2].1.trim()
+            },
+            "rhyme_scheme": "ABA ABA ABA ABA ABA ABAA",
+            "min_level": min_level,
+            "stanza_sizes": stanza_sizes(&stanzas),
+            "violations_count": violation_count
+        }),
+    }.located(text))
+}
@@ -0,0 +1,251 @@
+//! Minimal Hunspell `.dic`/`.aff` reader with prefix and suffix expansion.
+//!
+//! Only the parts of the affix format needed to enumerate surface forms are
+//! supported: `FLAG`, `PFX`, `SFX` (including cross products) and `NEEDAFFIX`.
+//! Compounding, replacement tables and the like are ignored.
+
+use crate::oulipo::errors::{OulipoError, OulipoResult};
+use std::collections::{BTreeSet, HashMap};
+
+/// Parts of speech that mark a `.dic` entry as a noun via its `po:` morphological field
+const NOUN_TAGS: &[&str] = &["noun", "n", "nom", "nn", "subst"];
+
+/// How affix flags are written in the `.dic` and `.aff` files
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagMode {
+    /// One character per flag (the default, also used for `FLAG UTF-8`)
+    Char,
+    /// Two characters per flag (`FLAG long`)
+    Long,
+    /// Comma-separated decimal numbers (`FLAG num`)
+    Num,
+}
+
+/// One element of an affix condition such as `[^aeiou]y`
+#[derive(Debug, Clone)]
+enum ConditionPart {
+    Any,
+    Literal(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl ConditionPart {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            ConditionPart::Any => true,
+            ConditionPart::Literal(expected) => *expected == ch,
+            ConditionPart::Set { chars, negated } => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+/// A single `PFX`/`SFX` rule line
+#[derive(Debug, Clone)]
+struct AffixEntry {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+/// A group of affix rules sharing one flag
+#[derive(Debug, Clone)]
+struct AffixClass {
+    cross_product: bool,
+    entries: Vec<AffixEntry>,
+}
+
+/// Parsed `.aff` file
+#[derive(Debug, Default)]
+struct AffixFile {
+    flag_mode: Option<FlagMode>,
+    need_affix: Option<String>,
+    prefixes: HashMap<String, AffixClass>,
+    suffixes: HashMap<String, AffixClass>,
+}
+
+/// Surface forms and noun lemmas produced from a Hunspell dictionary
+#[derive(Debug, Default)]
+pub struct HunspellWords {
+    pub words: BTreeSet<String>,
+    pub nouns: BTreeSet<String>,
+}
+
+/// Expand a Hunspell dictionary into every word form it accepts
+pub fn expand(dic: &str, aff: &str) -> OulipoResult<HunspellWords> {
+    let affixes = parse_aff(aff)?;
+    let flag_mode = affixes.flag_mode.unwrap_or(FlagMode::Char);
+    let mut output = HunspellWords::default();
+
+    for (line_no, line) in dic.lines().enumerate() {
+        let line = line.trim();
+        // The first line holds the approximate entry count
+        if line.is_empty() || (line_no == 0 && line.chars().all(|c| c.is_ascii_digit())) {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let entry = fields.next().unwrap_or_default();
+        let (word, flags) = match entry.split_once('/') {
+            Some((word, flags)) => (word, split_flags(flags, flag_mode)),
+            None => (entry, Vec::new()),
+        };
+        if word.is_empty() {
+            continue;
+        }
+
+        let is_noun = fields.any(|field| {
+            field
+                .strip_prefix("po:")
+                .is_some_and(|tag| NOUN_TAGS.contains(&tag.to_lowercase().as_str()))
+        });
+        if is_noun {
+            output.nouns.insert(word.to_lowercase());
+        }
+
+        for form in expand_word(word, &flags, &affixes) {
+            output.words.insert(form.to_lowercase());
+        }
+    }
+
+    Ok(output)
+}
+
+/// Generate the base form (unless it needs an affix) plus all affixed forms
+fn expand_word(word: &str, flags: &[String], affixes: &AffixFile) -> Vec<String> {
+    let mut forms = Vec::new();
+    let needs_affix = affixes
+        .need_affix
+        .as_ref()
+        .is_some_and(|flag| flags.contains(flag));
+    if !needs_affix {
+        forms.push(word.to_string());
+    }
+
+    let mut cross_suffixed = Vec::new();
+    for flag in flags {
+        if let Some(class) = affixes.suffixes.get(flag) {
+            for entry in &class.entries {
+                if let Some(form) = apply_suffix(word, entry) {
+                    if class.cross_product {
+                        cross_suffixed.push(form.clone());
+                    }
+                    forms.push(form);
+                }
+            }
+        }
+    }
+
+    for flag in flags {
+        if let Some(class) = affixes.prefixes.get(flag) {
+            for entry in &class.entries {
+                if let Some(form) = apply_prefix(word, entry) {
+                    forms.push(form);
+                }
+                if class.cross_product {
+                    forms.extend(cross_suffixed.iter().filter_map(|stem| apply_prefix(stem, entry)));
+                }
+            }
+        }
+    }
+
+    forms
+}
+
+fn apply_suffix(word: &str, entry: &AffixEntry) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < entry.condition.len() || !word.ends_with(&entry.strip) {
+        return None;
+    }
+    let tail = &chars[chars.len() - entry.condition.len()..];
+    if !entry.condition.iter().zip(tail).all(|(part, &ch)| part.matches(ch)) {
+        return None;
+    }
+    Some(format!("{}{}", &word[..word.len() - entry.strip.len()], entry.add))
+}
+
+fn apply_prefix(word: &str, entry: &AffixEntry) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < entry.condition.len() || !word.starts_with(&entry.strip) {
+        return None;
+    }
+    if !entry.condition.iter().zip(&chars).all(|(part, &ch)| part.matches(ch)) {
+        return None;
+    }
+    Some(format!("{}{}", entry.add, &word[entry.strip.len()..]))
+}
+
+fn parse_aff(aff: &str) -> OulipoResult<AffixFile> {
+    let mut file = AffixFile::default();
+
+    for (line_no, line) in aff.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["FLAG", mode, ..] => {
+                file.flag_mode = Some(match mode.to_lowercase().as_str() {
+                    "long" => FlagMode::Long,
+                    "num" => FlagMode::Num,
+                    _ => FlagMode::Char,
+                });
+            }
+            ["NEEDAFFIX", flag, ..] => file.need_affix = Some(flag.to_string()),
+            [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                let table = if *kind == "PFX" { &mut file.prefixes } else { &mut file.suffixes };
+                table.insert(flag.to_string(), AffixClass {
+                    cross_product: *cross == "Y",
+                    entries: Vec::new(),
+                });
+            }
+            [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                let table = if *kind == "PFX" { &mut file.prefixes } else { &mut file.suffixes };
+                let class = table.get_mut(*flag).ok_or_else(|| OulipoError::Dictionary(
+                    format!("Affix rule on line {} uses undeclared flag '{}'", line_no + 1, flag)
+                ))?;
+                // Continuation flags on the added part are not expanded further
+                let add = add.split('/').next().unwrap_or_default();
+                class.entries.push(AffixEntry {
+                    strip: if *strip == "0" { String::new() } else { strip.to_string() },
+                    add: if add == "0" { String::new() } else { add.to_string() },
+                    condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(file)
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut parts = Vec::new();
+    let mut chars = condition.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => parts.push(ConditionPart::Any),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|&c| c != ']').collect();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                parts.push(ConditionPart::Set { chars: set, negated });
+            }
+            other => parts.push(ConditionPart::Literal(other)),
+        }
+    }
+
+    parts
+}
+
+fn split_flags(flags: &str, mode: FlagMode) -> Vec<String> {
+    match mode {
+        FlagMode::Char => flags.chars().map(String::from).collect(),
+        FlagMode::Long => flags
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|pair| pair.iter().collect())
+            .collect(),
+        FlagMode::Num => flags.split(',').map(|f| f.trim().to_string()).collect(),
+    }
+}
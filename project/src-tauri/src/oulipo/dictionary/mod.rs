@@ -0,0 +1,262 @@
+// Dictionary service for word transformations
+use crate::oulipo::{
+    errors::{OulipoError, OulipoResult},
+    utils::{is_plural_of, singular_candidates},
+};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+pub mod hunspell;
+
+/// Built-in English noun lexicon, one noun per line in alphabetical order
+const DEFAULT_NOUNS: &str = include_str!("../data/nouns_en.txt");
+
+/// Built-in English word list, one word per line in alphabetical order
+const DEFAULT_WORDS: &str = include_str!("../data/words_en.txt");
+
+/// Name of the dictionary every service starts with
+pub const DEFAULT_DICTIONARY: &str = "en";
+
+pub struct Dictionary {
+    language: String,
+    /// Every accepted word form, lowercased and in alphabetical order
+    words: BTreeSet<String>,
+    /// Sorted, deduplicated noun lexicon used for N+7 style substitutions
+    nouns: Vec<String>,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        let mut dictionary = Self::with_nouns(DEFAULT_NOUNS.lines());
+        dictionary.add_words(DEFAULT_WORDS.lines());
+        dictionary
+    }
+
+    /// Create an empty dictionary for the given language
+    pub fn empty(language: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            words: BTreeSet::new(),
+            nouns: Vec::new(),
+        }
+    }
+
+    /// Create a dictionary backed by a custom noun lexicon
+    pub fn with_nouns<I, S>(nouns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dictionary = Self::empty(DEFAULT_DICTIONARY);
+        dictionary.set_nouns(nouns);
+        dictionary
+    }
+
+    /// Build a dictionary from a plain word list (one word per line, `#` starts a comment)
+    pub fn from_word_list(language: &str, contents: &str) -> Self {
+        let mut dictionary = Self::empty(language);
+        dictionary.add_words(parse_word_list(contents));
+        dictionary
+    }
+
+    /// Load a plain word list from disk
+    pub fn from_word_list_file(language: &str, path: impl AsRef<Path>) -> OulipoResult<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::from_word_list(language, &contents))
+    }
+
+    /// Build a dictionary from Hunspell `.dic` and `.aff` contents, expanding all affixes
+    ///
+    /// Entries tagged with a noun part of speech (`po:noun`, `po:nom`, ...) also
+    /// populate the noun lexicon.
+    pub fn from_hunspell(language: &str, dic: &str, aff: &str) -> OulipoResult<Self> {
+        let expanded = hunspell::expand(dic, aff)?;
+        let mut dictionary = Self::empty(language);
+        dictionary.words = expanded.words;
+        dictionary.set_nouns(expanded.nouns);
+        Ok(dictionary)
+    }
+
+    /// Load a Hunspell `.dic`/`.aff` pair from disk
+    pub fn from_hunspell_files(
+        language: &str,
+        dic_path: impl AsRef<Path>,
+        aff_path: impl AsRef<Path>,
+    ) -> OulipoResult<Self> {
+        let dic = std::fs::read_to_string(dic_path)?;
+        let aff = std::fs::read_to_string(aff_path)?;
+        Self::from_hunspell(language, &dic, &aff)
+    }
+
+    /// Add words to the dictionary
+    pub fn add_words<I, S>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.words.extend(
+            words
+                .into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty()),
+        );
+    }
+
+    /// Replace the noun lexicon; nouns are also added to the word list
+    pub fn set_nouns<I, S>(&mut self, nouns: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut nouns: Vec<String> = nouns
+            .into_iter()
+            .map(|noun| noun.as_ref().trim().to_lowercase())
+            .filter(|noun| !noun.is_empty())
+            .collect();
+        nouns.sort();
+        nouns.dedup();
+
+        self.words.extend(nouns.iter().cloned());
+        self.nouns = nouns;
+    }
+
+    /// Load a noun lexicon from a plain word list on disk
+    pub fn load_nouns_file(&mut self, path: impl AsRef<Path>) -> OulipoResult<()> {
+        let contents = std::fs::read_to_string(path)?;
+        self.set_nouns(parse_word_list(&contents));
+        Ok(())
+    }
+
+    /// Language tag of this dictionary (e.g. `en_GB`)
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Every known word in alphabetical order
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// Number of known word forms
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// The noun lexicon in alphabetical order
+    pub fn nouns(&self) -> &[String] {
+        &self.nouns
+    }
+
+    /// Position of a noun in the alphabetical lexicon
+    pub fn noun_index(&self, noun: &str) -> Option<usize> {
+        self.nouns.binary_search(&noun.to_lowercase()).ok()
+    }
+
+    /// Check if a word (in its base form) is a known noun
+    pub fn is_noun(&self, word: &str) -> bool {
+        self.noun_index(word).is_some()
+    }
+
+    /// Get the noun `offset` entries after the given noun in alphabetical order
+    ///
+    /// Returns `None` when the word is not in the noun lexicon. The lexicon wraps
+    /// around, so the last nouns map onto the first ones and negative offsets
+    /// walk backwards.
+    pub fn get_n_plus_word(&self, word: &str, offset: i32) -> Option<String> {
+        let index = self.noun_index(word)? as i64;
+        let len = self.nouns.len() as i64;
+        let new_index = (index + offset as i64).rem_euclid(len) as usize;
+        Some(self.nouns[new_index].clone())
+    }
+
+    /// Check if a word exists in the dictionary
+    ///
+    /// Plurals of known nouns are accepted even when the word list only has
+    /// the singular.
+    pub fn contains_word(&self, word: &str) -> bool {
+        let lower = word.trim().to_lowercase();
+        if lower.is_empty() {
+            return false;
+        }
+        self.words.contains(&lower)
+            || singular_candidates(&lower)
+                .iter()
+                .any(|candidate| self.is_noun(candidate) && is_plural_of(&lower, candidate))
+    }
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Named dictionaries with one of them selected as active
+///
+/// Lets a service switch between, say, `en_GB` and `fr_FR` word lists without
+/// reloading them.
+pub struct DictionarySet {
+    dictionaries: HashMap<String, Dictionary>,
+    active: String,
+}
+
+impl DictionarySet {
+    /// Create a set holding only the built-in English dictionary
+    pub fn new() -> Self {
+        let mut dictionaries = HashMap::new();
+        dictionaries.insert(DEFAULT_DICTIONARY.to_string(), Dictionary::new());
+        Self {
+            dictionaries,
+            active: DEFAULT_DICTIONARY.to_string(),
+        }
+    }
+
+    /// Add or replace a named dictionary
+    pub fn insert(&mut self, name: &str, dictionary: Dictionary) {
+        self.dictionaries.insert(name.to_string(), dictionary);
+    }
+
+    /// Make a named dictionary the active one
+    pub fn select(&mut self, name: &str) -> OulipoResult<()> {
+        if !self.dictionaries.contains_key(name) {
+            return Err(OulipoError::Dictionary(format!("Unknown dictionary: {}", name)));
+        }
+        self.active = name.to_string();
+        Ok(())
+    }
+
+    /// Get a dictionary by name
+    pub fn get(&self, name: &str) -> Option<&Dictionary> {
+        self.dictionaries.get(name)
+    }
+
+    /// The currently selected dictionary
+    pub fn active(&self) -> &Dictionary {
+        &self.dictionaries[&self.active]
+    }
+
+    /// Name of the currently selected dictionary
+    pub fn active_name(&self) -> &str {
+        &self.active
+    }
+
+    /// Names of all loaded dictionaries in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.dictionaries.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+}
+
+impl Default for DictionarySet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_word_list(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
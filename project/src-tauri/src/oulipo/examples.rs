@@ -14,11 +14,11 @@ mod tests {
         let service = OulipoService::new();
         
         // Simple univocalic check
-        let result = service.check_univocalic("A cat sat at a mat", 'a')?;
+        let result = service.check_univocalic("A cat sat at a mat", "a")?;
         assert!(result.success);
         
         // This should fail
-        let result = service.check_univocalic("The cat sits", 'a')?;
+        let result = service.check_univocalic("The cat sits", "a")?;
         assert!(!result.success);
         
         Ok(())
@@ -85,6 +85,1112 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_registry_has_builtin_constraints() -> Result<()> {
+        let service = OulipoService::new();
+        let registry = service.constraint_registry();
+        
+        for name in ["univocalic", "lipogram", "palindrome", "snowball", "prisoners",
+                     "sestina", "nina", "villanelle", "pantoum", "terza_rima", "rondeau", "ghazal",
+                     "text_length", "word_count", "character_frequency",
+                     "pangram", "heterogram", "isogram", "letter_quota", "tautogram", "alliteration",
+                     "beau_present", "belle_absente"] {
+            let info = registry.get_constraint_info(name).unwrap();
+            assert_eq!(info.schema["type"], "object");
+        }
+        
+        let constraint = registry.create_constraint("lipogram", &serde_json::json!({ "forbidden_letter": "e" }))?;
+        assert!(constraint.check("A cat sat on a mat")?.success);
+        assert!(registry.create_constraint("sestina", &serde_json::json!({ "end_words": ["one"] })).is_err());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_workflow_combines_registered_constraints() -> Result<()> {
+        let service = OulipoService::new();
+        
+        let workflow = service
+            .create_workflow()
+            .with_lipogram('e')
+            .with_constraint("palindrome", serde_json::json!({}))
+            .with_constraint("character_frequency", serde_json::json!({ "target_char": "a", "max_frequency": 10 }))
+            .build()?;
+        
+        let result = workflow.check("Was it a cat I saw", &service)?;
+        assert_eq!(result.constraint_results.len(), 3);
+        assert!(result.success);
+        
+        let workflow = service.create_workflow().with_constraint("no_such_rule", serde_json::json!({})).build()?;
+        let result = workflow.check("anything", &service)?;
+        assert!(!result.success);
+        assert_eq!(result.constraint_results[0].metadata["error"], "unknown_constraint");
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_n_plus_7_replaces_nouns_only() -> Result<()> {
+        let service = OulipoService::new();
+        let dictionary = service.dictionary();
+        
+        let result = service.n_plus_7_transform("The Cats chased a bird, and they dance.", 7)?;
+        let text = result.result.unwrap();
+        
+        let cat = dictionary.noun_index("cat").unwrap();
+        let bird = dictionary.noun_index("bird").unwrap();
+        let cat_target = &dictionary.nouns()[cat + 7];
+        let bird_target = &dictionary.nouns()[bird + 7];
+        
+        assert!(text.starts_with("The "));
+        assert!(text.contains(&format!("{}, and they dance.", bird_target)));
+        assert!(text.to_lowercase().contains(&crate::oulipo::utils::pluralize(cat_target)));
+        assert_eq!(result.metadata["replacements_made"], 2);
+        assert_eq!(result.metadata["substitutions"][0]["original"], "Cats");
+        
+        // Accented nouns are whole words
+        let french = dictionary::Dictionary::with_nouns(["arbre", "été", "fenêtre", "île", "mer"]);
+        let service = OulipoService::with_dictionary("fr_FR", french);
+        let result = service.n_plus_7_transform("Une fenêtre en été", 1)?;
+        assert_eq!(result.result.as_deref(), Some("Une mer en île"));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_hunspell_dictionary_expansion() -> Result<()> {
+        use crate::oulipo::dictionary::Dictionary;
+        
+        let aff = "SET UTF-8\nSFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\nPFX U Y 1\nPFX U 0 un .\n";
+        let dic = "3\ncity/S po:noun\nlock/SU po:noun\nkind/U\n";
+        let dictionary = Dictionary::from_hunspell("en_GB", dic, aff)?;
+        
+        for word in ["city", "cities", "lock", "locks", "unlock", "unlocks", "unkind"] {
+            assert!(dictionary.contains_word(word), "missing {}", word);
+        }
+        assert!(!dictionary.contains_word("citys"));
+        assert!(!dictionary.contains_word("kinds"));
+        assert_eq!(dictionary.nouns(), ["city".to_string(), "lock".to_string()]);
+        
+        let mut service = OulipoService::new();
+        assert!(service.dictionary().contains_word("lighthouse"));
+        assert!(!service.dictionary().contains_word("qwzx"));
+        service.add_dictionary("fr_FR", Dictionary::from_word_list("fr_FR", "# mots\nmaison\nchat\n"));
+        service.select_dictionary("fr_FR")?;
+        assert!(service.dictionary().contains_word("Maison"));
+        assert!(service.select_dictionary("de_DE").is_err());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_word_anagram_solver() -> Result<()> {
+        use crate::oulipo::generators::anagram::AnagramOptions;
+        
+        let service = OulipoService::new();
+        
+        let anagrams = service.generate_anagrams("listen", 5)?;
+        assert!(anagrams.contains(&"silent".to_string()));
+        
+        let options = AnagramOptions {
+            max_results: 20,
+            min_word_length: 3,
+            required_words: vec!["moon".to_string()],
+            excluded_words: vec!["stare".to_string()],
+            ..Default::default()
+        };
+        let anagrams = service.generate_anagrams_with_options("moon starer", &options)?;
+        assert!(!anagrams.is_empty());
+        for anagram in &anagrams {
+            assert!(anagram.starts_with("moon "));
+            assert!(!anagram.split(' ').any(|w| w == "stare" || w.len() < 3));
+            assert!(service.check_anagram(anagram, "moon starer")?);
+        }
+        
+        // Long phrases must finish quickly thanks to the search budget
+        let started = std::time::Instant::now();
+        service.generate_anagrams("the quick brown fox jumps over", 10)?;
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        
+        // Hundreds of one letter are counted without overflowing
+        service.generate_anagrams(&"a".repeat(300), 5)?;
+        assert!(!service.check_anagram(&"a".repeat(300), &"a".repeat(299))?);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_haiku_syllables() -> Result<()> {
+        let service = OulipoService::new();
+        
+        assert_eq!(service.count_syllables("poem"), 2);
+        assert_eq!(service.count_syllables("The old pond"), 3);
+        // Words outside the lexicon fall back to estimation
+        assert_eq!(phonetics::estimate_syllables("candle"), 2);
+        assert_eq!(phonetics::estimate_syllables("stones"), 1);
+        
+        let haiku = "An old silent pond\nA frog jumps into the pond\nSplash! Silence again";
+        let result = service.check_haiku(haiku)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["line_syllables"], serde_json::json!([5, 7, 5]));
+        
+        // A short middle line is reported against that line
+        let result = service.check_haiku("An old silent pond\nA frog jumps\nSplash! Silence again")?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, haiku.find("A frog").unwrap());
+        
+        for theme in ["nature", "seasons", "love", "time", "anything"] {
+            let generated = service.generate_haiku(Some(theme), None)?;
+            assert!(service.check_haiku(&generated)?.success, "{}", generated);
+        }
+        
+        let tanka = service.create_custom_constraint("haiku", serde_json::json!({"pattern": [5, 7, 5, 7, 7]}))?;
+        assert!(!tanka.check(haiku)?.success);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_loaded_lexicon_reaches_registry() -> Result<()> {
+        let lexicon = "ZZYZX  Z IH1 Z IH0 K S AH0\nQWOO  K W UW1\nZHRUE  ZH R UW1\nPHLIM  P L IH1 M\n";
+        let path = std::env::temp_dir().join(format!("oulipo_lexicon_{}.dict", std::process::id()));
+        std::fs::write(&path, lexicon)?;
+        let mut service = OulipoService::new();
+        let config = serde_json::json!({ "pattern": [3] });
+        let before = service.create_custom_constraint("haiku", config.clone())?.check("zzyzx")?;
+        let couplet = serde_json::json!({ "scheme": "AA" });
+        let before_rhyme = service.create_custom_constraint("rhyme_scheme", couplet.clone())?.check("a qwoo\na zhrue")?;
+        let sound = serde_json::json!({ "match": "sound" });
+        let before_tautogram = service.create_custom_constraint("tautogram", sound.clone())?.check("phlim pat")?;
+        let before_alliteration = service.create_custom_constraint("alliteration", serde_json::json!({}))?.check("phlim pat")?;
+        let before_meter = service
+            .create_custom_constraint("meter", serde_json::json!({ "foot": "iamb", "feet": 1 }))?
+            .check("zzyzx ox")?;
+        service.load_pronunciations(path.to_str().unwrap())?;
+        std::fs::remove_file(&path)?;
+        
+        // Registry constraints count with the loaded lexicon, like the service's own checks
+        assert_eq!(service.count_syllables("zzyzx"), 3);
+        assert!(!before.success);
+        assert!(service.create_custom_constraint("haiku", config.clone())?.check("zzyzx")?.success);
+        let update = service.open_check_session("zzyzx", "haiku", config, None)?;
+        assert!(update.success);
+        
+        // Scanned with the loaded lexicon, the line is too long for one iamb
+        let iambic = serde_json::json!({ "foot": "iamb", "feet": 1 });
+        assert!(before_meter.success);
+        assert!(!service.create_custom_constraint("meter", iambic)?.check("zzyzx ox")?.success);
+        
+        // Spelled apart, the two words rhyme once their sounds are known
+        assert!(!before_rhyme.success);
+        assert!(service.create_custom_constraint("rhyme_scheme", couplet)?.check("a qwoo\na zhrue")?.success);
+        
+        // Initial sounds come from the loaded lexicon: this 'ph' is a /p/
+        assert!(!before_tautogram.success && !before_alliteration.success);
+        assert!(service.create_custom_constraint("tautogram", sound)?.check("phlim pat")?.success);
+        assert!(service.create_custom_constraint("alliteration", serde_json::json!({}))?.check("phlim pat")?.success);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_meter_scansion() -> Result<()> {
+        use crate::oulipo::constraints::meter::Substitution;
+        let service = OulipoService::new();
+        
+        let gray = "The curfew tolls the knell of parting day\nShall I compare thee to a summer's day?";
+        assert!(service.check_meter(gray, "iambic pentameter", &[])?.success);
+        
+        // A trochaic opening breaks strict iambs but passes as an initial inversion
+        let inverted = "Parting the curfew tolls the knell of day";
+        let result = service.check_meter(inverted, "iambic pentameter", &[])?;
+        assert_eq!(result.violations.len(), 2);
+        assert!(result.violations.iter().all(|v| v.position == 0 && v.length == "Parting".len()));
+        let result = service.check_meter(inverted, "iambic pentameter", &[Substitution::InitialInversion])?;
+        assert!(result.success);
+        assert_eq!(result.metadata["lines"][0]["substitutions"], serde_json::json!(["initial_inversion"]));
+        
+        let hamlet = "To be, or not to be, that is the question";
+        assert!(!service.check_meter(hamlet, "iambic pentameter", &[])?.success);
+        assert!(service.check_meter(hamlet, "iambic pentameter", &[Substitution::FeminineEnding])?.success);
+        
+        // Blake's trochaic tetrameter drops its final unstressed syllable
+        let blake = service.create_custom_constraint("meter", serde_json::json!({
+            "foot": "trochee",
+            "feet": 4,
+            "substitutions": ["catalexis"]
+        }))?;
+        assert!(blake.check("Tiger tiger burning bright")?.success);
+        assert!(service.create_custom_constraint("meter", serde_json::json!({"meter": "iambic octopus"})).is_err());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_rhyme_scheme() -> Result<()> {
+        use crate::oulipo::rhyme::RhymeLevel;
+        let service = OulipoService::new();
+        
+        assert_eq!(service.compare_rhyme("Day", "day"), RhymeLevel::Identical);
+        assert_eq!(service.compare_rhyme("night", "light"), RhymeLevel::Perfect);
+        assert_eq!(service.compare_rhyme("soul", "all"), RhymeLevel::Slant);
+        assert_eq!(service.compare_rhyme("cough", "bough"), RhymeLevel::Eye);
+        assert_eq!(service.compare_rhyme("sun", "world"), RhymeLevel::None);
+        
+        let quatrain = "The sun goes down at end of day,\nThe stars come out to light the night;\nThe children run along the way\nAnd all the world is calm and bright.";
+        let result = service.check_rhyme_scheme(quatrain, "ABAB", RhymeLevel::Perfect)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["detected_scheme"], "ABAB");
+        
+        let broken = quatrain.replace("bright", "still");
+        let result = service.check_rhyme_scheme(&broken, "ABAB", RhymeLevel::Perfect)?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, broken.find("still").unwrap());
+        assert_eq!(result.metadata["detected_scheme"], "ABAC");
+        
+        // Slant rhymes only pass when the constraint allows them
+        let slant = "I give my heart and soul\nTo one who gave it all";
+        let strict = service.create_custom_constraint("rhyme_scheme", serde_json::json!({"scheme": "AA"}))?;
+        assert!(!strict.check(slant)?.success);
+        let loose = service.create_custom_constraint("rhyme_scheme", serde_json::json!({"scheme": "AA", "min_level": "slant"}))?;
+        assert!(loose.check(slant)?.success);
+        assert!(service.create_custom_constraint("rhyme_scheme", serde_json::json!({"scheme": "AB-AB"})).is_err());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_forms() -> Result<()> {
+        use crate::oulipo::rhyme::RhymeLevel;
+        let service = OulipoService::new();
+        let stanzas = |stanzas: &[&[&str]]| stanzas.iter().map(|s| s.join("\n")).collect::<Vec<_>>().join("\n\n");
+        
+        let (a1, a2) = ("The lamp is burning through the night", "We keep the watch until the light");
+        let villanelle = stanzas(&[
+            &[a1, "We wait beside the fire all day", a2],
+            &["The stars above are cold and bright", "The road is long along the way", a1],
+            &["The moon is small and very white", "The children ask if they can stay", a2],
+            &["The owls are calling out of sight", "The old ones sit and have their say", a1],
+            &["The birds have settled from their flight", "The kittens sleep and will not play", a2],
+            &["The hills are lost in all their height", "The dawn is slow and cold and gray", a1, a2],
+        ]);
+        let result = service.check_villanelle(&villanelle, RhymeLevel::Perfect)?;
+        assert!(result.success, "{:?}", result.violations);
+        
+        // Line 12 drifts from refrain A1
+        let drifted = villanelle.replacen(&format!("say\n{}", a1), "say\nThe lamp is burning in the night", 1);
+        let result = service.check_villanelle(&drifted, RhymeLevel::Perfect)?;
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].issue.contains("Line 12"));
+        
+        let (l1, l2, l3, l4, l5, l6) = ("Rain on the roof", "A kettle sings", "The cat asleep", "Nobody calls", "The clock runs slow", "The garden waits");
+        let pantoum = stanzas(&[&[l1, l2, l3, l4], &[l2, l5, l4, l6], &[l5, l3, l6, l1]]);
+        assert!(service.check_pantoum(&pantoum, true)?.success);
+        let broken = stanzas(&[&[l1, l2, l3, l4], &[l2, l5, l6, l4]]);
+        let result = service.check_pantoum(&broken, false)?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, broken.rfind(l6).unwrap());
+        
+        let terza = stanzas(&[
+            &["I walked alone into the night", "And wandered far until the day", "Had filled the valley full of light"],
+            &["I followed every winding way", "Down to the edges of the sea", "And there I thought that I would stay"],
+            &["The water seemed to call to me", "So cold and wide and very white", "And I was tired and I was free"],
+            &["The morning came up clear and bright", "And all the world was in my sight"],
+        ]);
+        assert!(service.check_terza_rima(&terza, RhymeLevel::Perfect)?.success);
+        let broken = terza.replace("would stay", "would be");
+        let result = service.check_terza_rima(&broken, RhymeLevel::Perfect)?;
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].issue.contains("'be'"));
+        
+        let rondeau = stanzas(&[
+            &["Beneath the lamp we wait all night", "And watch the stars grow cold and white", "We do not speak of all the day",
+              "Or of the friends who could not stay", "We only watch the fading light"],
+            &["The moon goes down beyond our sight", "The owls have ended every flight", "The dark is slow to turn to gray", "Beneath the lamp"],
+            &["We hold our breath until the light", "And all the hills have lost their height", "The children wake and want to play",
+              "And there is little left to say", "Until the sun is up and bright", "Beneath the lamp"],
+        ]);
+        let result = service.check_rondeau(&rondeau, RhymeLevel::Perfect)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["rentrement"], "beneath the lamp");
+        let result = service.check_rondeau(&rondeau.replacen("Beneath the lamp\n", "Under the lamp\n", 1), RhymeLevel::Perfect)?;
+        assert!(result.violations[0].issue.contains("Line 9"));
+        
+        let ghazal = stanzas(&[
+            &["The lamp is burning bright tonight", "The stars are cold and white tonight"],
+            &["We wait beside the fire and sing", "The moon is lost from sight tonight"],
+            &["The owls are calling in the dark", "The birds have ended flight tonight"],
+            &["The children dream of summer days", "The hills have lost their height tonight"],
+            &["I write these couplets in the cold", "And hold the last of light tonight"],
+        ]);
+        let result = service.check_ghazal(&ghazal, None, RhymeLevel::Slant)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["radif"], "tonight");
+        let result = service.check_ghazal(&ghazal.replace("flight tonight", "flight"), None, RhymeLevel::Slant)?;
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].issue.contains("Couplet 3"));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_nina_skeleton_and_checks() -> Result<()> {
+        use crate::oulipo::constraints::nina::{is_valid_n, spiral_permutation};
+        let service = OulipoService::new();
+        let words = |ws: &[&str]| ws.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        
+        assert_eq!(spiral_permutation(6), vec![5, 0, 4, 1, 3, 2]);
+        assert!([1, 2, 3, 5, 6, 9, 11].iter().all(|&n| is_valid_n(n)));
+        assert!([4, 7, 8, 10].iter().all(|&n| !is_valid_n(n)));
+        
+        let sestina = words(&["stone", "river", "light", "bread", "window", "hand"]);
+        let skeleton = service.generate_nina_skeleton(&sestina)?;
+        assert_eq!(skeleton.lines().filter(|l| !l.is_empty()).count(), 39);
+        assert!(service.check_sestina(&skeleton, &sestina)?.success);
+        
+        let quenine = words(&["salt", "wind", "door", "moon", "ash"]);
+        let skeleton = service.generate_nina_skeleton(&quenine)?;
+        let result = service.check_nina(&skeleton, &quenine)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["permutation"], serde_json::json!([5, 1, 4, 2, 3]));
+        
+        // Stanza 2 opens on the last end word
+        let broken = skeleton.replacen("__________ ash\n__________ salt", "__________ ember\n__________ salt", 1);
+        let result = service.check_nina(&broken, &quenine)?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, broken.find("ember").unwrap());
+        
+        let envoi_start = skeleton.rfind("\n\n").unwrap();
+        let no_moon = format!("{}{}", &skeleton[..envoi_start], skeleton[envoi_start..].replace("moon", "sun"));
+        let result = service.check_nina(&no_moon, &quenine)?;
+        assert!(result.violations.iter().any(|v| v.issue.contains("'moon'")));
+        
+        assert!(service.generate_nina_skeleton(&words(&["a", "b", "c", "d"])).is_err());
+        let registry = service.constraint_registry();
+        assert!(registry.create_constraint("nina", &serde_json::json!({ "end_words": ["a", "b", "c", "d"] })).is_err());
+        assert!(registry.create_constraint("nina", &serde_json::json!({ "end_words": ["a", "b", "c"] })).is_ok());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_palindrome_modes_and_builder() -> Result<()> {
+        use crate::oulipo::constraints::PalindromeMode;
+        use crate::oulipo::generators::palindrome::PalindromeOptions;
+        let service = OulipoService::new();
+        
+        assert!(service.check_palindrome("A man, a plan, a canal: Panama!")?.success);
+        let text = "Never odd or even";
+        let result = service.check_palindrome_with_mode(text, PalindromeMode::Word)?;
+        assert!(!result.success);
+        assert_eq!(result.violations[0].position, 0);
+        assert_eq!(result.violations[1].position, text.find("even").unwrap());
+        assert!(service.check_palindrome(text)?.success);
+        
+        let text = "Fall leaves after leaves fall";
+        assert!(service.check_palindrome_with_mode(text, PalindromeMode::Word)?.success);
+        assert!(!service.check_palindrome(text)?.success);
+        let broken = "Fall leaves after the leaves fall";
+        let result = service.check_palindrome_with_mode(broken, PalindromeMode::Word)?;
+        assert_eq!(result.metadata["mirrored_pairs"], 2);
+        assert_eq!(result.violations[0].position, broken.find("after").unwrap());
+        assert_eq!(result.violations[1].position, broken.find("the").unwrap());
+        
+        // Letter violations point at the outermost mismatched pair in the original text
+        let result = service.check_palindrome("Race a car!")?;
+        assert_eq!(result.violations.len(), 2);
+        assert_eq!(result.violations[0].position, 3);
+        assert_eq!(result.violations[1].position, 5);
+        
+        let poem = "The tide goes out\nA gull is crying\nThe tide goes out";
+        assert!(service.check_palindrome_with_mode(poem, PalindromeMode::Line)?.success);
+        let result = service.check_palindrome_with_mode("The tide goes out\nA gull\n  The tide comes in", PalindromeMode::Line)?;
+        assert_eq!(result.violations[1].position, 27);
+        assert_eq!(result.violations[1].length, "The tide comes in".len());
+        
+        let constraint = service.constraint_registry().create_constraint("palindrome", &serde_json::json!({ "mode": "word" }))?;
+        assert!(constraint.check("Fall leaves after leaves fall")?.success);
+        assert!(service.constraint_registry().create_constraint("palindrome", &serde_json::json!({ "mode": "stanza" })).is_err());
+        
+        let palindromes = service.generate_palindromes("top", &PalindromeOptions::default())?;
+        assert!(palindromes.contains(&"top spot".to_string()), "{:?}", palindromes);
+        for phrase in &palindromes {
+            assert!(phrase.split(' ').any(|w| w == "top"), "{}", phrase);
+            assert!(service.check_palindrome(phrase)?.success, "{}", phrase);
+        }
+        let options = PalindromeOptions { max_words: 1, ..Default::default() };
+        let palindromes = service.generate_palindromes("star", &options)?;
+        assert!(palindromes.contains(&"star rats".to_string()), "{:?}", palindromes);
+        assert!(palindromes.iter().all(|p| p.split(' ').count() == 2));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_snowball_variants_and_generator() -> Result<()> {
+        use crate::oulipo::constraints::SnowballVariant;
+        use crate::oulipo::generators::snowball::{generate_with, SnowballOptions};
+        use rand::{rngs::StdRng, SeedableRng};
+        let service = OulipoService::new();
+        
+        assert!(service.check_snowball("I am the best, truly!")?.success);
+        let text = "Frozen lakes hold wind in a";
+        let result = service.check_snowball_variant(text, SnowballVariant::Melting, None)?;
+        assert!(!result.success);
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, text.find("wind").unwrap());
+        assert!(service.check_snowball_variant("Frozen lakes hold ice, in a", SnowballVariant::Melting, None)?.success);
+        
+        assert!(service.check_snowball_variant("I am the best one in a", SnowballVariant::Diamond, None)?.success);
+        let result = service.check_snowball_variant("I am the best one in", SnowballVariant::Diamond, None)?;
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].issue.contains("melt back"));
+        
+        let lines = "Snow\nSnow falls\n  Snow falls softly here";
+        let result = service.check_snowball_variant(lines, SnowballVariant::Lines, None)?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, lines.find("  Snow falls softly").unwrap());
+        
+        let constraint = service.constraint_registry().create_constraint("snowball", &serde_json::json!({ "variant": "melting" }))?;
+        assert!(constraint.check("Frozen lakes hold ice, in a")?.success);
+        
+        let mut rng = StdRng::seed_from_u64(7);
+        let options = SnowballOptions { start_length: 1, end_length: 7, theme_words: vec!["winter".to_string()], ..Default::default() };
+        let result = generate_with(service.dictionary(), &options, &mut rng)?;
+        assert!(result.success, "{:?}", result);
+        let text = result.result.unwrap();
+        assert!(text.split(' ').any(|w| w == "winter"), "{}", text);
+        assert!(service.check_snowball(&text)?.success);
+        
+        let melting = service.generate_snowball(&SnowballOptions { start_length: 7, end_length: 2, theme_words: vec![], ..Default::default() })?;
+        assert!(service.check_snowball_variant(&melting, SnowballVariant::Melting, Some(7))?.success, "{}", melting);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_letter_inventory_constraints() -> Result<()> {
+        let service = OulipoService::new();
+        
+        assert!(service.check_pangram("The quick brown fox jumps over the lazy dog")?.success);
+        let text = "The quick brown fox jumps over the dog";
+        let result = service.check_pangram(text)?;
+        assert_eq!(result.metadata["missing_letters"], serde_json::json!(["a", "l", "y", "z"]));
+        assert!(result.violations.iter().all(|v| v.position == text.len()));
+        
+        assert!(service.check_heterogram("The big dwarf only jumps")?.success);
+        let text = "Uncopyrightable letters";
+        let result = service.check_heterogram(text)?;
+        assert!(!result.success);
+        let first_repeat = result.violations.iter().map(|v| v.position).min().unwrap();
+        assert_eq!(first_repeat, text.find("letters").unwrap());
+        assert!(result.metadata["over_used_letters"].as_array().unwrap().contains(&serde_json::json!("e")));
+        
+        assert!(service.check_isogram("Caucasus", 2)?.success);
+        let result = service.check_isogram("Caucasian", 2)?;
+        assert_eq!(result.metadata["over_used_letters"], serde_json::json!(["a"]));
+        assert_eq!(result.metadata["under_used_letters"], serde_json::json!(["i", "n", "s", "u"]));
+        assert_eq!(result.violations[0].position, 2);
+        assert!(result.violations.iter().any(|v| v.position == 7 && v.issue.contains("'a' used 3 times")));
+        
+        let quotas = serde_json::from_value(serde_json::json!({ "e": { "max": 2 }, "z": { "min": 1 } }))?;
+        let text = "Every evening";
+        let result = service.check_letter_quotas(text, quotas)?;
+        let over: Vec<usize> = result.violations.iter().filter(|v| v.length == 1).map(|v| v.position).collect();
+        assert_eq!(over, vec![6, 8]);
+        assert_eq!(result.metadata["missing_letters"], serde_json::json!(["z"]));
+        
+        let registry = service.constraint_registry();
+        assert!(registry.create_constraint("isogram", &serde_json::json!({ "n": 0 })).is_err());
+        assert!(registry.create_constraint("letter_quota", &serde_json::json!({ "quotas": { "ab": { "max": 1 } } })).is_err());
+        assert!(registry.create_constraint("letter_quota", &serde_json::json!({ "quotas": { "a": { "min": 3, "max": 1 } } })).is_err());
+        let heterogram = registry.create_constraint("heterogram", &serde_json::json!({}))?;
+        assert!(heterogram.check("Dermatoglyphics")?.success);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_tautogram_and_alliteration() -> Result<()> {
+        use crate::oulipo::constraints::InitialMatch;
+        
+        let service = OulipoService::new();
+        
+        let result = service.check_tautogram("Five fluffy foxes fight for food", None, InitialMatch::Letter, false)?;
+        assert!(result.success);
+        assert_eq!(result.metadata["target"], "f");
+        
+        let text = "Five fluffy foxes fight the philosophers";
+        let strict = service.check_tautogram(text, Some("f"), InitialMatch::Letter, false)?;
+        let offending: Vec<&str> = strict.violations.iter().map(|v| &text[v.position..v.position + v.length]).collect();
+        assert_eq!(offending, vec!["the", "philosophers"]);
+        let loose = service.check_tautogram(text, Some("f"), InitialMatch::Letter, true)?;
+        assert_eq!(loose.violations.len(), 1);
+        assert_eq!(loose.metadata["skipped_words"], serde_json::json!(["the"]));
+        assert!(service.check_tautogram(text, Some("f"), InitialMatch::Sound, true)?.success);
+        
+        let result = service.check_alliteration("Peter Piper picked a peck of pickled peppers", None, InitialMatch::Sound)?;
+        assert!(result.success);
+        assert_eq!(result.metadata["skipped_words"], serde_json::json!(["a", "of"]));
+        
+        let text = "Kind knights keep castles";
+        let by_sound = service.check_alliteration(text, None, InitialMatch::Sound)?;
+        assert_eq!(by_sound.violations.len(), 1);
+        assert_eq!(by_sound.violations[0].position, text.find("knights").unwrap());
+        let by_letter = service.check_alliteration(text, None, InitialMatch::Letter)?;
+        assert_eq!(by_letter.violations[0].position, text.find("castles").unwrap());
+        
+        let workflow = service.create_workflow().with_tautogram('f').with_loose_tautogram('f').build()?;
+        assert!(workflow.check("Five fluffy foxes", &service)?.success);
+        let workflow = service.create_workflow().with_alliteration('p').build()?;
+        assert!(!workflow.check("Peter Piper picked a bushel", &service)?.success);
+        
+        let registry = service.constraint_registry();
+        assert!(registry.create_constraint("tautogram", &serde_json::json!({ "match": "rhyme" })).is_err());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_beau_present_and_belle_absente() -> Result<()> {
+        use crate::oulipo::constraints::{letter_inventory::ENGLISH_ALPHABET, BelleAbsenteUnit};
+        let service = OulipoService::new();
+        
+        assert!(service.check_beau_present("No, Sara soars on", "Anna Ross")?.success);
+        let result = service.check_beau_present("Sara sees", "Anna Ross")?;
+        assert_eq!(result.violations.iter().map(|v| v.position).collect::<Vec<_>>(), vec![6, 7]);
+        assert_eq!(result.metadata["disallowed_letters"], serde_json::json!(["e"]));
+        assert!(service.check_beau_present("anything", "42").is_err());
+        
+        let without = |letter: char| ENGLISH_ALPHABET.chars().filter(|&c| c != letter).collect::<String>();
+        let poem = format!("{}\n{}\n{}", without('e'), without('v'), without('a'));
+        let result = service.check_belle_absente(&poem, "Eva", BelleAbsenteUnit::Line)?;
+        assert!(result.success, "{:?}", result.violations);
+        assert_eq!(result.metadata["spelled"], "eva");
+        
+        let stanzas = poem.replace('\n', "\n\n");
+        assert!(service.check_belle_absente(&stanzas, "Eva", BelleAbsenteUnit::Stanza)?.success);
+        
+        let broken = format!("{}\n{}\n{}", without('e'), without('v').replace('q', ""), ENGLISH_ALPHABET);
+        let result = service.check_belle_absente(&broken, "Eva", BelleAbsenteUnit::Line)?;
+        assert!(result.violations[0].issue.starts_with("Line 2 breaks the hidden-name sequence"));
+        assert_eq!(result.violations[1].position, broken.rfind(ENGLISH_ALPHABET).unwrap());
+        assert_eq!(result.metadata["spelled"], "e??");
+        
+        let result = service.check_belle_absente(&without('e'), "Eva", BelleAbsenteUnit::Line)?;
+        assert!(result.violations.iter().any(|v| v.issue.contains("needs 3 lines")));
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_lipogram_rewrite() -> Result<()> {
+        use crate::oulipo::generators::lipogram::{LipogramEdit, LipogramRewriteOptions};
+        let service = OulipoService::new();
+        
+        let text = "The brave queen sleeps among old trees";
+        let options = LipogramRewriteOptions { full_rewrite: true, ..Default::default() };
+        let result = service.rewrite_lipogram(text, "e", &options)?;
+        let edits: Vec<LipogramEdit> = serde_json::from_value(result.metadata["edits"].clone())?;
+        let originals: Vec<&str> = edits.iter().map(|e| e.original.as_str()).collect();
+        assert_eq!(originals, vec!["brave", "queen", "sleeps", "trees"]);
+        assert!(edits.iter().all(|e| text[e.position..e.position + e.length] == e.original));
+        assert!(edits.iter().all(|e| e.alternatives.windows(2).all(|w| w[0].score >= w[1].score)));
+        assert_eq!(edits[0].replacement, "bold");
+        assert_eq!(edits[3].replacement, "oaks");
+        
+        // "The" has no synonym, so it is left for the writer
+        assert!(!result.success);
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, 0);
+        let rewritten = result.result.unwrap();
+        assert!(rewritten.starts_with("The bold monarch"), "{}", rewritten);
+        
+        let result = service.rewrite_lipogram("A brave king", "e, a", &LipogramRewriteOptions::default())?;
+        let edits: Vec<LipogramEdit> = serde_json::from_value(result.metadata["edits"].clone())?;
+        assert_eq!(edits[0].replacement, "bold");
+        assert_eq!(result.metadata["forbidden_letters"], serde_json::json!(["e", "a"]));
+        assert!(service.rewrite_lipogram(text, "", &options).is_err());
+        
+        let suggestions = service.generate_lipogram_suggestions("Brave hearts", "e")?;
+        assert!(suggestions[0].starts_with("Replace 'Brave' with 'Bold'"), "{:?}", suggestions);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_thesaurus_lookups() -> Result<()> {
+        use crate::oulipo::thesaurus::{PartOfSpeech, Thesaurus};
+        let service = OulipoService::new();
+        let thesaurus = service.thesaurus();
+        
+        // Moby synonyms come first, WordNet synset members after them
+        let synonyms = thesaurus.synonyms("sleep", None);
+        assert_eq!(synonyms[0], "nap");
+        assert!(synonyms.contains(&"slumber".to_string()));
+        assert_eq!(thesaurus.synonyms("sleep", Some(PartOfSpeech::Verb)), vec!["slumber", "doze"]);
+        assert!(thesaurus.synonyms("sleep", Some(PartOfSpeech::Noun)).is_empty());
+        
+        assert_eq!(thesaurus.antonyms("happy", None), vec!["sad"]);
+        assert_eq!(thesaurus.antonyms("glad", None), Vec::<String>::new());
+        assert_eq!(thesaurus.hypernyms("dog", Some(PartOfSpeech::Noun)), vec!["canine", "canid"]);
+        assert!(thesaurus.synonyms("huge", Some(PartOfSpeech::Adjective)).contains(&"big".to_string()));
+        assert_eq!(thesaurus.parts_of_speech("love"), vec![PartOfSpeech::Noun, PartOfSpeech::Verb]);
+        
+        let entry = service.lookup_word("Love", Some(PartOfSpeech::Verb));
+        assert_eq!(entry.word, "love");
+        assert_eq!(entry.antonyms, vec!["hate"]);
+        assert_eq!(entry.senses.len(), 1);
+        
+        let mut custom = Thesaurus::empty();
+        custom.extend_from_wordnet(
+            "  licence line\n\
+             00000010 00 a 02 up(p) 0 upward 0 001 ! 00000020 a 0101 | directed up\n\
+             00000020 00 a 01 down 0 001 ! 00000010 a 0101 | directed down\n\
+             not a synset line"
+        );
+        custom.extend_from_moby("up,aloft,above");
+        assert_eq!(custom.len(), 3);
+        assert_eq!(custom.antonyms("up", None), vec!["down"]);
+        assert_eq!(custom.synonyms("up", None), vec!["upward", "aloft", "above"]);
+        assert!(custom.synonyms("missing", None).is_empty());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_combinatorial_book() -> Result<()> {
+        use crate::oulipo::generators::combinatorial::CombinatorialBook;
+        use crate::oulipo::rhyme::{RhymeEngine, RhymeLevel};
+        use rand::{rngs::StdRng, SeedableRng};
+        let sources = [
+            "The morning starts with day\nThe stars go out at night\nThe children run to play\nThe lamps give off their light",
+            "I wander on my way\nI sit and watch the light\nI hear what people say\nI sleep in bed at night",
+            "We shall not stay\nWe hold each other tight\nWe kneel and then we pray\nWe sing with all our might",
+        ];
+        let book = CombinatorialBook::from_texts(&sources)?;
+        assert_eq!(book.source_count(), 3);
+        assert_eq!(book.line_count(), 4);
+        assert_eq!(book.total(), 81);
+        
+        // Index 0 is the first source; the last line varies fastest
+        assert_eq!(book.poem(0).as_deref(), Some(sources[0]));
+        assert_eq!(book.choices(5), Some(vec![0, 0, 1, 2]));
+        assert_eq!(book.index_of(&[2, 0, 1, 2]), Some(2 * 27 + 5));
+        assert_eq!(book.choices(81), None);
+        for index in [0, 17, 80] {
+            assert_eq!(book.index_of(&book.choices(index).unwrap()), Some(index));
+        }
+        
+        let page = book.enumerate(79, 5);
+        assert_eq!(page.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![79, 80]);
+        assert!(page[1].1.ends_with("We sing with all our might"));
+        
+        let first = book.sample(10, &mut StdRng::seed_from_u64(7));
+        assert_eq!(first, book.sample(10, &mut StdRng::seed_from_u64(7)));
+        assert_eq!(first.iter().collect::<std::collections::BTreeSet<_>>().len(), 10);
+        assert_eq!(book.sample(200, &mut StdRng::seed_from_u64(7)).len(), 81);
+        
+        let check = book.check_interchangeable(&RhymeEngine::new(), RhymeLevel::Perfect);
+        assert!(check.success, "{:?}", check.violations);
+        assert_eq!(check.metadata["rhyme_scheme"], "ABAB");
+        
+        // A line that breaks the shared rhyme is reported in its own source
+        let broken = CombinatorialBook::from_texts(&[sources[0], "I wander on my way\nI sit and watch the sea\nI hear what people say\nI sleep in bed at night"])?;
+        let check = broken.check_interchangeable(&RhymeEngine::new(), RhymeLevel::Perfect);
+        assert_eq!(check.violations.len(), 1);
+        assert!(check.violations[0].issue.starts_with("Source 2, line 2 ends in 'sea'"));
+        assert_eq!(check.violations[0].position, "I wander on my way\nI sit and watch the ".len());
+        
+        assert!(CombinatorialBook::from_texts(&[sources[0], "Too short\nby far"]).is_err());
+        assert!(CombinatorialBook::new(vec![vec!["line".to_string(); 14]; 30]).is_err());
+        
+        let service = OulipoService::new();
+        let sets: Vec<Vec<String>> = sources.iter().map(|s| s.lines().map(str::to_string).collect()).collect();
+        let poem = service.combinatorial_poem(&sets, Some(80), None)?;
+        assert_eq!(poem.result.as_deref(), Some(sources[2]));
+        assert_eq!(poem.metadata["line_sources"], serde_json::json!([3, 3, 3, 3]));
+        assert!(service.combinatorial_poem(&sets, Some(81), None).is_err());
+        assert_eq!(service.generate_combinatorial_poem(&sets, Some("queneau"), Some(3))?.lines().count(), 4);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_generators_are_reproducible() -> Result<()> {
+        use crate::oulipo::generators::{self, anagram::AnagramOptions, palindrome::PalindromeOptions, snowball::SnowballOptions};
+        use crate::oulipo::phonetics::PronunciationDictionary;
+        let service = OulipoService::new();
+        let pronunciations = PronunciationDictionary::shared();
+        let words: Vec<String> = "the quiet river runs past old stones under a pale moon".split(' ').map(str::to_string).collect();
+        let sources = vec![
+            vec!["The day is long".to_string(), "The night is cold".to_string()],
+            vec!["We sing a song".to_string(), "The tale is told".to_string()],
+        ];
+        let book = generators::combinatorial::CombinatorialBook::new(sources.clone())?;
+        
+        // For any seed, running twice gives the same piece and records the seed
+        for seed in (0..40u64).map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15)) {
+            let haiku = generators::haiku::generate("nature", &pronunciations, Some(seed))?;
+            assert_eq!(haiku.result, generators::haiku::generate("nature", &pronunciations, Some(seed))?.result);
+            assert_eq!(haiku.metadata["random_seed"], seed);
+            
+            let options = SnowballOptions { start_length: 1, end_length: 6, seed: Some(seed), ..Default::default() };
+            let snowball = generators::snowball::generate(service.dictionary(), &options)?;
+            assert_eq!(snowball.result, generators::snowball::generate(service.dictionary(), &options)?.result);
+            assert_eq!(snowball.metadata["random_seed"], seed);
+            
+            let poem = generators::combinatorial::generate_combinatorial_poem(words.clone(), "random", Some(seed))?;
+            assert_eq!(poem.result, generators::combinatorial::generate_combinatorial_poem(words.clone(), "random", Some(seed))?.result);
+            assert_eq!(poem.metadata["random_seed"], seed);
+            
+            let page = generators::combinatorial::book_poem(&book, None, Some(seed))?;
+            assert_eq!(page.metadata["index"], generators::combinatorial::book_poem(&book, None, Some(seed))?.metadata["index"]);
+            assert_eq!(page.metadata["random_seed"], seed);
+            
+            let options = AnagramOptions { max_results: 3, seed: Some(seed), ..Default::default() };
+            let anagrams = generators::anagram::generate_anagrams("dormitory", service.dictionary(), &options)?;
+            assert_eq!(anagrams.result, generators::anagram::generate_anagrams("dormitory", service.dictionary(), &options)?.result);
+            assert_eq!(anagrams.metadata["random_seed"], seed);
+            
+            let options = PalindromeOptions { max_results: 3, seed: Some(seed), ..Default::default() };
+            let palindromes = service.generate_palindromes("top", &options)?;
+            assert_eq!(palindromes, service.generate_palindromes("top", &options)?);
+        }
+        
+        // Different seeds explore different arrangements; an unseeded run still records its seed
+        let arrangements: std::collections::HashSet<_> = (0..10)
+            .map(|seed| generators::combinatorial::generate_combinatorial_poem(words.clone(), "random", Some(seed)).map(|r| r.result))
+            .collect::<Result<_>>()?;
+        assert!(arrangements.len() > 1);
+        let unseeded = generators::haiku::generate("love", &pronunciations, None)?;
+        let seed = unseeded.metadata["random_seed"].as_u64().expect("seed recorded");
+        assert_eq!(unseeded.result, generators::haiku::generate("love", &pronunciations, Some(seed))?.result);
+        
+        // Deterministic structures and indexed poems need no seed
+        let spiral = generators::combinatorial::generate_combinatorial_poem(words.clone(), "spiral", Some(1))?;
+        assert!(spiral.metadata["random_seed"].is_null());
+        assert!(generators::combinatorial::book_poem(&book, Some(2), None)?.metadata["random_seed"].is_null());
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraint_composition() -> Result<()> {
+        use crate::oulipo::composition::{Composition, ConstraintExpression as E, Scope};
+        let service = OulipoService::new();
+        let lipogram = |letter: &str| E::constraint("lipogram", serde_json::json!({ "forbidden_letter": letter }));
+        
+        // Each stanza is a lipogram in a different vowel
+        let each_stanza = Composition::new(E::sequence(Scope::Stanza, vec![lipogram("e"), lipogram("a"), lipogram("o")]));
+        let poem = "A cold wind blows\nOn a dark hill\n\nThe river runs\nBy the old mill\n\nThe night is here\nAnd stars will gleam";
+        assert!(service.check_composition(poem, &each_stanza)?.success);
+        
+        let broken = poem.replace("By the old", "By an old");
+        let result = service.check_composition(&broken, &each_stanza)?;
+        assert_eq!(result.violations.len(), 1);
+        assert_eq!(result.violations[0].position, broken.find("an old").unwrap());
+        assert_eq!(result.violations[0].issue, "Stanza 2: Forbidden letter 'a' found");
+        assert_eq!(result.metadata["outcome"]["children"][1]["success"], false);
+        
+        let short = service.check_composition("A cold wind blows", &each_stanza)?;
+        assert!(short.violations.iter().any(|v| v.issue.contains("needs 3 stanzas")));
+        
+        // Combinators
+        let text = "The quick brown fox";
+        let check = |expression: E| service.check_composition(text, &Composition::new(expression));
+        assert!(check(E::or(vec![lipogram("e"), lipogram("z")]))?.success);
+        assert!(!check(E::or(vec![lipogram("e"), lipogram("o")]))?.success);
+        assert!(check(E::negate(lipogram("e")))?.success);
+        let not = check(E::negate(lipogram("z")))?;
+        assert_eq!(not.violations[0].issue, "Satisfies 'lipogram', which is not allowed here");
+        assert!(check(E::at_least(2, vec![lipogram("z"), lipogram("j"), lipogram("e")]))?.success);
+        let at_least = check(E::at_least(3, vec![lipogram("z"), lipogram("j"), lipogram("e")]))?;
+        assert_eq!(at_least.violations.len(), 1);
+        assert_eq!(at_least.violations[0].position, 2);
+        
+        // Scopes map violations back to the full text
+        let per_line = Composition::new(E::or(vec![lipogram("e"), lipogram("i")]).scoped(Scope::Line));
+        let result = service.check_composition("a dry road\nset in line", &per_line)?;
+        assert_eq!(result.violations.len(), 2);
+        assert_eq!(result.violations[0].issue, "Line 2: Forbidden letter 'e' found");
+        assert_eq!(result.violations[0].position, 12);
+        
+        let sentences = Composition::new(E::sequence(Scope::Sentence, vec![lipogram("e"), lipogram("a")]));
+        assert!(service.check_composition("Cats nap. Dogs sleep! ", &sentences)?.success);
+        
+        let chorus = Composition::new(lipogram("e").scoped(Scope::Range("chorus".to_string()))).with_range("chorus", 10, 19);
+        assert!(service.check_composition("every one: a tall cat, there", &chorus)?.success);
+        assert!(service.check_composition("every one: the tall cat", &chorus).is_ok_and(|r| !r.success));
+        
+        // The tree is serialisable
+        let json = serde_json::json!({
+            "expression": {
+                "op": "and",
+                "children": [
+                    { "op": "constraint", "name": "lipogram", "config": { "forbidden_letter": "z" } },
+                    { "op": "at_least_k", "k": 1, "scope": "line", "children": [
+                        { "op": "constraint", "name": "tautogram", "config": { "letter": "s" } },
+                        { "op": "not", "child": { "op": "constraint", "name": "lipogram", "config": { "forbidden_letter": "e" } } }
+                    ] }
+                ]
+            },
+            "ranges": { "opening": { "start": 0, "end": 4 } }
+        });
+        let parsed: Composition = serde_json::from_value(json.clone())?;
+        assert_eq!(serde_json::to_value(&parsed)?, json);
+        assert!(service.check_composition("Silent snow settles\nthe end", &parsed)?.success);
+        
+        // Structural and configuration errors are reported before checking
+        assert!(service.check_composition(text, &Composition::new(E::constraint("no_such", serde_json::json!({})))).is_err());
+        assert!(service.check_composition(text, &Composition::new(E::at_least(3, vec![lipogram("z")]))).is_err());
+        assert!(service.check_composition(text, &Composition::new(E::sequence(Scope::Whole, vec![lipogram("z")]))).is_err());
+        assert!(service.check_composition(text, &Composition::new(lipogram("z").scoped(Scope::Range("x".to_string())))).is_err());
+        
+        // Flat workflows are compositions too
+        let workflow = ConstraintPresets::perec().build()?.to_composition();
+        assert!(service.check_composition("A cat sat on a mat", &workflow)?.success);
+        assert!(!service.check_composition("The cat", &workflow)?.success);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_violation_spans() -> Result<()> {
+        use crate::oulipo::composition::{Composition, ConstraintExpression as E, Scope};
+        let service = OulipoService::new();
+
+        // "é" is two bytes and one UTF-16 unit, "😀" four bytes and two units
+        let text = "café 😀\nthe end";
+        let result = service.check_lipogram(text, "e")?;
+        assert_eq!(result.violations.len(), 2, "'é' is not an 'e'");
+        let span = result.violations[0].span.expect("violations are located");
+        assert_eq!(span.byte, OffsetRange { start: 13, end: 14 });
+        assert_eq!(span.char, OffsetRange { start: 9, end: 10 });
+        assert_eq!(span.utf16, OffsetRange { start: 10, end: 11 });
+        assert_eq!(span.start, LineColumn { line: 2, column: 3 });
+        assert_eq!(span.end, LineColumn { line: 2, column: 4 });
+
+        let index = SpanIndex::new(text);
+        assert_eq!(index.byte_from_utf16(span.utf16.start), Some(span.byte.start));
+        assert_eq!(index.byte_from_char(span.char.start), Some(span.byte.start));
+        assert_eq!(index.byte_from_utf16(6), None, "inside the emoji's surrogate pair");
+        assert_eq!(index.byte_from_char(text.chars().count()), Some(text.len()));
+        assert_eq!(index.span(4, 5).byte, OffsetRange { start: 3, end: 5 }, "widened to the whole 'é'");
+
+        // Letter violations cover the letter's bytes, not one byte of it
+        let accented = service.check_lipogram(text, "é")?;
+        let violation = &accented.violations[0];
+        assert_eq!(&text[violation.position..violation.position + violation.length], "é");
+        assert_eq!(violation.span.map(|s| s.byte), Some(OffsetRange { start: 3, end: 5 }));
+        let prisoners = crate::oulipo::constraints::prisoners::check("é")?;
+        assert_eq!(prisoners.violations[0].length, 2);
+
+        // Length limits count characters, not bytes
+        let length = crate::oulipo::validators::validate_text_length("😀😀😀", 0, Some(2))?;
+        assert_eq!(length.violations[0].span.map(|s| s.char), Some(OffsetRange { start: 2, end: 3 }));
+        assert!(crate::oulipo::validators::validate_text_length("😀😀", 0, Some(2))?.success);
+
+        // Scoped violations are located against the whole text
+        let per_line = Composition::new(E::constraint("lipogram", serde_json::json!({ "forbidden_letter": "e" })).scoped(Scope::Line));
+        let result = service.check_composition(text, &per_line)?;
+        let last = result.violations.last().and_then(|v| v.span).expect("violations are located");
+        assert_eq!(last.start, LineColumn { line: 2, column: 5 });
+
+        // Spans are serialised alongside the byte position
+        let json = serde_json::to_value(&result.violations[0])?;
+        assert_eq!(json["span"]["utf16"]["start"], 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quick_fixes() -> Result<()> {
+        use crate::oulipo::fixes::apply_fixes;
+        let service = OulipoService::new();
+
+        // Lipogram violations offer the word's synonyms
+        let text = "A brave queen";
+        let result = service.check_lipogram(text, "e")?;
+        assert_eq!(result.violations.len(), 3);
+        let brave = &result.violations[0].fixes;
+        assert_eq!(brave[0].replacement, "bold");
+        assert_eq!(&text[brave[0].position..brave[0].end()], "brave");
+        assert!(brave.windows(2).all(|w| w[0].confidence >= w[1].confidence));
+        assert_eq!(brave[0].span.map(|s| s.char.start), Some(2));
+        assert_eq!(result.violations[1].fixes, result.violations[2].fixes, "both 'e's of 'queen'");
+
+        // Choosing the same fix twice applies it once; alternatives for one word collide
+        let mut chosen = vec![brave[0].clone(), brave[0].clone()];
+        chosen.extend(brave.get(1).cloned());
+        let outcome = apply_fixes(text, &chosen)?;
+        assert_eq!(outcome.text, "A bold queen");
+        assert_eq!(outcome.applied.len(), 1);
+        assert_eq!(outcome.skipped.len(), chosen.len() - 2);
+        assert!(apply_fixes("café", &[Fix::new("split", 4, 1, "", 1.0)]).is_err());
+        assert!(apply_fixes("café", &[Fix::new("overflow", usize::MAX, 1, "", 1.0)]).is_err());
+
+        // Applying re-checks against a registry constraint
+        let config = serde_json::json!({ "forbidden_letter": "e" });
+        let outcome = service.apply_fixes("A brave king", &[brave[0].clone()], "lipogram", &config)?;
+        assert_eq!(outcome.text, "A bold king");
+        assert!(outcome.check.is_some_and(|check| check.success));
+
+        // Wrong end words are swapped for the expected word, in the line's case
+        let end_words: Vec<String> = ["stone", "river", "light"].iter().map(|w| w.to_string()).collect();
+        let poem = "I lift the stone\nand cross the river\ninto the light\n\n\
+                    Beyond the light\nlies one more stone\nbeside the river\n\n\
+                    Along the river\nI follow light\nback to the stone\n\n\
+                    By stone and river\nwe keep the light";
+        assert!(service.check_nina(poem, &end_words)?.success);
+        let broken = poem.replace("lies one more stone", "lies one more Stones");
+        let result = service.check_nina(&broken, &end_words)?;
+        let fix = &result.violations[0].fixes[0];
+        assert_eq!((fix.replacement.as_str(), fix.confidence), ("Stone", 0.9));
+        let outcome = service.apply_fixes(&broken, &result.violations[0].fixes, "nina", &serde_json::json!({ "end_words": end_words }))?;
+        assert_eq!(outcome.text, poem.replace("lies one more stone", "lies one more Stone"));
+        assert!(outcome.check.is_some_and(|check| check.success));
+
+        // Over-used letters can be deleted
+        let quotas = serde_json::from_value(serde_json::json!({ "e": { "max": 1 }, "t": { "max": 1 } }))?;
+        let result = service.check_letter_quotas("letters", quotas)?;
+        let deletions: Vec<Fix> = result.violations.iter().flat_map(|v| v.fixes.clone()).collect();
+        assert!(deletions.iter().all(|fix| fix.replacement.is_empty() && fix.length == 1));
+        assert_eq!(apply_fixes("letters", &deletions)?.text, "letrs");
+
+        // Over-long text can be trimmed to a whole word or to the exact limit
+        let result = service.validate_text_length("the quick brown fox", 0, Some(12))?;
+        let trims: Vec<&str> = result.violations[0].fixes.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(trims, vec!["Trim to the last whole word", "Trim to 12 characters"]);
+        let trimmed: Vec<String> = result.violations[0]
+            .fixes
+            .iter()
+            .map(|fix| apply_fixes("the quick brown fox", std::slice::from_ref(fix)).map(|o| o.text))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(trimmed, vec!["the quick", "the quick br"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_check_session() -> Result<()> {
+        use crate::oulipo::span::OffsetUnit;
+        let mut service = OulipoService::new();
+        let config = serde_json::json!({ "forbidden_letter": "e" });
+        let full_check = |service: &OulipoService, text: &str| -> Result<Vec<usize>> {
+            Ok(service.check_lipogram(text, "e")?.violations.iter().map(|v| v.position).collect())
+        };
+
+        let text = "A cat 😀 sat\non a mat\nby the door";
+        let opened = service.open_check_session(text, "lipogram", config.clone(), None)?;
+        let session = opened.session;
+        assert_eq!((opened.checked_units, opened.total_units), (3, 3));
+        assert_eq!(opened.added.len(), 1);
+        assert!(!opened.success);
+
+        // Typing on line 1, in UTF-16 offsets past the emoji, re-checks that line alone
+        let delta = TextDelta { start: 12, end: 12, text: " here".to_string(), unit: OffsetUnit::Utf16 };
+        let update = service.update_check_session(session, &[delta])?;
+        assert_eq!(service.check_session(session).unwrap().text(), "A cat 😀 sat here\non a mat\nby the door");
+        assert_eq!((update.checked_units, update.total_units), (1, 3));
+        assert_eq!(update.added.len(), 2);
+        assert!(update.removed.is_empty(), "the violation on line 3 only moved");
+        let here = update.added[0].id;
+
+        // Deleting the word removes its violations and reports nothing new
+        let update = service.update_check_session(session, &[TextDelta::new(14, 19, "")])?;
+        assert_eq!(update.removed.len(), 2);
+        assert!(update.removed.contains(&here));
+        assert!(update.added.is_empty());
+        assert_eq!(update.checked_units, 1);
+
+        // Several deltas in one update, each against the text the previous one left
+        let update = service.update_check_session(session, &[TextDelta::new(27, 35, "a gap"), TextDelta::insert(0, "So\n")])?;
+        assert_eq!(update.removed.len(), 1);
+        assert!(update.success);
+        let current = service.check_session(session).unwrap();
+        assert_eq!(current.text(), "So\nA cat 😀 sat\non a mat\nby a gap");
+        assert_eq!(full_check(&service, current.text())?, Vec::<usize>::new());
+
+        let update = service.update_check_session(session, &[TextDelta::insert(3, "Every ")])?;
+        let current = service.check_session(session).unwrap();
+        let positions: Vec<usize> = current.violations().iter().map(|v| v.violation.position).collect();
+        assert_eq!(positions, full_check(&service, current.text())?);
+        assert_eq!(update.added[0].violation.span.map(|s| s.start), Some(LineColumn { line: 2, column: 1 }));
+
+        // Bad deltas leave the session untouched
+        let before = current.text().to_string();
+        assert!(service.update_check_session(session, &[TextDelta::new(16, 17, "")]).is_err(), "inside the emoji");
+        assert!(service.update_check_session(session, &[TextDelta::insert(0, "x"), TextDelta::new(0, 1000, "")]).is_err());
+        assert_eq!(service.check_session(session).unwrap().text(), before);
+
+        // Forms are re-checked as a whole
+        let opened = service.open_check_session("Bold brave birds", "tautogram", serde_json::json!({}), None)?;
+        assert!(opened.success);
+        let update = service.update_check_session(opened.session, &[TextDelta::insert(16, " sing")])?;
+        assert_eq!((update.checked_units, update.added.len()), (1, 1));
+
+        assert!(service.close_check_session(session));
+        assert!(service.update_check_session(session, &[]).is_err());
+        assert!(service.open_check_session(text, "no_such", config, None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graded_scores() -> Result<()> {
+        let service = OulipoService::new();
+
+        // Five of the six vowels conform
+        let result = service.check_univocalic("A cat sat on a mat", "a")?;
+        assert!(!result.success);
+        assert_eq!(result.score(), 0.833);
+        assert_eq!(service.check_univocalic("A cat sat at a mat", "a")?.score(), 1.0);
+        let listed = ConstraintResult::failure("failed".to_string(), Vec::new(), Vec::new(), serde_json::json!([1, 2])).with_score(0.5);
+        assert_eq!((&listed.metadata, listed.score()), (&serde_json::json!([1, 2]), 0.0));
+        assert_eq!(ConstraintResult::failure("failed".to_string(), Vec::new(), Vec::new(), serde_json::Value::Null).with_score(f64::NAN).metadata["score"], 0.0);
+
+        // Three of the four stanza lines end on the right word; the envoi does not count
+        let end_words = vec!["sun".to_string(), "moon".to_string()];
+        let poem = "I see the sun\nand then the moon\n\nup goes the moon\nthen comes the day\n\nsun, moon";
+        assert_eq!(service.check_nina(poem, &end_words)?.score(), 0.75);
+        assert_eq!(service.check_nina("I see the sun", &end_words)?.score(), 0.25);
+
+        assert_eq!(service.validate_text_length("short", 10, None)?.score(), 0.5);
+        assert_eq!(service.validate_text_length("far too long", 0, Some(6))?.score(), 0.5);
+
+        // Results without a measure of their own score 1 or 0
+        let palindrome = service.create_custom_constraint("palindrome", serde_json::json!({}))?;
+        assert_eq!(palindrome.check("not one")?.score(), 0.0);
+
+        // Lipogram scores 5/6, univocalic 1/2 and the word count 1, weighted 3:1:1
+        let workflow = service
+            .create_workflow()
+            .with_lipogram('e')
+            .with_weight(3.0)
+            .with_univocalic('a')
+            .with_word_limits(Some(1), None)
+            .build()?;
+        let result = workflow.check("the cat", &service)?;
+        assert!(!result.success);
+        assert_eq!(result.weights, vec![3.0, 1.0, 1.0]);
+        assert_eq!(result.score, 0.8);
+        assert!(result.summary.contains("80% adherence"), "{}", result.summary);
+
+        // Replacing a bound keeps the weights in step with the constraints
+        let workflow = service
+            .create_workflow()
+            .with_word_limits(Some(1), None)
+            .with_weight(0.0)
+            .with_lipogram('e')
+            .with_word_limits(Some(5), None)
+            .build()?;
+        assert_eq!(workflow.weights, vec![1.0, 1.0]);
+        assert_eq!(workflow.check("a cat", &service)?.score, 0.7);
+
+        Ok(())
+    }
+
     #[test]
     fn test_batch_constraint_checker() -> Result<()> {
         let service = OulipoService::new();
@@ -160,11 +1266,166 @@ mod tests {
         
         Ok(())
     }
+
+    #[test]
+    fn test_generate_and_test_loop() -> Result<()> {
+        let service = OulipoService::new();
+
+        // A haiku meets the short preset at once
+        let outcome = service
+            .create_generation_workflow()
+            .with_theme("nature")
+            .with_constraint("short")
+            .with_seed(7)
+            .build()
+            .generate(&service)?;
+        assert!(outcome.success);
+        assert_eq!((outcome.attempts.len(), outcome.best_attempt), (1, Some(1)));
+        assert_eq!(outcome.attempts[0].source, "haiku");
+
+        // An unmeetable constraint uses every attempt and keeps the best draft
+        let workflow = service
+            .create_generation_workflow()
+            .with_theme("nature")
+            .with_constraint_config("univocalic", serde_json::json!({ "allowed_vowel": "u" }))
+            .with_constraint("short")
+            .max_attempts(4)
+            .with_seed(7)
+            .build();
+        let outcome = workflow.generate(&service)?;
+        assert!(!outcome.success);
+        assert_eq!(outcome.attempts.len(), 4);
+        let seeds: Vec<u64> = outcome.attempts.iter().map(|a| a.seed).collect();
+        assert_eq!(seeds, vec![7, 8, 9, 10]);
+        let best = outcome.attempts.iter().map(|a| a.score).fold(0.0, f64::max);
+        assert_eq!(outcome.score, best);
+        let best_attempt = &outcome.attempts[outcome.best_attempt.unwrap() - 1];
+        assert_eq!(outcome.result, best_attempt.candidate);
+        assert!(outcome.attempts.iter().all(|a| a.feedback.iter().any(|issue| issue.contains("only 'u' permitted"))));
+        assert_eq!(workflow.generate(&service)?.result, outcome.result, "the same seed gives the same run");
+
+        // Feedback reaches the next request's prompt
+        let lipogram = service.create_workflow().with_lipogram('e').build()?;
+        let mut run = GenerationRun::new("time", lipogram, 2, Some(1), &service)?;
+        run.record("ai", Ok("The end".to_string()), &service)?;
+        let prompt = run.next_request().unwrap().prompt();
+        assert!(prompt.contains("Your previous attempt was:\nThe end"));
+        assert!(prompt.contains("forbidden"), "{}", prompt);
+        run.record("ai", Err(anyhow::anyhow!("offline")), &service)?;
+        assert!(run.next_request().is_none());
+        let outcome = run.finish();
+        assert_eq!(outcome.attempts[1].error.as_deref(), Some("offline"));
+        assert_eq!(outcome.result.as_deref(), Some("The end"));
+
+        assert!(service.create_generation_workflow().with_constraint("no_such").build().generate(&service).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_rules() -> Result<()> {
+        let source = r#"
+            [[rules]]
+            name = "short_words"
+            description = "No word over 7 letters"
+            scope = "line"
+            checks = [{ type = "words", where = { min_length = 8 }, max = 0 }]
+
+            [[rules]]
+            name = "moving_lines"
+            description = "Every line starts with a verb of motion"
+            scope = "line"
+            lists = { motion = ["run", "walk", "drift", "climb", "go"] }
+            checks = [{ type = "words", position = "first", where = { in_list = "motion" }, min = 1 }]
+
+            [[rules]]
+            name = "lighthouse"
+            scope = "stanza"
+            checks = [{ type = "pattern", pattern = '(?i)\blighthouse\b', min = 1, max = 1, message = "Name the lighthouse once" }]
+        "#;
+        let path = std::env::temp_dir().join(format!("oulipo_rules_{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut service = OulipoService::new();
+        service.attach_rule_file(&path)?;
+        let names = service.import_rules(source, RuleFormat::Toml)?;
+        assert_eq!(names, vec!["short_words", "moving_lines", "lighthouse"]);
+
+        // Rules are registry constraints with generated schemas
+        let registry = service.constraint_registry();
+        assert_eq!(registry.locality("lighthouse"), Some(Scope::Stanza));
+        let schema = registry.get_config_schema("short_words").unwrap();
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(schema["x-user-rule"]["checks"][0], "at most 0 words with at least 8 letters");
+
+        let check = |name: &str, text: &str| service.create_custom_constraint(name, serde_json::json!({}))?.check(text);
+        let result = check("short_words", "Walk the shoreline\nRun to the extraordinary cliffs")?;
+        assert!(!result.success);
+        let flagged: Vec<&str> = result.violations.iter().map(|v| &"Walk the shoreline\nRun to the extraordinary cliffs"[v.position..v.position + v.length]).collect();
+        assert_eq!(flagged, vec!["shoreline", "extraordinary"]);
+        assert!(result.violations[1].issue.starts_with("Line 2:"));
+        assert_eq!(result.score(), 0.0);
+
+        let result = check("moving_lines", "Drift past the rocks\nthe tide comes in\nClimb the stair")?;
+        assert_eq!(result.violations.len(), 1);
+        assert!(result.violations[0].issue.contains("Line 2 should start with"), "{}", result.violations[0].issue);
+        assert_eq!(result.score(), 0.667);
+
+        let poem = "The lighthouse keeps\nits watch\n\nno light here\n\nLighthouse, lighthouse";
+        let result = check("lighthouse", poem)?;
+        assert_eq!(result.violations.len(), 2);
+        assert!(result.violations.iter().all(|v| v.issue == "Name the lighthouse once"));
+        assert_eq!(result.violations[1].span.as_ref().unwrap().start.line, 6);
+        assert!(check("lighthouse", "The lighthouse keeps watch")?.success);
+        assert!(service.create_custom_constraint("lighthouse", serde_json::json!({ "count": 2 })).is_err());
+
+        // Sessions check rules unit by unit
+        let update = service.open_check_session("Run home\nWander everywhere", "short_words", serde_json::json!({}), None)?;
+        assert_eq!((update.total_units, update.added.len()), (2, 1));
+
+        // Rules persist to the attached file and load into a new service
+        let saved = std::fs::read_to_string(&path)?;
+        assert!(saved.contains("[[rules]]"), "{}", saved);
+        let mut reloaded = OulipoService::new();
+        reloaded.attach_rule_file(&path)?;
+        assert_eq!(reloaded.user_rules(), service.user_rules());
+        assert!(reloaded.constraint_registry().contains("moving_lines"));
+        let json = reloaded.export_rules(RuleFormat::Json)?;
+        assert_eq!(rules::parse_rules(&json, RuleFormat::Json)?, service.user_rules());
+
+        // Invalid rules are rejected, and leave the rules as they were
+        let lipogram = r#"{ "rules": [{ "name": "lipogram", "checks": [{ "type": "pattern", "pattern": "e", "max": 0 }] }] }"#;
+        assert!(service.import_rules(lipogram, RuleFormat::Json).is_err());
+        let broken = r#"{ "rules": [
+            { "name": "fine", "checks": [{ "type": "pattern", "pattern": "x", "max": 0 }] },
+            { "name": "broken", "checks": [{ "type": "pattern", "pattern": "(", "max": 0 }] }
+        ] }"#;
+        assert!(service.import_rules(broken, RuleFormat::Json).is_err());
+        assert!(!service.constraint_registry().contains("fine"));
+        let unbounded = r#"{ "rules": [{ "name": "unbounded", "checks": [{ "type": "words", "where": { "max_length": 3 } }] }] }"#;
+        assert!(service.import_rules(unbounded, RuleFormat::Json).is_err());
+        let unknown_list = r#"{ "rules": [{ "name": "listless", "checks": [{ "type": "words", "where": { "in_list": "nope" }, "min": 1 }] }] }"#;
+        assert!(service.import_rules(unknown_list, RuleFormat::Json).is_err());
+        let empty_match = r#"{ "rules": [{ "name": "no_a", "checks": [{ "type": "pattern", "pattern": "a*", "max": 0 }] }] }"#;
+        assert!(service.import_rules(empty_match, RuleFormat::Json).is_err());
+        assert_eq!(service.user_rules().len(), 3);
+
+        // Removing a rule unregisters it and saves
+        assert!(service.remove_rule("lighthouse")?);
+        assert!(!service.remove_rule("lighthouse")?);
+        assert!(!service.constraint_registry().contains("lighthouse"));
+        let mut reloaded = OulipoService::new();
+        reloaded.attach_rule_file(&path)?;
+        assert_eq!(reloaded.user_rules().len(), 2);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
 }
 
 /// Example usage patterns and demonstrations
 pub mod examples {
-    use super::*;
+    use crate::oulipo::*;
     use anyhow::Result;
     
     /// Demonstrate simple constraint checking
@@ -174,7 +1435,7 @@ pub mod examples {
         let service = OulipoService::new();
         
         let text = "A cat sat at a mat";
-        let result = service.check_univocalic(text, 'a')?;
+        let result = service.check_univocalic(text, "a")?;
         
         println!("Text: '{}'", text);
         println!("Constraint: Univocalic (only 'a' allowed)");
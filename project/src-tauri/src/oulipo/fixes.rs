@@ -0,0 +1,76 @@
+//! Applying the quick fixes attached to violations.
+//!
+//! A front end offers each violation's fixes, the writer picks some, and
+//! `apply_fixes` makes the chosen edits in one pass. Edits are applied in text
+//! order. One that overlaps an edit already applied is skipped rather than
+//! merged, so picking two alternatives for the same word keeps the earlier one.
+
+use crate::oulipo::{
+    types::{ConstraintResult, Fix},
+    errors::{OulipoError, OulipoResult},
+};
+use serde::{Deserialize, Serialize};
+
+/// The text after applying a set of fixes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixOutcome {
+    /// The edited text
+    pub text: String,
+    /// Fixes that were applied, in text order, with positions in the original text
+    pub applied: Vec<Fix>,
+    /// Fixes left out because they overlap an applied one
+    pub skipped: Vec<Fix>,
+    /// The edited text checked again, when a constraint was given
+    pub check: Option<ConstraintResult>,
+}
+
+/// Apply fixes to `text`, skipping any that overlap an earlier applied fix
+///
+/// Fixes may be given in any order; fixes at the same position are applied
+/// shorter first. The same fix chosen twice, e.g. from two violations in one
+/// word, is applied once. A fix outside the text or splitting a character is
+/// an error.
+pub fn apply_fixes(text: &str, fixes: &[Fix]) -> OulipoResult<FixOutcome> {
+    for fix in fixes {
+        let in_text = fix.position.checked_add(fix.length).is_some_and(|end| {
+            end <= text.len() && text.is_char_boundary(fix.position) && text.is_char_boundary(end)
+        });
+        if !in_text {
+            return Err(OulipoError::TextProcessing(format!(
+                "Fix '{}' covers {} bytes from {}, which do not fall on character boundaries of the text",
+                fix.label, fix.length, fix.position
+            )));
+        }
+    }
+
+    let mut ordered: Vec<&Fix> = Vec::with_capacity(fixes.len());
+    for fix in fixes {
+        if !ordered.iter().any(|chosen| same_edit(chosen, fix)) {
+            ordered.push(fix);
+        }
+    }
+    ordered.sort_by_key(|fix| (fix.position, fix.end()));
+
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut applied = Vec::new();
+    let mut skipped = Vec::new();
+    for fix in ordered {
+        if fix.position < cursor {
+            skipped.push(fix.clone());
+            continue;
+        }
+        output.push_str(&text[cursor..fix.position]);
+        output.push_str(&fix.replacement);
+        cursor = fix.end();
+        applied.push(fix.clone());
+    }
+    output.push_str(&text[cursor..]);
+
+    Ok(FixOutcome { text: output, applied, skipped, check: None })
+}
+
+/// Whether two fixes make the same edit, whatever their labels and confidence
+fn same_edit(a: &Fix, b: &Fix) -> bool {
+    a.position == b.position && a.length == b.length && a.replacement == b.replacement
+}
@@ -0,0 +1,354 @@
+//! Generate-and-test: drafting text until it satisfies a set of constraints.
+//!
+//! A run asks a source for a candidate, checks it against the workflow's
+//! constraints and turns the violations into feedback for the next request.
+//! The source is one of the Oulipo generators or, from the command layer, the
+//! configured AI provider. Because a run hands out requests and takes
+//! candidates back, an asynchronous source can drive it without holding the
+//! service across an await. The run stops at the first candidate that
+//! satisfies every constraint, or after `max_attempts`, and keeps the
+//! best-scoring candidate.
+
+use crate::oulipo::{
+    builder::{ConstraintPresets, ConstraintWorkflowConfig, GenerationWorkflowConfig, WorkflowResult},
+    types::Fix,
+    errors::{OulipoError, OulipoResult},
+    fixes::apply_fixes,
+    generators::{resolve_seed, snowball::SnowballOptions},
+    OulipoService,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Issues passed back per attempt; more would drown a prompt
+const MAX_FEEDBACK: usize = 12;
+
+/// An Oulipo generator that drafts candidates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateGenerator {
+    /// A 5-7-5 haiku from the theme's word bank
+    #[default]
+    Haiku,
+    /// A snowball from the active dictionary, preferring the theme
+    Snowball,
+}
+
+impl CandidateGenerator {
+    /// Parse a generator name as used in commands
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "haiku" => Some(CandidateGenerator::Haiku),
+            "snowball" | "rhopalic" => Some(CandidateGenerator::Snowball),
+            _ => None,
+        }
+    }
+
+    /// The generator whose output can meet the constraints: a snowball for a snowball constraint, otherwise a haiku
+    pub fn for_constraints(constraints: &[(String, serde_json::Value)]) -> Self {
+        if constraints.iter().any(|(name, _)| name == "snowball") {
+            CandidateGenerator::Snowball
+        } else {
+            CandidateGenerator::Haiku
+        }
+    }
+
+    /// The generator's name in attempt logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            CandidateGenerator::Haiku => "haiku",
+            CandidateGenerator::Snowball => "snowball",
+        }
+    }
+
+    /// Draft a fresh candidate with the request's seed
+    pub fn draft(&self, service: &OulipoService, request: &CandidateRequest) -> Result<String> {
+        match self {
+            CandidateGenerator::Haiku => service.generate_haiku(Some(&request.theme), Some(request.seed)),
+            CandidateGenerator::Snowball => service.generate_snowball(&SnowballOptions {
+                theme_words: request.theme.split_whitespace().map(str::to_string).collect(),
+                seed: Some(request.seed),
+                ..SnowballOptions::default()
+            }),
+        }
+    }
+}
+
+/// What a source is asked for on one attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidateRequest {
+    /// The attempt number, from 1
+    pub attempt: usize,
+    pub theme: String,
+    /// The seed for this attempt; a generator given the same seed drafts the same text
+    pub seed: u64,
+    /// Each constraint, described for a writer
+    pub constraints: Vec<String>,
+    /// The best candidate so far
+    pub previous: Option<String>,
+    /// What the best candidate so far gets wrong
+    pub feedback: Vec<String>,
+    /// The best candidate with its most confident fixes applied, not yet tried
+    pub repair: Option<String>,
+}
+
+impl CandidateRequest {
+    /// The request as a prompt for a language model
+    pub fn prompt(&self) -> String {
+        let mut prompt = format!(
+            "Write a short text on the theme \"{}\" that satisfies every one of these constraints:\n",
+            self.theme
+        );
+        for constraint in &self.constraints {
+            prompt.push_str(&format!("- {}\n", constraint));
+        }
+        if let Some(previous) = &self.previous {
+            prompt.push_str(&format!("\nYour previous attempt was:\n{}\n", previous));
+            if !self.feedback.is_empty() {
+                prompt.push_str("\nIt broke these rules:\n");
+                for issue in &self.feedback {
+                    prompt.push_str(&format!("- {}\n", issue));
+                }
+            }
+        }
+        prompt.push_str("\nReply with the text only.");
+        prompt
+    }
+}
+
+/// One attempt in a run's log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationAttempt {
+    /// The attempt number, from 1
+    pub attempt: usize,
+    /// What produced the candidate: a generator, "ai", or "fixes" for a repair
+    pub source: String,
+    pub seed: u64,
+    /// The candidate, unless the source failed
+    pub candidate: Option<String>,
+    pub success: bool,
+    /// The candidate's weighted adherence score, from 0 to 1
+    pub score: f64,
+    /// The candidate's violations, passed back on the next attempt
+    pub feedback: Vec<String>,
+    /// Why the source produced nothing
+    pub error: Option<String>,
+}
+
+/// The best candidate of a run, with the log of every attempt
+#[derive(Debug, Serialize)]
+pub struct GenerationOutcome {
+    /// Whether a candidate satisfied every constraint
+    pub success: bool,
+    /// The best-scoring candidate; the earliest one on a tie
+    pub result: Option<String>,
+    pub score: f64,
+    /// The attempt number of the best candidate
+    pub best_attempt: Option<usize>,
+    /// The workflow check of the best candidate
+    pub check: Option<WorkflowResult>,
+    /// The seed the attempts' seeds are counted from
+    pub seed: u64,
+    pub attempts: Vec<GenerationAttempt>,
+}
+
+/// The best candidate so far and its check
+struct Best {
+    attempt: usize,
+    text: String,
+    check: WorkflowResult,
+    feedback: Vec<String>,
+}
+
+/// A generate-and-test run in progress
+pub struct GenerationRun {
+    theme: String,
+    max_attempts: usize,
+    seed: u64,
+    workflow: ConstraintWorkflowConfig,
+    descriptions: Vec<String>,
+    attempts: Vec<GenerationAttempt>,
+    best: Option<Best>,
+    repair: Option<String>,
+}
+
+impl GenerationRun {
+    /// Start a run checking candidates against `workflow`
+    ///
+    /// Every constraint must be registered and accept its configuration.
+    /// Without a seed, a fresh one is drawn; attempt `n` uses `seed + n - 1`.
+    pub fn new(
+        theme: &str,
+        workflow: ConstraintWorkflowConfig,
+        max_attempts: usize,
+        seed: Option<u64>,
+        service: &OulipoService,
+    ) -> OulipoResult<Self> {
+        let mut descriptions = Vec::with_capacity(workflow.constraints.len());
+        for (name, config) in &workflow.constraints {
+            let constraint = service.constraint_registry().create_constraint(name, config)?;
+            let settings = match config.as_object() {
+                Some(settings) if !settings.is_empty() => format!(" {}", config),
+                _ => String::new(),
+            };
+            descriptions.push(format!("{}{}: {}", name, settings, constraint.description()));
+        }
+
+        Ok(Self {
+            theme: theme.to_string(),
+            max_attempts: max_attempts.max(1),
+            seed: resolve_seed(seed),
+            workflow,
+            descriptions,
+            attempts: Vec::new(),
+            best: None,
+            repair: None,
+        })
+    }
+
+    /// Start a run for a generation workflow
+    ///
+    /// Each constraint name is looked up in the registry first, then among
+    /// the presets (see `ConstraintPresets::by_name`), whose constraints it
+    /// stands for.
+    pub fn from_config(config: &GenerationWorkflowConfig, service: &OulipoService) -> OulipoResult<Self> {
+        let mut workflow = ConstraintWorkflowConfig {
+            constraints: Vec::new(),
+            weights: Vec::new(),
+        };
+        for (name, settings) in &config.constraints {
+            if service.constraint_registry().contains(name) {
+                workflow.constraints.push((name.clone(), settings.clone()));
+                workflow.weights.push(1.0);
+                continue;
+            }
+            let preset = ConstraintPresets::by_name(name)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown constraint or preset: {}", name)))?
+                .build()?;
+            workflow.weights.extend((0..preset.constraints.len()).map(|index| preset.weight(index)));
+            workflow.constraints.extend(preset.constraints);
+        }
+        Self::new(&config.theme, workflow, config.max_attempts, config.seed, service)
+    }
+
+    /// The constraints candidates are checked against
+    pub fn workflow(&self) -> &ConstraintWorkflowConfig {
+        &self.workflow
+    }
+
+    /// The next request, or `None` once a candidate succeeded or the attempts ran out
+    pub fn next_request(&self) -> Option<CandidateRequest> {
+        let succeeded = self.best.as_ref().is_some_and(|best| best.check.success);
+        if succeeded || self.attempts.len() >= self.max_attempts {
+            return None;
+        }
+        let attempt = self.attempts.len() + 1;
+        Some(CandidateRequest {
+            attempt,
+            theme: self.theme.clone(),
+            seed: self.seed.wrapping_add(attempt as u64 - 1),
+            constraints: self.descriptions.clone(),
+            previous: self.best.as_ref().map(|best| best.text.clone()),
+            feedback: self.best.as_ref().map(|best| best.feedback.clone()).unwrap_or_default(),
+            repair: self.repair.clone(),
+        })
+    }
+
+    /// Check the candidate for the current request and log the attempt
+    ///
+    /// A source that failed is logged with its error and scores 0.
+    pub fn record(&mut self, source: &str, candidate: Result<String>, service: &OulipoService) -> Result<&GenerationAttempt> {
+        let attempt = self.attempts.len() + 1;
+        let seed = self.seed.wrapping_add(attempt as u64 - 1);
+        let mut entry = GenerationAttempt {
+            attempt,
+            source: source.to_string(),
+            seed,
+            candidate: None,
+            success: false,
+            score: 0.0,
+            feedback: Vec::new(),
+            error: None,
+        };
+
+        match candidate {
+            Err(e) => entry.error = Some(e.to_string()),
+            Ok(text) => {
+                if self.repair.as_deref() == Some(text.as_str()) {
+                    self.repair = None;
+                }
+                let check = service.check_with_workflow(&text, &self.workflow)?;
+                entry.success = check.success;
+                entry.score = check.score;
+                entry.feedback = feedback(&check);
+                entry.candidate = Some(text.clone());
+
+                let improved = !self.best.as_ref().is_some_and(|best| check.score <= best.check.score);
+                if improved {
+                    // A fix that cannot be applied only means there is nothing to repair
+                    self.repair = repair(&text, &check).unwrap_or_else(|e| {
+                        log::warn!("Could not repair attempt {}: {}", attempt, e);
+                        None
+                    });
+                    self.best = Some(Best { attempt, text, check, feedback: entry.feedback.clone() });
+                }
+            }
+        }
+
+        self.attempts.push(entry);
+        Ok(&self.attempts[attempt - 1])
+    }
+
+    /// End the run with its best candidate
+    pub fn finish(self) -> GenerationOutcome {
+        let (success, score) = self.best.as_ref().map_or((false, 0.0), |best| (best.check.success, best.check.score));
+        GenerationOutcome {
+            success,
+            score,
+            best_attempt: self.best.as_ref().map(|best| best.attempt),
+            result: self.best.as_ref().map(|best| best.text.clone()),
+            check: self.best.map(|best| best.check),
+            seed: self.seed,
+            attempts: self.attempts,
+        }
+    }
+}
+
+/// The issues of every failed constraint, or its result when it has no violations
+fn feedback(check: &WorkflowResult) -> Vec<String> {
+    check
+        .constraint_results
+        .iter()
+        .filter(|result| !result.success)
+        .flat_map(|result| {
+            let issues: Vec<String> = result.violations.iter().map(|v| v.issue.clone()).collect();
+            if issues.is_empty() {
+                result.result.clone().into_iter().collect()
+            } else {
+                issues
+            }
+        })
+        .take(MAX_FEEDBACK)
+        .collect()
+}
+
+/// The candidate with each violation's most confident fix applied, if that changes it
+fn repair(text: &str, check: &WorkflowResult) -> OulipoResult<Option<String>> {
+    let fixes: Vec<Fix> = check
+        .constraint_results
+        .iter()
+        .flat_map(|result| &result.violations)
+        .filter_map(|violation| {
+            violation
+                .fixes
+                .iter()
+                .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+                .cloned()
+        })
+        .collect();
+    if fixes.is_empty() {
+        return Ok(None);
+    }
+    let outcome = apply_fixes(text, &fixes)?;
+    Ok(Some(outcome.text).filter(|repaired| repaired != text))
+}
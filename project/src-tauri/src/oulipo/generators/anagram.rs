@@ -1,13 +1,90 @@
 // Anagram generation and challenges
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{ConstraintResult, Violation, dictionary::Dictionary, generators::seeded_rng};
 use anyhow::Result;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-pub fn generate_anagrams(text: &str) -> Result<ConstraintResult> {
-    let clean_text = text.chars()
-        .filter(|c| c.is_alphabetic())
-        .collect::<String>()
-        .to_lowercase();
+/// Upper bound on search nodes so long phrases stay responsive
+const SEARCH_NODE_BUDGET: usize = 250_000;
+
+/// Options for the anagram solver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnagramOptions {
+    /// Maximum number of anagrams to return
+    pub max_results: usize,
+    /// Shortest word allowed in a multi-word anagram
+    pub min_word_length: usize,
+    /// Maximum number of words per anagram
+    pub max_words: usize,
+    /// Words that must appear in every anagram
+    pub required_words: Vec<String>,
+    /// Words that must never appear
+    pub excluded_words: Vec<String>,
+    /// Vary which equally ranked anagrams are found and listed first; the same
+    /// seed gives the same results, and without one the order is alphabetical
+    pub seed: Option<u64>,
+}
+
+impl Default for AnagramOptions {
+    fn default() -> Self {
+        Self {
+            max_results: 10,
+            min_word_length: 1,
+            max_words: 4,
+            required_words: Vec::new(),
+            excluded_words: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+/// Letter multiset over the distinct letters of the source phrase
+#[derive(Debug, Clone, PartialEq)]
+struct LetterBag(Vec<usize>);
+
+impl LetterBag {
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&c| c == 0)
+    }
+
+    fn contains(&self, other: &LetterBag) -> bool {
+        self.0.iter().zip(&other.0).all(|(have, need)| have >= need)
+    }
+
+    fn subtract(&self, other: &LetterBag) -> LetterBag {
+        LetterBag(self.0.iter().zip(&other.0).map(|(have, need)| have - need).collect())
+    }
+}
+
+/// Maps letters of the source phrase to positions in a `LetterBag`
+struct Alphabet(Vec<char>);
+
+impl Alphabet {
+    fn bag(&self, word: &str) -> Option<LetterBag> {
+        let mut counts = vec![0; self.0.len()];
+        for ch in normalize(word).chars() {
+            let idx = self.0.iter().position(|&c| c == ch)?;
+            counts[idx] += 1;
+        }
+        Some(LetterBag(counts))
+    }
+}
+
+/// A dictionary word that fits inside the phrase
+struct Candidate {
+    word: String,
+    bag: LetterBag,
+}
+
+/// Find single- and multi-word anagrams of a phrase using words from the dictionary
+///
+/// The solver searches the letter multiset depth-first, only ever trying words that
+/// still fit in the remaining letters and taking words in a fixed order so each
+/// combination is visited once. Results are ranked with fewer and longer words first.
+pub fn generate_anagrams(text: &str, dictionary: &Dictionary, options: &AnagramOptions) -> Result<ConstraintResult> {
+    let clean_text = normalize(text);
     
     if clean_text.is_empty() {
         return Ok(ConstraintResult {
@@ -16,8 +93,10 @@ pub fn generate_anagrams(text: &str) -> Result<ConstraintResult> {
             violations: vec![Violation {
                 position: 0,
                 length: text.len(),
+                span: None,
                 issue: "No alphabetic characters found".to_string(),
                 suggestion: Some("Enter text with letters".to_string()),
+                fixes: Vec::new(),
             }],
             suggestions: vec!["Try entering some words with letters".to_string()],
             metadata: serde_json::json!({
@@ -28,46 +107,183 @@ pub fn generate_anagrams(text: &str) -> Result<ConstraintResult> {
         });
     }
 
-    // Generate some simple anagrams by rearranging letters
-    let anagrams = generate_simple_anagrams(&clean_text);
-    
+    let mut letters: Vec<char> = clean_text.chars().collect();
+    letters.sort();
+    letters.dedup();
+    let alphabet = Alphabet(letters);
+    let full_bag = alphabet.bag(&clean_text).expect("phrase letters are in its own alphabet");
+
+    // Required words are taken out of the phrase before searching
+    let mut remaining = full_bag.clone();
+    let mut required = Vec::new();
+    for word in &options.required_words {
+        match alphabet.bag(word).filter(|bag| remaining.contains(bag)) {
+            Some(bag) => {
+                remaining = remaining.subtract(&bag);
+                required.push(word.to_lowercase());
+            }
+            None => {
+                return Ok(ConstraintResult::failure(
+                    format!("Required word '{}' cannot be formed from the phrase", word),
+                    vec![Violation {
+                        position: 0,
+                        length: text.len(),
+                        span: None,
+                        issue: format!("Not enough letters for required word '{}'", word),
+                        suggestion: Some("Remove the required word or change the phrase".to_string()),
+                        fixes: Vec::new(),
+                    }],
+                    vec!["Required words must use letters from the phrase".to_string()],
+                    serde_json::json!({
+                        "constraint_type": "anagram_generation",
+                        "original_text": text,
+                        "required_words": options.required_words
+                    }),
+                ));
+            }
+        }
+    }
+
+    let excluded: Vec<String> = options.excluded_words.iter().map(|w| w.to_lowercase()).collect();
+    let mut candidates: Vec<Candidate> = dictionary
+        .words()
+        .filter(|word| word.chars().count() >= options.min_word_length.max(1))
+        .filter(|word| !excluded.iter().any(|ex| ex == word))
+        .filter_map(|word| {
+            let bag = alphabet.bag(word)?;
+            remaining.contains(&bag).then(|| Candidate { word: word.to_string(), bag })
+        })
+        .collect();
+    // Longer words first: they shrink the search fastest and give the best anagrams
+    candidates.sort_by(|a, b| b.word.len().cmp(&a.word.len()).then_with(|| a.word.cmp(&b.word)));
+    if let Some(seed) = options.seed {
+        candidates.shuffle(&mut seeded_rng(seed));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.word.len()));
+    }
+
+    let max_words = options.max_words.max(1).saturating_sub(required.len());
+    let collect_limit = options.max_results.max(1) * 20;
+    let mut search = Search {
+        candidates: &candidates,
+        max_words,
+        collect_limit,
+        nodes: 0,
+        found: Vec::new(),
+        stack: Vec::new(),
+    };
+    let indices: Vec<usize> = (0..candidates.len()).collect();
+    if remaining.is_empty() {
+        search.found.push(Vec::new());
+    } else if max_words > 0 {
+        search.run(&remaining, &indices);
+    }
+    let exhausted = search.nodes < SEARCH_NODE_BUDGET && search.found.len() < collect_limit;
+
+    let mut anagrams: Vec<Vec<String>> = search
+        .found
+        .into_iter()
+        .map(|mut words| {
+            words.splice(0..0, required.iter().cloned());
+            words
+        })
+        .filter(|words| words.join("") != clean_text || words.len() > 1)
+        .collect();
+    if options.seed.is_some() {
+        // Ties keep the seeded discovery order
+        anagrams.sort_by_key(|words| {
+            let (count, shortest, _) = rank_key(words);
+            (count, shortest)
+        });
+        let mut seen = std::collections::HashSet::new();
+        anagrams.retain(|words| seen.insert(words.clone()));
+    } else {
+        anagrams.sort_by_key(|words| rank_key(words));
+        anagrams.dedup();
+    }
+    anagrams.truncate(options.max_results);
+
+    let phrases: Vec<String> = anagrams.iter().map(|words| words.join(" ")).collect();
+    let success = !phrases.is_empty();
+
     Ok(ConstraintResult {
-        success: true,
-        result: Some(anagrams.join(", ")),
+        success,
+        result: Some(if success { phrases.join(", ") } else { "No anagrams found".to_string() }),
         violations: vec![],
-        suggestions: vec![
-            "Try different letter combinations".to_string(),
-            "Look for meaningful words in the anagrams".to_string(),
-        ],
+        suggestions: if success {
+            vec![
+                "Try different letter combinations".to_string(),
+                "Look for meaningful words in the anagrams".to_string(),
+            ]
+        } else {
+            vec![
+                "Lower the minimum word length".to_string(),
+                "Allow more words per anagram".to_string(),
+                "Load a larger dictionary".to_string(),
+            ]
+        },
         metadata: serde_json::json!({
             "constraint_type": "anagram_generation",
-            "anagram_count": anagrams.len(),
+            "anagram_count": phrases.len(),
+            "anagrams": phrases,
             "original_text": text,
-            "letter_frequency": get_letter_frequency(&clean_text)
+            "candidate_words": candidates.len(),
+            "search_nodes": search.nodes,
+            "search_exhausted": exhausted,
+            "letter_frequency": get_letter_frequency(&clean_text),
+            "random_seed": options.seed
         }),
     })
 }
 
-fn generate_simple_anagrams(text: &str) -> Vec<String> {
-    let mut chars: Vec<char> = text.chars().collect();
-    let mut anagrams = Vec::new();
-    
-    // Generate a few simple permutations
-    for _i in 0..std::cmp::min(5, text.len()) {
-        chars.rotate_left(1);
-        let anagram: String = chars.iter().collect();
-        if anagram != text && !anagrams.contains(&anagram) {
-            anagrams.push(anagram);
+/// Depth-first search state for the anagram solver
+struct Search<'a> {
+    candidates: &'a [Candidate],
+    max_words: usize,
+    collect_limit: usize,
+    nodes: usize,
+    found: Vec<Vec<String>>,
+    stack: Vec<usize>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, remaining: &LetterBag, fitting: &[usize]) {
+        for (pos, &idx) in fitting.iter().enumerate() {
+            if self.nodes >= SEARCH_NODE_BUDGET || self.found.len() >= self.collect_limit {
+                return;
+            }
+            self.nodes += 1;
+
+            let rest = remaining.subtract(&self.candidates[idx].bag);
+            self.stack.push(idx);
+            if rest.is_empty() {
+                self.found.push(self.stack.iter().map(|&i| self.candidates[i].word.clone()).collect());
+            } else if self.stack.len() < self.max_words {
+                // Only words at or after this one, so each combination is tried once
+                let next: Vec<usize> = fitting[pos..]
+                    .iter()
+                    .copied()
+                    .filter(|&i| rest.contains(&self.candidates[i].bag))
+                    .collect();
+                if !next.is_empty() {
+                    self.run(&rest, &next);
+                }
+            }
+            self.stack.pop();
         }
     }
-    
-    // Add some reversed versions
-    let reversed: String = text.chars().rev().collect();
-    if reversed != text && !anagrams.contains(&reversed) {
-        anagrams.push(reversed);
-    }
-    
-    anagrams
+}
+
+/// Ranking: fewer words, then longer shortest word, then alphabetical
+fn rank_key(words: &[String]) -> (usize, std::cmp::Reverse<usize>, String) {
+    let shortest = words.iter().map(|w| w.chars().count()).min().unwrap_or(0);
+    (words.len(), std::cmp::Reverse(shortest), words.join(" "))
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
 }
 
 fn get_letter_frequency(text: &str) -> HashMap<char, usize> {
@@ -103,8 +319,10 @@ pub fn check_anagram(text1: &str, text2: &str) -> Result<ConstraintResult> {
             vec![Violation {
                 position: 0,
                 length: text2.len(),
+                span: None,
                 issue: "Letter frequencies don't match".to_string(),
                 suggestion: Some("Rearrange letters to match the first text".to_string()),
+                fixes: Vec::new(),
             }]
         },
         suggestions: if is_anagram {
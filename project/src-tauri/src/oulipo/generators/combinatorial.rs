@@ -1,10 +1,22 @@
 // Combinatorial poetry generation
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    ConstraintResult, Violation,
+    errors::{OulipoError, OulipoResult},
+    generators::{resolve_seed, seeded_rng},
+    rhyme::{end_word, RhymeEngine, RhymeLevel},
+    utils::line_spans,
+};
 use anyhow::Result;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
+use std::collections::BTreeSet;
 
-pub fn generate_combinatorial_poem(words: Vec<String>, structure: &str) -> Result<ConstraintResult> {
+/// Arrange words into a poem following a structure
+///
+/// The random structure (also used for unknown names) shuffles with the given
+/// seed, or a fresh one, which is recorded in the metadata as `random_seed`.
+/// The other structures are deterministic and record no seed.
+pub fn generate_combinatorial_poem(words: Vec<String>, structure: &str, seed: Option<u64>) -> Result<ConstraintResult> {
     if words.is_empty() {
         return Ok(ConstraintResult {
             success: false,
@@ -12,8 +24,10 @@ pub fn generate_combinatorial_poem(words: Vec<String>, structure: &str) -> Resul
             violations: vec![Violation {
                 position: 0,
                 length: 0,
+                span: None,
                 issue: "No words provided".to_string(),
                 suggestion: Some("Provide a list of words to combine".to_string()),
+                fixes: Vec::new(),
             }],
             suggestions: vec!["Enter at least 3-5 words".to_string()],
             metadata: serde_json::json!({
@@ -23,12 +37,14 @@ pub fn generate_combinatorial_poem(words: Vec<String>, structure: &str) -> Resul
         });
     }
 
-    let poem = match structure {
-        "random" => generate_random_combination(&words),
-        "ascending" => generate_ascending_combination(&words),
-        "chiasmus" => generate_chiasmus(&words),
-        "spiral" => generate_spiral_combination(&words),
-        _ => generate_random_combination(&words),
+    let (poem, seed) = match structure {
+        "ascending" => (generate_ascending_combination(&words), None),
+        "chiasmus" => (generate_chiasmus(&words), None),
+        "spiral" => (generate_spiral_combination(&words), None),
+        _ => {
+            let seed = resolve_seed(seed);
+            (generate_random_combination(&words, &mut seeded_rng(seed)), Some(seed))
+        }
     };
 
     Ok(ConstraintResult {
@@ -44,15 +60,15 @@ pub fn generate_combinatorial_poem(words: Vec<String>, structure: &str) -> Resul
             "constraint_type": "combinatorial_poem",
             "structure": structure,
             "word_count": words.len(),
-            "input_words": words
+            "input_words": words,
+            "random_seed": seed
         }),
     })
 }
 
-fn generate_random_combination(words: &[String]) -> String {
-    let mut rng = thread_rng();
+fn generate_random_combination<R: Rng>(words: &[String], rng: &mut R) -> String {
     let mut shuffled = words.to_vec();
-    shuffled.shuffle(&mut rng);
+    shuffled.shuffle(rng);
     
     let lines: Vec<String> = shuffled.chunks(3)
         .map(|chunk| chunk.join(" "))
@@ -70,12 +86,10 @@ fn generate_ascending_combination(words: &[String]) -> String {
     let mut current_length = 0;
     
     for word in sorted_words {
-        if current_length + word.len() > 50 || current_line.len() >= 4 {
-            if !current_line.is_empty() {
-                lines.push(current_line.join(" "));
-                current_line.clear();
-                current_length = 0;
-            }
+        if (current_length + word.len() > 50 || current_line.len() >= 4) && !current_line.is_empty() {
+            lines.push(current_line.join(" "));
+            current_line.clear();
+            current_length = 0;
         }
         current_line.push(word.clone());
         current_length += word.len() + 1;
@@ -134,3 +148,238 @@ fn generate_spiral_combination(words: &[String]) -> String {
     
     lines.join("\n")
 }
+
+/// A "Cent mille milliards de poèmes" book: N source poems with interchangeable lines
+///
+/// Every poem of the book takes each of its lines from any one of the sources,
+/// so a book of N sources with L lines holds N^L poems. Poems are addressed by
+/// an index read as an L-digit number in base N: the first line's source is
+/// the most significant digit, so index 0 is the first source unchanged.
+#[derive(Debug, Clone)]
+pub struct CombinatorialBook {
+    sources: Vec<Vec<String>>,
+    total: u64,
+}
+
+impl CombinatorialBook {
+    /// Create a book from source poems given as lists of lines
+    ///
+    /// Every source needs the same, non-zero number of lines, and the number
+    /// of poems must fit in a `u64` (20 sonnets is the practical limit).
+    pub fn new(sources: Vec<Vec<String>>) -> OulipoResult<Self> {
+        let sources: Vec<Vec<String>> = sources
+            .into_iter()
+            .map(|lines| lines.into_iter().map(|line| line.trim_end().to_string()).filter(|line| !line.trim().is_empty()).collect())
+            .collect();
+        let line_count = sources.first().map_or(0, Vec::len);
+        if line_count == 0 {
+            return Err(OulipoError::InvalidConfig("A combinatorial book needs at least one source poem with lines".to_string()));
+        }
+        if let Some((i, source)) = sources.iter().enumerate().find(|(_, s)| s.len() != line_count) {
+            return Err(OulipoError::InvalidConfig(format!(
+                "Source {} has {} lines, but source 1 has {}; every source must have the same number of lines",
+                i + 1,
+                source.len(),
+                line_count
+            )));
+        }
+        let total = u32::try_from(line_count)
+            .ok()
+            .and_then(|lines| (sources.len() as u64).checked_pow(lines))
+            .ok_or_else(|| OulipoError::InvalidConfig("Too many combinations to address".to_string()))?;
+        Ok(Self { sources, total })
+    }
+
+    /// Create a book from source poem texts, one line per non-empty line
+    pub fn from_texts(texts: &[&str]) -> OulipoResult<Self> {
+        Self::new(
+            texts
+                .iter()
+                .map(|text| line_spans(text).into_iter().map(|(_, line)| line.to_string()).collect())
+                .collect(),
+        )
+    }
+
+    /// Number of source poems
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Lines in every source and every poem
+    pub fn line_count(&self) -> usize {
+        self.sources[0].len()
+    }
+
+    /// Number of distinct poems, N^L
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The source (0-based) of each line of the poem at `index`
+    pub fn choices(&self, index: u64) -> Option<Vec<usize>> {
+        if index >= self.total {
+            return None;
+        }
+        let base = self.sources.len() as u64;
+        let mut remaining = index;
+        let mut choices = vec![0; self.line_count()];
+        for choice in choices.iter_mut().rev() {
+            *choice = (remaining % base) as usize;
+            remaining /= base;
+        }
+        Some(choices)
+    }
+
+    /// The index of the poem taking each line from the given source (0-based)
+    pub fn index_of(&self, choices: &[usize]) -> Option<u64> {
+        if choices.len() != self.line_count() || choices.iter().any(|&c| c >= self.sources.len()) {
+            return None;
+        }
+        let base = self.sources.len() as u64;
+        Some(choices.iter().fold(0, |index, &choice| index * base + choice as u64))
+    }
+
+    /// The lines of the poem at `index`
+    pub fn lines(&self, index: u64) -> Option<Vec<&str>> {
+        let choices = self.choices(index)?;
+        Some(choices.iter().enumerate().map(|(line, &source)| self.sources[source][line].as_str()).collect())
+    }
+
+    /// The poem at `index`, one line per line
+    pub fn poem(&self, index: u64) -> Option<String> {
+        self.lines(index).map(|lines| lines.join("\n"))
+    }
+
+    /// `count` consecutive poems starting at `start`, with their indices
+    pub fn enumerate(&self, start: u64, count: usize) -> Vec<(u64, String)> {
+        (start..self.total)
+            .take(count)
+            .filter_map(|index| self.poem(index).map(|poem| (index, poem)))
+            .collect()
+    }
+
+    /// Up to `count` distinct poem indices drawn from `rng`, in drawing order
+    pub fn sample<R: Rng>(&self, count: usize, rng: &mut R) -> Vec<u64> {
+        let count = count.min(usize::try_from(self.total).unwrap_or(usize::MAX));
+        let mut seen = BTreeSet::new();
+        // A book may hold far more poems than could ever be preallocated for
+        let mut indices = Vec::new();
+        while indices.len() < count {
+            let index = rng.gen_range(0..self.total);
+            if seen.insert(index) {
+                indices.push(index);
+            }
+        }
+        indices
+    }
+
+    /// Check that the sources' lines can be swapped without breaking the rhyme
+    ///
+    /// The first source's rhyme scheme is the reference. At every line that
+    /// rhymes with another in that scheme, each source's end word must rhyme
+    /// with the first source's end word for the group, so any choice of lines
+    /// keeps the scheme. Violations are positioned within the offending source,
+    /// with its lines joined by newlines.
+    pub fn check_interchangeable(&self, engine: &RhymeEngine, min_level: RhymeLevel) -> ConstraintResult {
+        let end_words: Vec<Vec<&str>> = self
+            .sources
+            .iter()
+            .map(|lines| lines.iter().map(|line| end_word(line).map_or("", |(_, word)| word)).collect())
+            .collect();
+        let scheme = engine.detect_scheme(&end_words[0], min_level);
+
+        let mut violations = Vec::new();
+        for (s, lines) in self.sources.iter().enumerate().skip(1) {
+            let mut offset = 0;
+            for (line, text) in lines.iter().enumerate() {
+                let anchor = scheme.iter().position(|&letter| letter == scheme[line]).unwrap_or(line);
+                let rhymed = scheme.iter().filter(|&&letter| letter == scheme[line]).count() > 1;
+                let (word, reference) = (end_words[s][line], end_words[0][anchor]);
+                if rhymed && !engine.rhymes(word, reference, min_level) {
+                    let (word_offset, _) = end_word(text).unwrap_or((0, ""));
+                    violations.push(Violation {
+                        position: offset + word_offset,
+                        length: word.len(),
+                        span: None,
+                        issue: format!(
+                            "Source {}, line {} ends in '{}', which does not rhyme with '{}' (source 1, line {})",
+                            s + 1,
+                            line + 1,
+                            word,
+                            reference,
+                            anchor + 1
+                        ),
+                        suggestion: Some(format!("End the line with a word rhyming with '{}'", reference)),
+                        fixes: Vec::new(),
+                    });
+                }
+                offset += text.len() + 1;
+            }
+        }
+
+        let success = violations.is_empty();
+        let violation_count = violations.len();
+        ConstraintResult {
+            success,
+            result: Some(if success {
+                format!("{} interchangeable sources make {} poems", self.sources.len(), self.total)
+            } else {
+                format!("{} lines would break the rhyme when swapped", violation_count)
+            }),
+            violations,
+            suggestions: if success {
+                vec!["Every combination keeps the rhyme scheme".to_string()]
+            } else {
+                vec![
+                    "Give every source the same rhyme sounds at the same positions".to_string(),
+                    "Queneau's sonnets share their rhymes exactly, not just their scheme".to_string(),
+                ]
+            },
+            metadata: serde_json::json!({
+                "constraint_type": "combinatorial_book",
+                "source_count": self.sources.len(),
+                "line_count": self.line_count(),
+                "total": self.total,
+                "rhyme_scheme": scheme.iter().collect::<String>(),
+                "violation_count": violation_count
+            }),
+        }
+    }
+}
+
+/// Read the poem at `index` from a book, or a random one when no index is given
+///
+/// The result is the poem; metadata records its index and the source of each
+/// line (1-based). A random poem is drawn with the given seed, or a fresh one,
+/// recorded as `random_seed`.
+pub fn book_poem(book: &CombinatorialBook, index: Option<u64>, seed: Option<u64>) -> Result<ConstraintResult> {
+    let (index, seed) = match index {
+        Some(index) => (index, None),
+        None => {
+            let seed = resolve_seed(seed);
+            (seeded_rng(seed).gen_range(0..book.total()), Some(seed))
+        }
+    };
+    let poem = book.poem(index).ok_or_else(|| {
+        OulipoError::InvalidConfig(format!("Poem {} is out of range; the book has {} poems", index, book.total()))
+    })?;
+    let sources: Vec<usize> = book.choices(index).unwrap_or_default().iter().map(|c| c + 1).collect();
+
+    Ok(ConstraintResult {
+        success: true,
+        result: Some(poem),
+        violations: vec![],
+        suggestions: vec![
+            format!("This is poem {} of {}", index + 1, book.total()),
+            "Share the index to let others read the same poem".to_string(),
+        ],
+        metadata: serde_json::json!({
+            "constraint_type": "combinatorial_book",
+            "index": index,
+            "line_sources": sources,
+            "source_count": book.source_count(),
+            "total": book.total(),
+            "random_seed": seed
+        }),
+    })
+}
@@ -1,77 +1,219 @@
-use crate::oulipo::ConstraintResult;
+use crate::oulipo::{
+    ConstraintResult,
+    constraints::haiku::{self, HAIKU_PATTERN},
+    errors::OulipoError,
+    generators::{record_seed, resolve_seed, seeded_rng},
+    phonetics::PronunciationDictionary,
+    utils::match_case,
+};
 use anyhow::Result;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
-/// Generate haiku following 5-7-5 syllable pattern
-pub fn generate(theme: &str) -> Result<ConstraintResult> {
-    let haiku = match theme.to_lowercase().as_str() {
-        "nature" => generate_nature_haiku(),
-        "seasons" => generate_seasonal_haiku(),
-        "love" => generate_love_haiku(),
-        "time" => generate_time_haiku(),
-        _ => generate_default_haiku(),
-    };
-    
-    Ok(ConstraintResult {
-        success: true,
-        result: Some(haiku.clone()),
-        violations: Vec::new(),
-        suggestions: vec![
-            "Traditional haiku captures a moment in nature".to_string(),
-            "Focus on sensory imagery".to_string(),
-            "Include a seasonal reference (kigo)".to_string(),
-            "Create a pause or break (kireji)".to_string(),
-        ],
-        metadata: serde_json::json!({
-            "theme": theme,
-            "syllable_pattern": "5-7-5",
-            "lines": haiku.lines().count(),
-            "traditional_elements": ["kigo", "kireji", "present_tense"]
-        }),
-    })
+/// How many full haiku to assemble before giving up
+const MAX_ATTEMPTS: usize = 50;
+
+/// A slot in a line template
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Adjective,
+    Noun,
+    /// Third person singular present ("sings"), agreeing with a singular noun
+    Verb,
+    Adverb,
+    Preposition,
+    Word(&'static str),
 }
 
-fn generate_nature_haiku() -> String {
-    let haikus = [
-        "Cherry blossoms fall\nSilent pond reflects the moon\nSpring wind carries peace",
-        "Ancient oak stands tall\nRoots deep in the fertile earth\nLeaves dance with the sky",
-        "Morning dew glistens\nOn grass blades bent by the weight\nSun breaks through the mist",
-        "River flows swiftly\nCarrying stories downstream\nTo the waiting sea",
-    ];
-    
-    haikus[0].to_string() // In a real implementation, this would be random
+/// Line shapes the generator fills with words from a theme's bank
+const LINE_TEMPLATES: &[&[Slot]] = &[
+    &[Slot::Adjective, Slot::Noun],
+    &[Slot::Word("the"), Slot::Adjective, Slot::Noun],
+    &[Slot::Adjective, Slot::Adjective, Slot::Noun],
+    &[Slot::Noun, Slot::Verb],
+    &[Slot::Adjective, Slot::Noun, Slot::Verb],
+    &[Slot::Word("the"), Slot::Noun, Slot::Verb, Slot::Adverb],
+    &[Slot::Preposition, Slot::Word("the"), Slot::Adjective, Slot::Noun],
+    &[Slot::Adjective, Slot::Noun, Slot::Preposition, Slot::Word("the"), Slot::Noun],
+    &[Slot::Word("the"), Slot::Noun, Slot::Verb, Slot::Preposition, Slot::Word("the"), Slot::Noun],
+    &[Slot::Adverb, Slot::Word("the"), Slot::Adjective, Slot::Noun, Slot::Verb],
+];
+
+const PREPOSITIONS: &[&str] = &[
+    "in", "on", "through", "beneath", "above", "across", "along", "beyond", "upon", "over", "under", "toward",
+];
+
+/// Theme-specific vocabulary
+struct WordBank {
+    adjectives: &'static [&'static str],
+    nouns: &'static [&'static str],
+    verbs: &'static [&'static str],
+    adverbs: &'static [&'static str],
+}
+
+const NATURE: WordBank = WordBank {
+    adjectives: &["quiet", "ancient", "green", "still", "gentle", "silver", "deep", "hollow", "misty", "golden", "wild"],
+    nouns: &["pond", "frog", "river", "mountain", "forest", "meadow", "stone", "moon", "cherry blossom", "crow", "dew", "cricket", "willow"],
+    verbs: &["sleeps", "drifts", "glows", "sings", "waits", "lingers", "whispers", "falls", "rises", "shines"],
+    adverbs: &["softly", "slowly", "alone", "again", "tonight"],
+};
+
+const SEASONS: WordBank = WordBank {
+    adjectives: &["autumn", "winter", "summer", "cold", "warm", "pale", "golden", "first", "bitter", "late"],
+    nouns: &["leaf", "snow", "frost", "harvest", "wind", "rain", "blossom", "field", "sun", "ice", "fog"],
+    verbs: &["falls", "melts", "fades", "drifts", "returns", "settles", "lingers", "blooms", "turns"],
+    adverbs: &["slowly", "softly", "again", "at last", "once more"],
+};
+
+const LOVE: WordBank = WordBank {
+    adjectives: &["tender", "gentle", "warm", "secret", "distant", "sweet", "faithful", "quiet", "bright"],
+    nouns: &["heart", "kiss", "hand", "promise", "letter", "smile", "embrace", "lantern", "rose", "dream"],
+    verbs: &["waits", "glows", "trembles", "lingers", "whispers", "beats", "remembers", "returns", "burns"],
+    adverbs: &["softly", "always", "tonight", "forever", "again"],
+};
+
+const TIME: WordBank = WordBank {
+    adjectives: &["old", "patient", "endless", "fleeting", "silent", "brief", "ancient", "slow", "final"],
+    nouns: &["clock", "hour", "moment", "shadow", "candle", "season", "memory", "bell", "hourglass", "dusk"],
+    verbs: &["passes", "turns", "fades", "waits", "slips", "ticks", "returns", "burns", "ends"],
+    adverbs: &["slowly", "again", "at last", "always", "too soon"],
+};
+
+const DEFAULT_BANK: WordBank = WordBank {
+    adjectives: &["bright", "quiet", "restless", "open", "simple", "hidden", "gentle", "strange", "new"],
+    nouns: &["word", "page", "thought", "letter", "pencil", "window", "story", "silence", "voice", "line"],
+    verbs: &["flows", "waits", "shapes", "turns", "finds", "opens", "sings", "rests", "grows"],
+    adverbs: &["softly", "slowly", "again", "today", "somehow"],
+};
+
+fn word_bank(theme: &str) -> &'static WordBank {
+    match theme.to_lowercase().as_str() {
+        "nature" => &NATURE,
+        "seasons" => &SEASONS,
+        "love" => &LOVE,
+        "time" => &TIME,
+        _ => &DEFAULT_BANK,
+    }
 }
 
-fn generate_seasonal_haiku() -> String {
-    let haikus = [
-        "Autumn leaves spiral\nGolden carpet on the path\nTime's gentle passage",
-        "Winter's first snowfall\nSilence blankets the garden\nWorld holds its breath",
-        "Spring buds emerging\nPromise of renewal blooms\nLife begins again",
-        "Summer heat shimmers\nCicadas sing their stories\nAfternoon dreams",
-    ];
-    
-    haikus[0].to_string()
+/// Generate haiku following 5-7-5 syllable pattern
+///
+/// The same seed always gives the same haiku; without one a fresh seed is
+/// drawn. Either way the seed is recorded in the metadata as `random_seed`.
+pub fn generate(theme: &str, pronunciations: &PronunciationDictionary, seed: Option<u64>) -> Result<ConstraintResult> {
+    let seed = resolve_seed(seed);
+    let mut result = generate_with(theme, pronunciations, &mut seeded_rng(seed))?;
+    record_seed(&mut result, Some(seed));
+    Ok(result)
 }
 
-fn generate_love_haiku() -> String {
-    let haikus = [
-        "Two hearts beat as one\nIn the quiet of twilight\nLove needs no words",
-        "Your gentle smile\nLights up the darkest corner\nOf my waiting heart",
-        "Distance cannot dim\nThe warmth of our connection\nSouls know no borders",
-    ];
-    
-    haikus[0].to_string()
+/// Assemble a themed haiku from the word bank, verified against the 5-7-5 constraint
+pub fn generate_with<R: Rng>(
+    theme: &str,
+    pronunciations: &PronunciationDictionary,
+    rng: &mut R,
+) -> Result<ConstraintResult> {
+    let bank = word_bank(theme);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let lines: Option<Vec<String>> = HAIKU_PATTERN
+            .iter()
+            .map(|&target| compose_line(bank, target, pronunciations, rng))
+            .collect();
+        let Some(lines) = lines else {
+            continue;
+        };
+
+        let haiku = lines.join("\n");
+        let verification = haiku::check(&haiku, pronunciations)?;
+        if !verification.success {
+            continue;
+        }
+
+        return Ok(ConstraintResult {
+            success: true,
+            result: Some(haiku.clone()),
+            violations: Vec::new(),
+            suggestions: vec![
+                "Traditional haiku captures a moment in nature".to_string(),
+                "Focus on sensory imagery".to_string(),
+                "Include a seasonal reference (kigo)".to_string(),
+                "Create a pause or break (kireji)".to_string(),
+            ],
+            metadata: serde_json::json!({
+                "theme": theme,
+                "syllable_pattern": "5-7-5",
+                "line_syllables": verification.metadata["line_syllables"],
+                "lines": haiku.lines().count(),
+                "attempts": attempt,
+                "verified": true,
+                "traditional_elements": ["kigo", "kireji", "present_tense"]
+            }),
+        });
+    }
+
+    Err(OulipoError::GenerationFailed(
+        format!("Could not assemble a 5-7-5 haiku for theme '{}'", theme)
+    ).into())
 }
 
-fn generate_time_haiku() -> String {
-    let haikus = [
-        "Clock hands circle round\nMoments slip like grains of sand\nNow is all we have",
-        "Yesterday's shadows\nFade into tomorrow's light\nPresent moment shines",
-        "Seasons turn again\nEternal cycle of change\nTime's patient wisdom",
-    ];
-    
-    haikus[0].to_string()
+/// Fill a random template with words whose syllables add up to `target`
+fn compose_line<R: Rng>(
+    bank: &WordBank,
+    target: usize,
+    pronunciations: &PronunciationDictionary,
+    rng: &mut R,
+) -> Option<String> {
+    let mut templates: Vec<&[Slot]> = LINE_TEMPLATES.to_vec();
+    templates.shuffle(rng);
+
+    for template in templates {
+        let mut words = Vec::with_capacity(template.len());
+        if fill_slots(template, bank, target, pronunciations, rng, &mut words) {
+            // Bank words are all lowercase, so only the first letter changes
+            return Some(match_case("A", &words.join(" ")));
+        }
+    }
+    None
 }
 
-fn generate_default_haiku() -> String {
-    "Words flow like water\nConstraints shape creative thought\nBeauty finds its way".to_string()
+fn fill_slots<R: Rng>(
+    slots: &[Slot],
+    bank: &WordBank,
+    remaining: usize,
+    pronunciations: &PronunciationDictionary,
+    rng: &mut R,
+    words: &mut Vec<&'static str>,
+) -> bool {
+    let Some((slot, rest)) = slots.split_first() else {
+        return remaining == 0;
+    };
+
+    let mut options: Vec<&'static str> = match slot {
+        Slot::Adjective => bank.adjectives.to_vec(),
+        Slot::Noun => bank.nouns.to_vec(),
+        Slot::Verb => bank.verbs.to_vec(),
+        Slot::Adverb => bank.adverbs.to_vec(),
+        Slot::Preposition => PREPOSITIONS.to_vec(),
+        Slot::Word(word) => vec![*word],
+    };
+    options.shuffle(rng);
+
+    for option in options {
+        // Repeating a content word inside one line reads badly
+        if !matches!(slot, Slot::Word(_)) && words.contains(&option) {
+            continue;
+        }
+        let syllables = pronunciations.text_syllables(option);
+        // Every remaining slot needs at least one syllable
+        if syllables + rest.len() > remaining {
+            continue;
+        }
+        words.push(option);
+        if fill_slots(rest, bank, remaining - syllables, pronunciations, rng, words) {
+            return true;
+        }
+        words.pop();
+    }
+    false
 }
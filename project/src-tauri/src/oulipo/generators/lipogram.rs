@@ -0,0 +1,242 @@
+// Lipogram rewriting by synonym substitution
+use crate::oulipo::{
+    ConstraintResult, Fix, Violation,
+    dictionary::Dictionary,
+    errors::OulipoError,
+    thesaurus::Thesaurus,
+    utils::{match_case, pluralize, singular_candidates, word_spans},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Options for lipogram rewriting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LipogramRewriteOptions {
+    /// Synonyms offered for each offending word
+    pub max_alternatives: usize,
+    /// Also produce the passage with every offending word swapped for its best synonym
+    pub full_rewrite: bool,
+}
+
+impl Default for LipogramRewriteOptions {
+    fn default() -> Self {
+        Self {
+            max_alternatives: 5,
+            full_rewrite: false,
+        }
+    }
+}
+
+/// A synonym with its ranking score between 0 and 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedSynonym {
+    pub word: String,
+    pub score: f64,
+}
+
+/// Replace the word at `position..position + length` with `replacement`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LipogramEdit {
+    /// Byte offset of the offending word
+    pub position: usize,
+    /// Byte length of the offending word
+    pub length: usize,
+    pub original: String,
+    /// The best-ranked synonym, in the original word's case
+    pub replacement: String,
+    /// Every acceptable synonym, best first (the replacement included)
+    pub alternatives: Vec<RankedSynonym>,
+}
+
+/// Propose synonym swaps for every word that uses a forbidden letter
+///
+/// Synonyms come from the thesaurus and are kept only when they avoid every
+/// forbidden letter. They are ranked by how close the thesaurus lists them,
+/// how near their length is to the original, and whether the dictionary knows
+/// them. Plurals are matched through their singular, so "trees" can become
+/// "oaks". Words without an acceptable synonym are reported as violations.
+pub fn rewrite(
+    text: &str,
+    forbidden_letters: &[char],
+    dictionary: &Dictionary,
+    thesaurus: &Thesaurus,
+    options: &LipogramRewriteOptions,
+) -> Result<ConstraintResult> {
+    if forbidden_letters.is_empty() || !forbidden_letters.iter().all(|c| c.is_alphabetic()) {
+        return Err(OulipoError::InvalidConfig("Forbidden letters must be one or more letters".to_string()).into());
+    }
+    let forbidden: Vec<char> = forbidden_letters.iter().flat_map(|c| c.to_lowercase()).collect();
+    let avoids = |word: &str| !word.to_lowercase().chars().any(|c| forbidden.contains(&c));
+    let listed = forbidden.iter().map(|c| format!("'{}'", c)).collect::<Vec<_>>().join(", ");
+
+    let mut edits = Vec::new();
+    let mut violations = Vec::new();
+    let mut offending = 0;
+
+    for (position, word) in word_spans(text) {
+        if avoids(word) {
+            continue;
+        }
+        offending += 1;
+
+        let alternatives = alternatives(word, avoids, dictionary, thesaurus, options.max_alternatives);
+
+        match alternatives.first() {
+            Some(best) => edits.push(LipogramEdit {
+                position,
+                length: word.len(),
+                original: word.to_string(),
+                replacement: best.word.clone(),
+                alternatives: alternatives.clone(),
+            }),
+            None => violations.push(Violation {
+                position,
+                length: word.len(),
+                span: None,
+                issue: format!("No synonym for '{}' avoids {}", word, listed),
+                suggestion: Some("Rephrase the sentence around this word".to_string()),
+                fixes: Vec::new(),
+            }),
+        }
+    }
+
+    let rewritten = options.full_rewrite.then(|| apply_edits(text, &edits));
+    let success = violations.is_empty();
+    let suggestions = if offending == 0 {
+        vec!["Text already avoids every forbidden letter".to_string()]
+    } else if success {
+        vec!["Every offending word has a synonym; review the swaps for sense".to_string()]
+    } else {
+        vec![
+            "Reword sentences whose key words have no lipogrammatic synonym".to_string(),
+            "Load a larger thesaurus for more candidates".to_string(),
+        ]
+    };
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(rewritten.clone().unwrap_or_else(|| {
+            format!("{} of {} offending words have replacements", edits.len(), offending)
+        })),
+        violations,
+        suggestions,
+        metadata: serde_json::json!({
+            "constraint_type": "lipogram_rewrite",
+            "forbidden_letters": forbidden.iter().map(char::to_string).collect::<Vec<_>>(),
+            "offending_words": offending,
+            "edits": edits,
+            "rewritten": rewritten,
+            "verified": rewritten.as_deref().is_some_and(avoids)
+        }),
+    }.located(text))
+}
+
+/// Apply non-overlapping edits, given in text order
+pub fn apply_edits(text: &str, edits: &[LipogramEdit]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for edit in edits {
+        if edit.position < cursor {
+            continue;
+        }
+        output.push_str(&text[cursor..edit.position]);
+        output.push_str(&edit.replacement);
+        cursor = edit.position + edit.length;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+/// Attach synonym swaps as fixes to the violations of a lipogram check
+///
+/// Every violation inside an offending word gets that word's acceptable
+/// synonyms, best first, with their ranking score as the fix confidence.
+/// The violations must be in text order. The fixes are not located; locate
+/// the result once they are attached.
+pub fn with_synonym_fixes(
+    mut result: ConstraintResult,
+    text: &str,
+    forbidden_letters: &[char],
+    dictionary: &Dictionary,
+    thesaurus: &Thesaurus,
+    max_alternatives: usize,
+) -> ConstraintResult {
+    if result.violations.is_empty() {
+        return result;
+    }
+    let forbidden: Vec<char> = forbidden_letters.iter().flat_map(|c| c.to_lowercase()).collect();
+    let avoids = |word: &str| !word.to_lowercase().chars().any(|c| forbidden.contains(&c));
+
+    // Violations and words are both in text order, so one cursor walks the violations
+    let mut next = 0;
+    for (position, word) in word_spans(text) {
+        let end = position + word.len();
+        while result.violations.get(next).is_some_and(|v| v.position < position) {
+            next += 1;
+        }
+        let first = next;
+        while result.violations.get(next).is_some_and(|v| v.position < end) {
+            next += 1;
+        }
+        if first == next || avoids(word) {
+            continue;
+        }
+        let fixes: Vec<Fix> = alternatives(word, avoids, dictionary, thesaurus, max_alternatives)
+            .into_iter()
+            .map(|synonym| Fix::new(format!("Replace with '{}'", synonym.word), position, word.len(), synonym.word, synonym.score))
+            .collect();
+        for violation in &mut result.violations[first..next] {
+            violation.fixes = fixes.clone();
+        }
+    }
+    result
+}
+
+/// The best synonyms of a word that pass `avoids`, in the word's case
+fn alternatives(
+    word: &str,
+    avoids: impl Fn(&str) -> bool,
+    dictionary: &Dictionary,
+    thesaurus: &Thesaurus,
+    max_alternatives: usize,
+) -> Vec<RankedSynonym> {
+    rank_synonyms(word, dictionary, thesaurus)
+        .into_iter()
+        .filter(|candidate| avoids(&candidate.word))
+        .take(max_alternatives.max(1))
+        .map(|candidate| RankedSynonym { word: match_case(word, &candidate.word), ..candidate })
+        .collect()
+}
+
+/// Synonyms of a word, or of its singular re-pluralised, best first
+fn rank_synonyms(word: &str, dictionary: &Dictionary, thesaurus: &Thesaurus) -> Vec<RankedSynonym> {
+    let lower = word.to_lowercase();
+    let mut listed: Vec<String> = thesaurus.synonyms(&lower, None);
+    if listed.is_empty() {
+        if let Some(singular) = singular_candidates(&lower).into_iter().find(|s| *s != lower && !thesaurus.senses(s).is_empty()) {
+            listed = thesaurus
+                .synonyms(&singular, None)
+                .iter()
+                .map(|synonym| if synonym.contains(' ') { synonym.clone() } else { pluralize(synonym) })
+                .collect();
+        }
+    }
+
+    let original_length = lower.chars().count() as f64;
+    let mut ranked: Vec<RankedSynonym> = listed
+        .into_iter()
+        .enumerate()
+        .filter(|(_, synonym)| *synonym != lower)
+        .map(|(rank, synonym)| {
+            let closeness = 1.0 / (1.0 + rank as f64);
+            let length = synonym.chars().count() as f64;
+            let fit = 1.0 - (length - original_length).abs() / length.max(original_length);
+            let known = if synonym.split(' ').all(|w| dictionary.contains_word(w)) { 1.0 } else { 0.0 };
+            let score = 0.6 * closeness + 0.25 * fit + 0.15 * known;
+            RankedSynonym { word: synonym, score: (score * 1000.0).round() / 1000.0 }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
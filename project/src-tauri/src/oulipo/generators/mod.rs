@@ -2,3 +2,26 @@
 pub mod haiku;
 pub mod combinatorial;
 pub mod anagram;
+pub mod lipogram;
+pub mod nina;
+pub mod palindrome;
+pub mod snowball;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The seed for a generator run: the one given, or a fresh one so the run can be repeated
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+/// A random number generator that always produces the same sequence for the same seed
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Record the seed a result was generated with in its metadata
+pub fn record_seed(result: &mut crate::oulipo::ConstraintResult, seed: Option<u64>) {
+    if let serde_json::Value::Object(metadata) = &mut result.metadata {
+        metadata.insert("random_seed".to_string(), serde_json::json!(seed));
+    }
+}
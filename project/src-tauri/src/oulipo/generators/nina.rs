@@ -0,0 +1,76 @@
+// Fill-in skeletons for sestinas and other n-inas
+use crate::oulipo::{
+    ConstraintResult,
+    constraints::nina::{self, envoi_length, stanza_orders},
+};
+use anyhow::Result;
+
+/// Placeholder for the part of a line the writer fills in
+const BLANK: &str = "__________";
+
+/// Emit an n-ina skeleton with every end word already in place
+///
+/// Each stanza line is a blank followed by its end word. The envoi follows the
+/// traditional sestina pairing, generalised: line k carries the (2k+2)th end word
+/// mid-line and ends on the odd-numbered words in reverse (for a sestina: 2/5, 4/3, 6/1).
+pub fn generate_skeleton(end_words: &[String]) -> Result<ConstraintResult> {
+    nina::validate_end_words(end_words)?;
+    let n = end_words.len();
+
+    let stanzas: Vec<String> = stanza_orders(n)
+        .iter()
+        .map(|order| {
+            order
+                .iter()
+                .map(|&idx| format!("{} {}", BLANK, end_words[idx]))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+
+    let pairs = envoi_pairs(n);
+    let envoi = pairs
+        .iter()
+        .map(|&(middle, end)| match middle {
+            Some(middle) => format!("{} {} {} {}", BLANK, end_words[middle], BLANK, end_words[end]),
+            None => format!("{} {}", BLANK, end_words[end]),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let skeleton = format!("{}\n\n{}", stanzas.join("\n\n"), envoi);
+    let verification = nina::check(&skeleton, end_words, true)?;
+
+    Ok(ConstraintResult {
+        success: verification.success,
+        result: Some(skeleton),
+        violations: verification.violations,
+        suggestions: vec![
+            format!("Replace each {} with your own words, keeping the end words in place", BLANK),
+            "Let each end word shift meaning as it moves through the stanzas".to_string(),
+            "The envoi should gather every end word one last time".to_string(),
+        ],
+        metadata: serde_json::json!({
+            "constraint_type": verification.metadata["constraint_type"],
+            "n": n,
+            "end_words": end_words,
+            "stanza_orders": verification.metadata["stanza_orders"],
+            "envoi_pairs": pairs.iter().map(|&(middle, end)| serde_json::json!({
+                "middle": middle.map(|i| end_words[i].clone()),
+                "end": end_words[end]
+            })).collect::<Vec<_>>(),
+            "line_count": n * n + envoi_length(n),
+            "verified": verification.success
+        }),
+    })
+}
+
+/// (middle word, end word) indexes for each envoi line
+fn envoi_pairs(n: usize) -> Vec<(Option<usize>, usize)> {
+    let mut middles = (1..n).step_by(2);
+    (0..n)
+        .step_by(2)
+        .rev()
+        .map(|end| (middles.next(), end))
+        .collect()
+}
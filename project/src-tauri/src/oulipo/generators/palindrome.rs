@@ -0,0 +1,326 @@
+// Centre-out palindrome building
+use crate::oulipo::{
+    ConstraintResult, Violation,
+    dictionary::Dictionary,
+    generators::seeded_rng,
+    utils::{is_palindrome, normalize_line},
+};
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Upper bound on search nodes so common seeds stay responsive
+const SEARCH_NODE_BUDGET: usize = 200_000;
+
+/// Options for the palindrome builder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PalindromeOptions {
+    /// Maximum number of palindromes to return
+    pub max_results: usize,
+    /// Maximum number of words added around the seed
+    pub max_words: usize,
+    /// Shortest dictionary word the builder may add
+    pub min_word_length: usize,
+    /// Words that must never be added
+    pub excluded_words: Vec<String>,
+    /// Vary which equally ranked palindromes are found and listed first; the
+    /// same seed gives the same results, and without one the order is alphabetical
+    pub seed: Option<u64>,
+}
+
+impl Default for PalindromeOptions {
+    fn default() -> Self {
+        Self {
+            max_results: 10,
+            max_words: 3,
+            min_word_length: 1,
+            excluded_words: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+/// The side of the centre that still owes letters
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// A partly built palindrome
+///
+/// `owed` holds the letters the `side` must still spell, read outward from the
+/// centre, before both halves mirror again.
+#[derive(Debug, Clone)]
+struct Partial {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    side: Side,
+    owed: String,
+}
+
+impl Partial {
+    fn is_complete(&self) -> bool {
+        self.owed.is_empty()
+    }
+
+    fn word_count(&self) -> usize {
+        self.left.len() + self.right.len()
+    }
+}
+
+/// A dictionary word with its letters in both reading directions
+struct Candidate {
+    word: String,
+    reversed: String,
+}
+
+/// Grow palindromes outward from a seed word using words from the dictionary
+///
+/// The centre of the palindrome is placed inside the seed or inside a word
+/// beside it. Letters left unmatched on one side are owed by the other, and
+/// words are added to whichever side owes letters until nothing is owed.
+/// Searching is breadth-first, so palindromes with fewer added words come first.
+pub fn generate_palindromes(seed: &str, dictionary: &Dictionary, options: &PalindromeOptions) -> Result<ConstraintResult> {
+    let letters = normalize(seed);
+
+    if letters.is_empty() {
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: seed.len(),
+                span: None,
+                issue: "No alphabetic characters found".to_string(),
+                suggestion: Some("Enter a seed word with letters".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec!["Try a short word such as 'top' or 'star'".to_string()],
+            metadata: serde_json::json!({
+                "constraint_type": "palindrome_generation",
+                "seed": seed
+            }),
+        });
+    }
+
+    let excluded: Vec<String> = options.excluded_words.iter().map(|w| w.to_lowercase()).collect();
+    let mut candidates: Vec<Candidate> = dictionary
+        .words()
+        .filter(|word| word.chars().all(char::is_alphabetic))
+        .filter(|word| word.chars().count() >= options.min_word_length.max(1))
+        .filter(|word| !excluded.iter().any(|ex| ex == word))
+        .map(|word| Candidate { word: word.to_string(), reversed: word.chars().rev().collect() })
+        .collect();
+    if let Some(seed) = options.seed {
+        candidates.shuffle(&mut seeded_rng(seed));
+    }
+
+    let all: Vec<usize> = (0..candidates.len()).collect();
+    let mut by_first: HashMap<char, Vec<usize>> = HashMap::new();
+    let mut by_last: HashMap<char, Vec<usize>> = HashMap::new();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        if let (Some(first), Some(last)) = (candidate.word.chars().next(), candidate.reversed.chars().next()) {
+            by_first.entry(first).or_default().push(idx);
+            by_last.entry(last).or_default().push(idx);
+        }
+    }
+
+    let centres = seed_centres(&letters);
+    let collect_limit = options.max_results.max(1) * 20;
+    let mut nodes = 0;
+    let mut frontier: Vec<Partial> = centres.clone();
+    if options.max_words > 0 {
+        frontier.extend(straddling_centres(&letters, &candidates));
+    }
+    let mut found: Vec<Partial> = frontier.iter().filter(|state| state.is_complete() && state.word_count() > 0).cloned().collect();
+
+    while !frontier.is_empty() && nodes < SEARCH_NODE_BUDGET && found.len() < collect_limit {
+        let mut next = Vec::new();
+        'states: for state in &frontier {
+            if state.word_count() >= options.max_words {
+                continue;
+            }
+            // Only words that begin with the next owed letter, read outward
+            let pool: &[usize] = match (state.owed.chars().next(), state.side) {
+                (None, _) => &all,
+                (Some(letter), Side::Right) => by_first.get(&letter).map_or(&[], Vec::as_slice),
+                (Some(letter), Side::Left) => by_last.get(&letter).map_or(&[], Vec::as_slice),
+            };
+            for &idx in pool {
+                if nodes >= SEARCH_NODE_BUDGET || found.len() >= collect_limit {
+                    break 'states;
+                }
+                nodes += 1;
+
+                if let Some(grown) = extend(state, idx, &candidates[idx]) {
+                    if grown.is_complete() {
+                        found.push(grown.clone());
+                    }
+                    next.push(grown);
+                }
+            }
+        }
+        frontier = next;
+    }
+    let exhausted = frontier.is_empty() && nodes < SEARCH_NODE_BUDGET;
+
+    let centre = normalize_line(seed);
+    let mut palindromes: Vec<(usize, usize, String)> = found
+        .iter()
+        .map(|state| {
+            let words: Vec<&str> = state
+                .left
+                .iter()
+                .rev()
+                .map(|&i| candidates[i].word.as_str())
+                .chain(std::iter::once(centre.as_str()))
+                .chain(state.right.iter().map(|&i| candidates[i].word.as_str()))
+                .collect();
+            let shortest = state.left.iter().chain(&state.right).map(|&i| candidates[i].word.len()).min().unwrap_or(0);
+            (state.word_count(), shortest, words.join(" "))
+        })
+        .filter(|(_, _, phrase)| is_palindrome(phrase))
+        .collect();
+    // Fewer added words, then longer shortest word, then alphabetical (or seeded discovery order)
+    if options.seed.is_some() {
+        palindromes.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        let mut seen = std::collections::HashSet::new();
+        palindromes.retain(|(_, _, phrase)| seen.insert(phrase.clone()));
+    } else {
+        palindromes.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then_with(|| a.2.cmp(&b.2)));
+        palindromes.dedup_by(|a, b| a.2 == b.2);
+    }
+    palindromes.truncate(options.max_results);
+
+    let phrases: Vec<String> = palindromes.into_iter().map(|(_, _, phrase)| phrase).collect();
+    let success = !phrases.is_empty();
+
+    Ok(ConstraintResult {
+        success,
+        result: Some(if success { phrases.join(", ") } else { "No palindromes found".to_string() }),
+        violations: vec![],
+        suggestions: if success {
+            vec![
+                "Extend a result further by using it as the new seed".to_string(),
+                "Punctuate freely: only the letters need to mirror".to_string(),
+            ]
+        } else {
+            vec![
+                "Allow more words around the seed".to_string(),
+                "Lower the minimum word length".to_string(),
+                "Load a larger dictionary".to_string(),
+            ]
+        },
+        metadata: serde_json::json!({
+            "constraint_type": "palindrome_generation",
+            "seed": seed,
+            "palindrome_count": phrases.len(),
+            "palindromes": phrases,
+            "seed_centres": centres.len(),
+            "candidate_words": candidates.len(),
+            "search_nodes": nodes,
+            "search_exhausted": exhausted,
+            "random_seed": options.seed
+        }),
+    })
+}
+
+/// Every way of placing the palindrome's centre inside the seed
+fn seed_centres(letters: &str) -> Vec<Partial> {
+    let chars: Vec<char> = letters.chars().collect();
+    let mut centres: Vec<Partial> = Vec::new();
+    for (side, owed) in centres_within(&chars, 0..=2 * chars.len()) {
+        if !centres.iter().any(|c| c.side == side && c.owed == owed) {
+            centres.push(Partial { left: Vec::new(), right: Vec::new(), side, owed });
+        }
+    }
+    centres
+}
+
+/// Centres falling inside a word placed directly beside the seed ("top spot")
+fn straddling_centres(letters: &str, candidates: &[Candidate]) -> Vec<Partial> {
+    let seed: Vec<char> = letters.chars().collect();
+    let mut centres = Vec::new();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        let word: Vec<char> = candidate.word.chars().collect();
+
+        let right: Vec<char> = seed.iter().chain(&word).copied().collect();
+        for (side, owed) in centres_within(&right, 2 * seed.len() + 1..=2 * right.len() - 1) {
+            centres.push(Partial { left: Vec::new(), right: vec![idx], side, owed });
+        }
+        let left: Vec<char> = word.iter().chain(&seed).copied().collect();
+        for (side, owed) in centres_within(&left, 1..=2 * word.len() - 1) {
+            centres.push(Partial { left: vec![idx], right: Vec::new(), side, owed });
+        }
+    }
+    centres
+}
+
+/// The side and letters owed for each usable centre in a range of positions
+///
+/// Positions count gaps and letters alternately: even positions are gaps,
+/// odd positions are letters. A centre is usable when the letters around it
+/// mirror until one end is reached; the letters left over beyond that point
+/// are owed by the other side.
+fn centres_within(chars: &[char], positions: std::ops::RangeInclusive<usize>) -> Vec<(Side, String)> {
+    positions
+        .filter_map(|doubled| {
+            let left_end = doubled / 2;
+            let right_start = left_end + doubled % 2;
+            let left_out: Vec<char> = chars[..left_end].iter().rev().copied().collect();
+            let right_out = &chars[right_start..];
+
+            let shared = left_out.len().min(right_out.len());
+            if left_out[..shared] != right_out[..shared] {
+                return None;
+            }
+            Some(if left_out.len() > right_out.len() {
+                (Side::Right, left_out[shared..].iter().collect())
+            } else {
+                (Side::Left, right_out[shared..].iter().collect())
+            })
+        })
+        .collect()
+}
+
+/// Add a word on the side that owes letters, or on the right of a complete palindrome
+fn extend(state: &Partial, idx: usize, candidate: &Candidate) -> Option<Partial> {
+    let placed = if state.is_complete() { Side::Right } else { state.side };
+    // Letters the word contributes, read outward from the centre
+    let outward = match placed {
+        Side::Right => &candidate.word,
+        Side::Left => &candidate.reversed,
+    };
+
+    let (side, owed) = if let Some(rest) = state.owed.strip_prefix(outward.as_str()) {
+        (placed, rest.to_string())
+    } else if let Some(extra) = outward.strip_prefix(state.owed.as_str()) {
+        // The word overshoots: its extra letters are now owed by the other side
+        let other = match placed {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        };
+        (other, extra.to_string())
+    } else {
+        return None;
+    };
+
+    let mut grown = state.clone();
+    match placed {
+        Side::Left => grown.left.push(idx),
+        Side::Right => grown.right.push(idx),
+    }
+    grown.side = side;
+    grown.owed = owed;
+    Some(grown)
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
@@ -0,0 +1,332 @@
+// Dictionary-driven snowball generation
+use crate::oulipo::{
+    ConstraintResult, Violation,
+    constraints::snowball::{self, SnowballVariant},
+    dictionary::Dictionary,
+    errors::OulipoError,
+    generators::{record_seed, resolve_seed, seeded_rng},
+    utils::match_case,
+};
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on search nodes before giving up on a chain
+const SEARCH_NODE_BUDGET: usize = 50_000;
+
+/// Options for the snowball generator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnowballOptions {
+    /// Letters in the first word
+    pub start_length: usize,
+    /// Letters in the last word; shorter than the start for a melting snowball
+    pub end_length: usize,
+    /// Words to prefer wherever their length fits
+    pub theme_words: Vec<String>,
+    /// Seed for the word choices; the same seed gives the same snowball
+    pub seed: Option<u64>,
+}
+
+impl Default for SnowballOptions {
+    fn default() -> Self {
+        Self {
+            start_length: 1,
+            end_length: 7,
+            theme_words: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+/// Rough part of speech, enough to keep a chain sounding like a sentence
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum WordClass {
+    Determiner,
+    Pronoun,
+    Preposition,
+    Conjunction,
+    Auxiliary,
+    Noun,
+    /// Verbs, adjectives and adverbs, which the lexicon does not tell apart
+    Content,
+}
+
+const DETERMINERS: &[&str] = &[
+    "a", "an", "the", "my", "his", "her", "its", "our", "your", "their", "this", "that", "these", "those",
+    "every", "each", "some", "any", "no",
+];
+const PRONOUNS: &[&str] = &["i", "we", "you", "he", "she", "it", "they", "me", "us", "him", "them", "nobody", "someone"];
+const PREPOSITIONS: &[&str] = &[
+    "in", "on", "at", "by", "to", "of", "for", "from", "with", "into", "over", "under", "after", "before",
+    "through", "between", "beneath", "without", "across", "towards", "around", "along", "among", "beyond", "within",
+    "up", "down", "near", "past", "upon", "above", "below", "behind", "beside", "against",
+];
+const CONJUNCTIONS: &[&str] = &["and", "or", "but", "so", "yet", "nor", "while", "though", "because", "although", "until", "unless", "if", "when", "where", "whereas"];
+const AUXILIARIES: &[&str] = &[
+    "am", "is", "are", "was", "were", "be", "do", "did", "does", "can", "may", "will", "must", "shall",
+    "could", "would", "should", "might", "has", "have", "had",
+];
+
+impl WordClass {
+    fn of(word: &str, dictionary: &Dictionary) -> Self {
+        let lists: [(&[&str], WordClass); 5] = [
+            (DETERMINERS, WordClass::Determiner),
+            (PRONOUNS, WordClass::Pronoun),
+            (PREPOSITIONS, WordClass::Preposition),
+            (CONJUNCTIONS, WordClass::Conjunction),
+            (AUXILIARIES, WordClass::Auxiliary),
+        ];
+        lists
+            .iter()
+            .find(|(words, _)| words.contains(&word))
+            .map(|&(_, class)| class)
+            .unwrap_or(if dictionary.is_noun(word) { WordClass::Noun } else { WordClass::Content })
+    }
+
+    /// Determiners and prepositions open a phrase that only a noun closes
+    fn opens_phrase(self) -> bool {
+        matches!(self, WordClass::Determiner | WordClass::Preposition)
+    }
+
+    fn can_start(self) -> bool {
+        !matches!(self, WordClass::Conjunction | WordClass::Auxiliary)
+    }
+
+    fn can_end(self) -> bool {
+        matches!(self, WordClass::Noun | WordClass::Content | WordClass::Pronoun)
+    }
+
+    /// Whether `next` may follow this class, given whether a phrase is still open
+    fn can_precede(self, next: WordClass, open: bool) -> bool {
+        use WordClass::*;
+        if open {
+            // Inside "the ... noun" or "in ... noun": adjectives, then the noun
+            return match next {
+                Noun | Content => true,
+                Determiner | Pronoun => self == Preposition,
+                _ => false,
+            };
+        }
+        match self {
+            Pronoun => matches!(next, Auxiliary | Content | Conjunction),
+            Auxiliary => matches!(next, Determiner | Preposition | Pronoun | Content),
+            Noun => matches!(next, Auxiliary | Preposition | Conjunction | Content),
+            Content => matches!(next, Determiner | Preposition | Conjunction | Noun | Pronoun),
+            Conjunction => matches!(next, Determiner | Preposition | Pronoun | Noun | Content),
+            Determiner | Preposition => false,
+        }
+    }
+
+    /// The most natural continuations, tried before the merely allowed ones
+    fn prefers(self, next: WordClass, open: bool) -> bool {
+        use WordClass::*;
+        if open {
+            return match self {
+                Preposition => matches!(next, Determiner | Noun),
+                _ => next == Noun || (self == Determiner && next == Content),
+            };
+        }
+        match self {
+            Pronoun => next == Auxiliary,
+            Auxiliary => matches!(next, Determiner | Content),
+            Noun => matches!(next, Preposition | Conjunction | Auxiliary),
+            Content => matches!(next, Preposition | Determiner),
+            Conjunction => matches!(next, Pronoun | Determiner),
+            Determiner | Preposition => false,
+        }
+    }
+}
+
+/// A word that fits one position of the chain
+struct Candidate {
+    word: String,
+    class: WordClass,
+    themed: bool,
+}
+
+/// Generate a snowball seeded from the options, or from a fresh seed
+///
+/// The seed used is recorded in the metadata as `random_seed`.
+pub fn generate(dictionary: &Dictionary, options: &SnowballOptions) -> Result<ConstraintResult> {
+    let seed = resolve_seed(options.seed);
+    let mut result = generate_with(dictionary, options, &mut seeded_rng(seed))?;
+    record_seed(&mut result, Some(seed));
+    Ok(result)
+}
+
+/// Search the dictionary for a chain of words from the start length to the end length
+///
+/// Each word is one letter longer (or shorter, when the end is below the start)
+/// than the last. Words are tagged with a rough part of speech, and only
+/// transitions that read like a sentence fragment are allowed: a determiner or
+/// preposition must reach its noun, and function words never end the chain.
+/// Theme words are tried first at their lengths.
+pub fn generate_with<R: Rng>(dictionary: &Dictionary, options: &SnowballOptions, rng: &mut R) -> Result<ConstraintResult> {
+    let (start, end) = (options.start_length, options.end_length);
+    if start == 0 || end == 0 {
+        return Err(OulipoError::InvalidConfig("Snowball word lengths must be at least 1".to_string()).into());
+    }
+    let lengths: Vec<usize> = if start <= end {
+        (start..=end).collect()
+    } else {
+        (end..=start).rev().collect()
+    };
+    let variant = if start <= end { SnowballVariant::Ascending } else { SnowballVariant::Melting };
+
+    let theme: Vec<String> = options
+        .theme_words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty() && w.chars().all(char::is_alphabetic))
+        .collect();
+
+    let positions: Vec<Vec<Candidate>> = lengths
+        .iter()
+        .map(|&len| {
+            let mut pool: Vec<&str> = dictionary
+                .words()
+                .chain(DETERMINERS.iter().chain(PRONOUNS).chain(PREPOSITIONS).chain(CONJUNCTIONS).chain(AUXILIARIES).copied())
+                .filter(|w| w.chars().count() == len && w.chars().all(char::is_alphabetic))
+                .filter(|w| !theme.iter().any(|t| t == w))
+                .collect();
+            pool.sort_unstable();
+            pool.dedup();
+            pool.shuffle(rng);
+
+            let mut themed: Vec<&str> = theme.iter().map(String::as_str).filter(|w| w.chars().count() == len).collect();
+            themed.shuffle(rng);
+            themed
+                .into_iter()
+                .map(|word| (word, true))
+                .chain(pool.into_iter().map(|word| (word, false)))
+                .map(|(word, themed)| Candidate { word: word.to_string(), class: WordClass::of(word, dictionary), themed })
+                .collect()
+        })
+        .collect();
+
+    // Insist on a theme word wherever one fits, then relax if no chain exists
+    let mut search = Search { positions: &positions, nodes: 0, chain: Vec::new(), theme_only: !theme.is_empty() };
+    let mut found = search.run(false);
+    if !found && search.theme_only {
+        search.theme_only = false;
+        search.nodes = 0;
+        found = search.run(false);
+    }
+
+    if !found {
+        let empty: Vec<usize> = lengths.iter().zip(&positions).filter(|(_, p)| p.is_empty()).map(|(&len, _)| len).collect();
+        return Ok(ConstraintResult {
+            success: false,
+            result: None,
+            violations: vec![Violation {
+                position: 0,
+                length: 0,
+                span: None,
+                issue: if empty.is_empty() {
+                    format!("No grammatical chain from {} to {} letters was found", start, end)
+                } else {
+                    format!("The dictionary has no words of length {:?}", empty)
+                },
+                suggestion: Some("Narrow the range of lengths or load a larger dictionary".to_string()),
+                fixes: Vec::new(),
+            }],
+            suggestions: vec![
+                "Start from a one-letter word such as 'I' or 'a'".to_string(),
+                "Add theme words for the hardest lengths".to_string(),
+            ],
+            metadata: serde_json::json!({
+                "constraint_type": "snowball_generation",
+                "start_length": start,
+                "end_length": end,
+                "search_nodes": search.nodes
+            }),
+        });
+    }
+
+    let words: Vec<&Candidate> = search.chain.iter().enumerate().map(|(pos, &idx)| &positions[pos][idx]).collect();
+    let text = words
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i == 0 || c.word == "i" { match_case("I", &c.word) } else { c.word.clone() })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let verification = snowball::check_variant(&text, variant, Some(start))?;
+    let theme_used: Vec<&str> = words.iter().map(|c| c.word.as_str()).filter(|w| theme.iter().any(|t| t == w)).collect();
+
+    Ok(ConstraintResult {
+        success: verification.success,
+        result: Some(text),
+        violations: verification.violations,
+        suggestions: vec![
+            "Rearrange punctuation freely: only the word lengths are fixed".to_string(),
+            "Swap any word for another of the same length to refine the sense".to_string(),
+        ],
+        metadata: serde_json::json!({
+            "constraint_type": "snowball_generation",
+            "variant": variant,
+            "start_length": start,
+            "end_length": end,
+            "word_classes": words.iter().map(|c| c.class).collect::<Vec<_>>(),
+            "theme_words_used": theme_used,
+            "search_nodes": search.nodes,
+            "verified": verification.success
+        }),
+    })
+}
+
+/// Depth-first search for one word per position
+struct Search<'a> {
+    positions: &'a [Vec<Candidate>],
+    nodes: usize,
+    chain: Vec<usize>,
+    /// Only theme words at positions that have one
+    theme_only: bool,
+}
+
+impl Search<'_> {
+    /// Extend the chain from the current depth; `open` is whether a phrase awaits its noun
+    fn run(&mut self, open: bool) -> bool {
+        let depth = self.chain.len();
+        if depth == self.positions.len() {
+            return !open;
+        }
+        let previous = self.chain.last().map(|&idx| self.positions[depth - 1][idx].class);
+        let is_last = depth + 1 == self.positions.len();
+        let needs_theme = self.theme_only && self.positions[depth].iter().any(|c| c.themed);
+
+        // Fitting words: theme words, then the most sentence-like transitions
+        let mut fitting: Vec<((bool, bool), usize)> = Vec::new();
+        for (idx, candidate) in self.positions[depth].iter().enumerate() {
+            let class = candidate.class;
+            let fits = match previous {
+                None => class.can_start(),
+                Some(prev) => prev.can_precede(class, open),
+            };
+            if fits && (!is_last || class.can_end()) && (candidate.themed || !needs_theme) {
+                let preferred = previous.map_or(matches!(class, WordClass::Pronoun | WordClass::Determiner), |prev| prev.prefers(class, open));
+                fitting.push(((!candidate.themed, !preferred), idx));
+            }
+        }
+        fitting.sort_by_key(|&(rank, _)| rank);
+
+        for (_, idx) in fitting {
+            if self.nodes >= SEARCH_NODE_BUDGET {
+                return false;
+            }
+            self.nodes += 1;
+
+            let class = self.positions[depth][idx].class;
+            let still_open = class.opens_phrase() || (open && class == WordClass::Content);
+
+            self.chain.push(idx);
+            if self.run(still_open) {
+                return true;
+            }
+            self.chain.pop();
+        }
+        false
+    }
+}
@@ -22,8 +22,14 @@
 pub mod types;
 pub mod errors;
 pub mod utils;
+pub mod span;
+pub mod fixes;
+pub mod session;
 pub mod registry;
 pub mod builder;
+pub mod composition;
+pub mod generation;
+pub mod rules;
 
 // Examples and documentation
 #[cfg(test)]
@@ -32,23 +38,44 @@ pub mod examples;
 // Feature modules
 pub mod constraints;
 pub mod dictionary;
+pub mod phonetics;
+pub mod thesaurus;
+pub mod rhyme;
 pub mod generators;
 pub mod validators;
 
 // Re-export commonly used types
-pub use types::{ConstraintResult, Violation, Constraint, Generator, Transformer, ValidationConfig};
+pub use types::{ConstraintResult, Violation, Fix, Constraint, Generator, Transformer, ValidationConfig};
+pub use span::{LineColumn, OffsetRange, OffsetUnit, SpanIndex, TextSpan};
+pub use fixes::FixOutcome;
+pub use session::{CheckSession, SessionUpdate, TextDelta};
 pub use errors::{OulipoError, OulipoResult};
 pub use registry::{ConstraintRegistry, ConstraintInfo, BatchConstraintChecker};
 pub use builder::{ConstraintWorkflowBuilder, ConstraintPresets, GenerationWorkflowBuilder, WorkflowResult, ConstraintWorkflowConfig};
+pub use composition::{Composition, ConstraintExpression, Scope};
+pub use generation::{CandidateGenerator, CandidateRequest, GenerationOutcome, GenerationRun};
+pub use rules::{RuleCheck, RuleDefinition, RuleFormat, WordPredicate};
 
 use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Main service for Oulipo constraint checking and text generation
 /// 
 /// This service provides a high-level API for all Oulipo functionality,
 /// managing internal state like dictionaries and caching results where appropriate.
 pub struct OulipoService {
-    dictionary: dictionary::Dictionary,
+    dictionaries: dictionary::DictionarySet,
+    registry: registry::ConstraintRegistry,
+    pronunciations: Arc<phonetics::PronunciationDictionary>,
+    thesaurus: Arc<thesaurus::Thesaurus>,
+    sessions: HashMap<u64, session::CheckSession>,
+    next_session: u64,
+    /// User rules in the order they were defined, all registered in `registry`
+    user_rules: Vec<rules::RuleDefinition>,
+    /// Where user rules are saved when they change
+    rules_file: Option<PathBuf>,
 }
 
 impl Default for OulipoService {
@@ -61,25 +88,132 @@ impl OulipoService {
     /// Create a new Oulipo service instance
     pub fn new() -> Self {
         Self {
-            dictionary: dictionary::Dictionary::new(),
+            dictionaries: dictionary::DictionarySet::new(),
+            registry: registry::ConstraintRegistry::new(),
+            pronunciations: phonetics::PronunciationDictionary::shared(),
+            thesaurus: thesaurus::Thesaurus::shared(),
+            sessions: HashMap::new(),
+            next_session: 1,
+            user_rules: Vec::new(),
+            rules_file: None,
         }
     }
     
-    /// Get a reference to the internal dictionary
+    /// Create a service that uses the given dictionary as its active one
+    pub fn with_dictionary(name: &str, dictionary: dictionary::Dictionary) -> Self {
+        let mut service = Self::new();
+        service.add_dictionary(name, dictionary);
+        service.dictionaries.select(name).expect("dictionary was just added");
+        service
+    }
+    
+    /// Get a reference to the active dictionary
     pub fn dictionary(&self) -> &dictionary::Dictionary {
-        &self.dictionary
+        self.dictionaries.active()
+    }
+    
+    /// Get all loaded dictionaries
+    pub fn dictionaries(&self) -> &dictionary::DictionarySet {
+        &self.dictionaries
+    }
+    
+    /// Add or replace a named dictionary without selecting it
+    pub fn add_dictionary(&mut self, name: &str, dictionary: dictionary::Dictionary) {
+        self.dictionaries.insert(name, dictionary);
+    }
+    
+    /// Load a plain one-word-per-line list from disk as a named dictionary
+    pub fn load_word_list(&mut self, name: &str, path: &str) -> OulipoResult<()> {
+        let dictionary = dictionary::Dictionary::from_word_list_file(name, path)?;
+        self.add_dictionary(name, dictionary);
+        Ok(())
+    }
+    
+    /// Load a Hunspell `.dic`/`.aff` pair from disk as a named dictionary
+    pub fn load_hunspell_dictionary(&mut self, name: &str, dic_path: &str, aff_path: &str) -> OulipoResult<()> {
+        let dictionary = dictionary::Dictionary::from_hunspell_files(name, dic_path, aff_path)?;
+        self.add_dictionary(name, dictionary);
+        Ok(())
+    }
+    
+    /// Select which named dictionary generators and transformers consult
+    pub fn select_dictionary(&mut self, name: &str) -> OulipoResult<()> {
+        self.dictionaries.select(name)
+    }
+    
+    /// Get the pronunciation dictionary used for syllable counting
+    pub fn pronunciations(&self) -> &phonetics::PronunciationDictionary {
+        &self.pronunciations
+    }
+    
+    /// Load a CMUdict-format pronunciation file on top of the built-in lexicon
+    ///
+    /// Registry constraints created afterwards use it too.
+    pub fn load_pronunciations(&mut self, path: &str) -> OulipoResult<()> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut pronunciations = phonetics::PronunciationDictionary::new();
+        pronunciations.extend_from_cmudict(&contents);
+        self.pronunciations = Arc::new(pronunciations);
+        self.registry.set_pronunciations(Arc::clone(&self.pronunciations));
+        Ok(())
+    }
+    
+    /// Get the thesaurus used for synonym, antonym and hypernym lookups
+    pub fn thesaurus(&self) -> &thesaurus::Thesaurus {
+        &self.thesaurus
+    }
+    
+    /// Load Moby-format synonym lists on top of the built-in thesaurus
+    pub fn load_moby_thesaurus(&mut self, paths: &[&str]) -> OulipoResult<()> {
+        let mut thesaurus = thesaurus::Thesaurus::new();
+        for path in paths {
+            thesaurus.extend_from_moby(&std::fs::read_to_string(path)?);
+        }
+        self.thesaurus = Arc::new(thesaurus);
+        Ok(())
+    }
+    
+    /// Load WordNet data files (`data.noun`, `data.verb`, ...) on top of the built-in thesaurus
+    pub fn load_wordnet(&mut self, paths: &[&str]) -> OulipoResult<()> {
+        let mut thesaurus = thesaurus::Thesaurus::new();
+        for path in paths {
+            thesaurus.extend_from_wordnet(&std::fs::read_to_string(path)?);
+        }
+        self.thesaurus = Arc::new(thesaurus);
+        Ok(())
+    }
+    
+    /// Synonyms, antonyms and hypernyms of a word, optionally for one part of speech
+    pub fn lookup_word(&self, word: &str, part_of_speech: Option<thesaurus::PartOfSpeech>) -> thesaurus::ThesaurusEntry {
+        self.thesaurus.lookup(word, part_of_speech)
     }
 
     // Constraint checking methods
     
     /// Check lipogram constraint (text without specific letters)
+    ///
+    /// Each violation carries the offending word's lipogrammatic synonyms as fixes.
     pub fn check_lipogram(&self, text: &str, forbidden_letter: &str) -> Result<ConstraintResult> {
-        constraints::lipogram::check(text, forbidden_letter)
+        let result = constraints::lipogram::evaluate(text, forbidden_letter);
+        let letters: Vec<char> = forbidden_letter.chars().collect();
+        let max_alternatives = generators::lipogram::LipogramRewriteOptions::default().max_alternatives;
+        Ok(generators::lipogram::with_synonym_fixes(result, text, &letters, self.dictionary(), &self.thesaurus, max_alternatives)
+            .located(text))
+    }
+
+    /// Check that text uses only the letters of a name
+    pub fn check_beau_present(&self, text: &str, name: &str) -> Result<ConstraintResult> {
+        constraints::BeauPresentConstraint::new(name)?.check(text)
+    }
+
+    /// Check that each line or stanza omits one letter, the omissions spelling a name
+    pub fn check_belle_absente(&self, text: &str, name: &str, unit: constraints::BelleAbsenteUnit) -> Result<ConstraintResult> {
+        constraints::BelleAbsenteConstraint::new(name)?.with_unit(unit).check(text)
     }
 
     /// Apply N+7 transformation (replace each noun with the noun 7 places later in dictionary)
     pub fn n_plus_7_transform(&self, text: &str, offset: i32) -> Result<ConstraintResult> {
-        constraints::n_plus_7::transform(text, offset, &self.dictionary)
+        constraints::n_plus_7::transform(text, offset, self.dictionary())
     }
 
     /// Check palindrome constraint
@@ -87,11 +221,21 @@ impl OulipoService {
         constraints::palindrome::check(text)
     }
 
+    /// Check a letter, word-unit or line-unit palindrome
+    pub fn check_palindrome_with_mode(&self, text: &str, mode: constraints::PalindromeMode) -> Result<ConstraintResult> {
+        constraints::palindrome::check_with_mode(text, mode)
+    }
+
     /// Check snowball constraint (each word one letter longer than the previous)
     pub fn check_snowball(&self, text: &str) -> Result<ConstraintResult> {
         constraints::snowball::check(text)
     }
 
+    /// Check a melting, diamond or line-level snowball, optionally fixing the first length
+    pub fn check_snowball_variant(&self, text: &str, variant: constraints::SnowballVariant, start: Option<usize>) -> Result<ConstraintResult> {
+        constraints::snowball::check_variant(text, variant, start)
+    }
+
     /// Check prisoners constraint (no letters with ascenders or descenders)
     pub fn check_prisoners_constraint(&self, text: &str) -> Result<ConstraintResult> {
         constraints::prisoners::check(text)
@@ -108,8 +252,10 @@ impl OulipoService {
                 violations: vec![Violation {
                     position: 0,
                     length: 0,
+                    span: None,
                     issue: "Invalid vowel parameter".to_string(),
                     suggestion: Some("Provide a single vowel character".to_string()),
+                    fixes: Vec::new(),
                 }],
                 suggestions: vec!["Provide a single vowel character".to_string()],
                 metadata: serde_json::Value::Null,
@@ -122,60 +268,112 @@ impl OulipoService {
         constraints::sestina::check(text, end_words)
     }
 
+    /// Check n-ina (quenine) constraint for any valid number of end words
+    pub fn check_nina(&self, text: &str, end_words: &[String]) -> Result<ConstraintResult> {
+        constraints::nina::check(text, end_words, true)
+    }
+
+    /// Check villanelle form (19 lines, refrains A1/A2, ABA rhymes)
+    pub fn check_villanelle(&self, text: &str, min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        constraints::villanelle::check(text, min_level, &self.rhyme_engine())
+    }
+
+    /// Check pantoum form (lines 2 and 4 carried into the next quatrain)
+    pub fn check_pantoum(&self, text: &str, circular_ending: bool) -> Result<ConstraintResult> {
+        constraints::pantoum::check(text, circular_ending)
+    }
+
+    /// Check terza rima form (chained tercets ABA BCB CDC ...)
+    pub fn check_terza_rima(&self, text: &str, min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        constraints::terza_rima::check(text, min_level, &self.rhyme_engine())
+    }
+
+    /// Check rondeau form (15 lines on two rhymes with a rentrement)
+    pub fn check_rondeau(&self, text: &str, min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        constraints::rondeau::check(text, min_level, &self.rhyme_engine())
+    }
+
+    /// Check ghazal form (radif and qafia across couplets)
+    pub fn check_ghazal(&self, text: &str, radif: Option<&str>, min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        constraints::ghazal::check(text, radif, min_level, &self.rhyme_engine())
+    }
+
+    /// Check haiku constraint (5-7-5 syllables per line)
+    pub fn check_haiku(&self, text: &str) -> Result<ConstraintResult> {
+        constraints::haiku::check(text, &self.pronunciations)
+    }
+
+    /// Check that every line scans in a named meter such as "iambic pentameter"
+    pub fn check_meter(&self, text: &str, meter: &str, substitutions: &[constraints::meter::Substitution]) -> Result<ConstraintResult> {
+        let meter = constraints::meter::Meter::parse(meter)?;
+        constraints::meter::check(text, &meter, substitutions, &self.pronunciations)
+    }
+
+    /// Check a poem against a rhyme scheme such as "ABAB CDCD EFEF GG"
+    pub fn check_rhyme_scheme(&self, text: &str, scheme: &str, min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        let scheme = constraints::rhyme_scheme::RhymeScheme::parse(scheme)?;
+        constraints::rhyme_scheme::check(text, &scheme, min_level, &self.rhyme_engine())
+    }
+
+    /// How closely two words rhyme
+    pub fn compare_rhyme(&self, first: &str, second: &str) -> rhyme::RhymeLevel {
+        self.rhyme_engine().compare(first, second)
+    }
+
+    /// Rhyme engine backed by the service's pronunciation dictionary
+    pub fn rhyme_engine(&self) -> rhyme::RhymeEngine {
+        rhyme::RhymeEngine::with_pronunciations(self.pronunciations.clone())
+    }
+
+    /// Count the syllables in a word, line or passage
+    pub fn count_syllables(&self, text: &str) -> usize {
+        self.pronunciations.text_syllables(text)
+    }
+
     // Generator methods
     
-    /// Generate haiku based on theme with optional parameter
-    pub fn generate_haiku(&self, theme: Option<&str>) -> Result<String> {
+    /// Generate a 5-7-5 haiku from a themed word bank
+    pub fn generate_haiku(&self, theme: Option<&str>, seed: Option<u64>) -> Result<String> {
         let theme_str = theme.unwrap_or("nature");
-        let result = generators::haiku::generate(theme_str)?;
+        let result = generators::haiku::generate(theme_str, &self.pronunciations, seed)?;
         
-        if result.success {
-            // Extract the generated haiku from the result
-            if let Some(result_text) = &result.result {
-                return Ok(result_text.clone());
-            }
-            if let serde_json::Value::Object(metadata) = &result.metadata {
-                if let Some(haiku) = metadata.get("generated_text") {
-                    if let Some(haiku_str) = haiku.as_str() {
-                        return Ok(haiku_str.to_string());
-                    }
-                }
-            }
-            Ok("Spring cherry blossoms / Dance in the gentle warm breeze / Peace fills the heart".to_string())
-        } else {
-            Err(OulipoError::ValidationError("Failed to generate haiku".to_string()).into())
-        }
+        result.result.ok_or_else(|| OulipoError::GenerationFailed("Failed to generate haiku".to_string()).into())
     }
 
-    /// Generate anagrams with maximum results limit
-    pub fn generate_anagrams(&self, word: &str, max_results: usize) -> Result<Vec<String>> {
-        let result = generators::anagram::generate_anagrams(word)?;
+    /// Generate a snowball from the active dictionary, growing or melting between two word lengths
+    pub fn generate_snowball(&self, options: &generators::snowball::SnowballOptions) -> Result<String> {
+        let result = generators::snowball::generate(self.dictionary(), options)?;
         
-        if result.success {
-            if let Some(result_text) = &result.result {
-                let anagrams: Vec<String> = result_text
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .take(max_results)
-                    .collect();
-                return Ok(anagrams);
-            }
-            if let serde_json::Value::Object(metadata) = &result.metadata {
-                if let Some(anagrams_str) = metadata.get("anagrams") {
-                    if let Some(anagrams_text) = anagrams_str.as_str() {
-                        let anagrams: Vec<String> = anagrams_text
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .take(max_results)
-                            .collect();
-                        return Ok(anagrams);
-                    }
-                }
-            }
-        }
+        result.result.ok_or_else(|| {
+            let reason = result.violations.first().map(|v| v.issue.clone()).unwrap_or_default();
+            OulipoError::GenerationFailed(format!("Failed to generate snowball: {}", reason)).into()
+        })
+    }
+
+    /// Generate a fill-in n-ina skeleton with every end word in place
+    pub fn generate_nina_skeleton(&self, end_words: &[String]) -> Result<String> {
+        let result = generators::nina::generate_skeleton(end_words)?;
+        result.result.ok_or_else(|| OulipoError::GenerationFailed("Failed to generate skeleton".to_string()).into())
+    }
+
+    /// Generate anagrams with maximum results limit, best-ranked first
+    pub fn generate_anagrams(&self, word: &str, max_results: usize) -> Result<Vec<String>> {
+        let options = generators::anagram::AnagramOptions {
+            max_results,
+            ..Default::default()
+        };
+        self.generate_anagrams_with_options(word, &options)
+    }
+    
+    /// Generate ranked single- and multi-word anagrams from the active dictionary
+    pub fn generate_anagrams_with_options(&self, phrase: &str, options: &generators::anagram::AnagramOptions) -> Result<Vec<String>> {
+        let result = generators::anagram::generate_anagrams(phrase, self.dictionary(), options)?;
         
-        // Fallback: generate some simple anagrams
-        Ok(vec![word.chars().rev().collect::<String>()])
+        let anagrams = result.metadata["anagrams"]
+            .as_array()
+            .map(|list| list.iter().filter_map(|a| a.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        Ok(anagrams)
     }
 
     /// Check if two words are anagrams
@@ -185,11 +383,18 @@ impl OulipoService {
     }
 
     /// Generate combinatorial poem
-    pub fn generate_combinatorial_poem(&self, word_sets: &[Vec<String>], pattern: Option<&str>) -> Result<String> {
+    ///
+    /// With the "queneau" pattern each word set is a source poem (one line per
+    /// entry) and a random poem of the resulting book is returned.
+    pub fn generate_combinatorial_poem(&self, word_sets: &[Vec<String>], pattern: Option<&str>, seed: Option<u64>) -> Result<String> {
         let pattern_str = pattern.unwrap_or("simple");
+        if matches!(pattern_str, "queneau" | "book") {
+            let poem = self.combinatorial_poem(word_sets, None, seed)?;
+            return Ok(poem.result.unwrap_or_default());
+        }
         let words: Vec<String> = word_sets.iter().flatten().cloned().collect();
         
-        let result = generators::combinatorial::generate_combinatorial_poem(words, pattern_str)?;
+        let result = generators::combinatorial::generate_combinatorial_poem(words, pattern_str, seed)?;
         
         if result.success {
             if let Some(result_text) = &result.result {
@@ -215,6 +420,24 @@ impl OulipoService {
         Ok(poem)
     }
 
+    /// Check that source poems can be combined line by line without breaking the rhyme
+    pub fn check_combinatorial_sources(&self, sources: &[Vec<String>], min_level: rhyme::RhymeLevel) -> Result<ConstraintResult> {
+        let book = generators::combinatorial::CombinatorialBook::new(sources.to_vec())?;
+        Ok(book.check_interchangeable(&self.rhyme_engine(), min_level))
+    }
+
+    /// Read one poem of a combinatorial book by index, or a random one drawn with the seed
+    pub fn combinatorial_poem(&self, sources: &[Vec<String>], index: Option<u64>, seed: Option<u64>) -> Result<ConstraintResult> {
+        let book = generators::combinatorial::CombinatorialBook::new(sources.to_vec())?;
+        generators::combinatorial::book_poem(&book, index, seed)
+    }
+
+    /// List `count` consecutive poems of a combinatorial book from `start`
+    pub fn enumerate_combinatorial_poems(&self, sources: &[Vec<String>], start: u64, count: usize) -> Result<Vec<(u64, String)>> {
+        let book = generators::combinatorial::CombinatorialBook::new(sources.to_vec())?;
+        Ok(book.enumerate(start, count))
+    }
+
     // Validation methods
     
     /// Validate text length constraints
@@ -232,21 +455,23 @@ impl OulipoService {
         validators::check_character_frequency(text, target_char, max_frequency)
     }
     
-    /// Generate lipogram suggestions
+    /// Generate lipogram suggestions, one per offending word
     pub fn generate_lipogram_suggestions(&self, text: &str, forbidden_letter: &str) -> Result<Vec<String>> {
-        let mut suggestions = Vec::new();
-        
-        // Find words that contain the forbidden letter
-        let forbidden_char = forbidden_letter.chars().next().unwrap_or('e');
-        let words: Vec<&str> = text.split_whitespace().collect();
+        let result = self.rewrite_lipogram(text, forbidden_letter, &generators::lipogram::LipogramRewriteOptions::default())?;
+        let edits: Vec<generators::lipogram::LipogramEdit> = serde_json::from_value(result.metadata["edits"].clone())?;
         
-        for word in words {
-            if word.to_lowercase().contains(forbidden_char.to_ascii_lowercase()) {
-                // Suggest alternatives (this is a simplified implementation)
-                let suggestion = format!("Replace '{}' with a word that doesn't contain '{}'", word, forbidden_char);
-                suggestions.push(suggestion);
-            }
-        }
+        let mut suggestions: Vec<String> = edits
+            .iter()
+            .map(|edit| {
+                let others: Vec<&str> = edit.alternatives.iter().skip(1).map(|a| a.word.as_str()).collect();
+                if others.is_empty() {
+                    format!("Replace '{}' with '{}'", edit.original, edit.replacement)
+                } else {
+                    format!("Replace '{}' with '{}' (or {})", edit.original, edit.replacement, others.join(", "))
+                }
+            })
+            .collect();
+        suggestions.extend(result.violations.iter().map(|v| v.issue.clone()));
         
         if suggestions.is_empty() {
             suggestions.push("Text already follows lipogram constraint".to_string());
@@ -255,16 +480,68 @@ impl OulipoService {
         Ok(suggestions)
     }
     
-    /// Generate palindrome suggestions
-    pub fn generate_palindrome_suggestions(&self, text: &str) -> Result<Vec<String>> {
-        let suggestions = vec![
-            "Try rearranging words to create symmetry".to_string(),
-            "Consider using palindromic words like 'level', 'radar', 'civic'".to_string(),
-            "Build from the center outward for sentence palindromes".to_string(),
-            format!("Current text: '{}' - work on making it read the same forwards and backwards", text),
-        ];
-        
-        Ok(suggestions)
+    /// Propose ranked synonym swaps for words that use any of the forbidden letters
+    ///
+    /// The edits are in `metadata["edits"]`; with `full_rewrite` the rewritten
+    /// passage is also returned as the result.
+    pub fn rewrite_lipogram(
+        &self,
+        text: &str,
+        forbidden_letters: &str,
+        options: &generators::lipogram::LipogramRewriteOptions,
+    ) -> Result<ConstraintResult> {
+        let letters: Vec<char> = forbidden_letters.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+        generators::lipogram::rewrite(text, &letters, self.dictionary(), &self.thesaurus, options)
+    }
+    
+    /// Build palindromes outward from a seed word using the active dictionary
+    pub fn generate_palindromes(&self, seed: &str, options: &generators::palindrome::PalindromeOptions) -> Result<Vec<String>> {
+        let result = generators::palindrome::generate_palindromes(seed, self.dictionary(), options)?;
+        let palindromes = result.metadata["palindromes"]
+            .as_array()
+            .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        Ok(palindromes)
+    }
+    
+    /// Check that every letter of the alphabet appears
+    pub fn check_pangram(&self, text: &str) -> Result<ConstraintResult> {
+        constraints::check_pangram(text)
+    }
+
+    /// Check that no letter appears more than once
+    pub fn check_heterogram(&self, text: &str) -> Result<ConstraintResult> {
+        constraints::check_heterogram(text)
+    }
+
+    /// Check that every letter used appears exactly `n` times
+    pub fn check_isogram(&self, text: &str, n: usize) -> Result<ConstraintResult> {
+        let constraint = constraints::IsogramConstraint::new(n)?;
+        constraint.check(text)
+    }
+
+    /// Check per-letter minimum and maximum counts, keyed by single letters
+    pub fn check_letter_quotas(&self, text: &str, quotas: HashMap<String, constraints::letter_inventory::LetterQuota>) -> Result<ConstraintResult> {
+        let constraint = constraints::LetterQuotaConstraint::from_letter_map(quotas)?;
+        constraint.check(text)
+    }
+
+    /// Check that every word begins with the same letter or sound
+    ///
+    /// Without a target letter the text's most common initial is used.
+    pub fn check_tautogram(
+        &self,
+        text: &str,
+        letter: Option<&str>,
+        matching: constraints::InitialMatch,
+        allow_function_words: bool,
+    ) -> Result<ConstraintResult> {
+        constraints::check_tautogram(text, letter, matching, allow_function_words, &self.pronunciations)
+    }
+
+    /// Check that every stressed word begins with the same letter or sound
+    pub fn check_alliteration(&self, text: &str, letter: Option<&str>, matching: constraints::InitialMatch) -> Result<ConstraintResult> {
+        constraints::check_alliteration(text, letter, matching, &self.pronunciations)
     }
     
     /// Validate text against multiple constraints using configuration
@@ -295,79 +572,260 @@ impl OulipoService {
     }
     
     /// Get the constraint registry for advanced constraint management
-    pub fn constraint_registry(&self) -> registry::ConstraintRegistry {
-        registry::ConstraintRegistry::new()
+    pub fn constraint_registry(&self) -> &registry::ConstraintRegistry {
+        &self.registry
+    }
+    
+    /// Get mutable access to the constraint registry to register additional constraints
+    pub fn constraint_registry_mut(&mut self) -> &mut registry::ConstraintRegistry {
+        &mut self.registry
     }
     
     /// Check text using a workflow configuration
+    ///
+    /// The result's score is the mean of the constraints' scores, weighted by
+    /// the workflow's weights.
     pub fn check_with_workflow(&self, text: &str, config: &builder::ConstraintWorkflowConfig) -> Result<builder::WorkflowResult> {
         let mut constraint_results = Vec::new();
         
-        // Check each constraint in the configuration
+        // Resolve each constraint through the registry
         for (name, constraint_config) in &config.constraints {
-            match name.as_str() {
-                "univocalic" => {
-                    let vowel = constraint_config["allowed_vowel"]
-                        .as_str()
-                        .and_then(|s| s.chars().next())
-                        .unwrap_or('a');
-                    let vowel_str = &vowel.to_string();
-                    let result = self.check_univocalic(text, vowel_str)?;
-                    constraint_results.push(result);
-                }
-                _ => {
-                    // For unknown constraints, create a failure result
-                    constraint_results.push(ConstraintResult {
-                        success: false,
-                        result: Some(format!("Unknown constraint: {}", name)),
-                        violations: Vec::new(),
-                        suggestions: vec!["Check constraint name".to_string()],
-                        metadata: serde_json::json!({"error": "unknown_constraint"}),
-                    });
-                }
+            if !self.registry.contains(name) {
+                constraint_results.push(ConstraintResult {
+                    success: false,
+                    result: Some(format!("Unknown constraint: {}", name)),
+                    violations: Vec::new(),
+                    suggestions: vec!["Check constraint name".to_string()],
+                    metadata: serde_json::json!({"error": "unknown_constraint", "constraint": name}),
+                });
+                continue;
+            }
+            
+            match self.registry.create_constraint(name, constraint_config) {
+                Ok(constraint) => constraint_results.push(constraint.check(text)?),
+                Err(e) => constraint_results.push(ConstraintResult {
+                    success: false,
+                    result: Some(e.to_string()),
+                    violations: Vec::new(),
+                    suggestions: vec![format!("Check the configuration for '{}'", name)],
+                    metadata: serde_json::json!({"error": "invalid_config", "constraint": name}),
+                }),
             }
         }
         
-        // Add validation results
-        let validation_results = self.validate_with_config(text, &config.validation_config)?;
-        constraint_results.extend(validation_results);
+        let weights: Vec<f64> = (0..constraint_results.len()).map(|index| config.weight(index)).collect();
+        let total_weight: f64 = weights.iter().sum();
+        let score = if total_weight > 0.0 {
+            constraint_results.iter().zip(&weights).map(|(r, w)| r.score() * w).sum::<f64>() / total_weight
+        } else {
+            1.0
+        };
         
         let overall_success = constraint_results.iter().all(|r| r.success);
-        let summary = self.generate_workflow_summary(&constraint_results);
+        let summary = self.generate_workflow_summary(&constraint_results, score);
         
         Ok(builder::WorkflowResult {
             success: overall_success,
             constraint_results,
+            weights,
+            score: (score * 1000.0).round() / 1000.0,
             summary,
         })
     }
     
+    /// Run a generation workflow with an Oulipo generator
+    ///
+    /// Each attempt drafts a fresh candidate, or tries the best one so far with
+    /// its fixes applied when it has any. Stops at the first candidate
+    /// satisfying every constraint or after `max_attempts`.
+    pub fn generate_with_workflow(&self, config: &builder::GenerationWorkflowConfig) -> Result<GenerationOutcome> {
+        let run = GenerationRun::from_config(config, self)?;
+        let generator = config
+            .generator
+            .unwrap_or_else(|| CandidateGenerator::for_constraints(&run.workflow().constraints));
+        self.run_generation(run, generator)
+    }
+    
+    /// Drive a generation run to the end with an Oulipo generator
+    pub fn run_generation(&self, mut run: GenerationRun, generator: CandidateGenerator) -> Result<GenerationOutcome> {
+        while let Some(request) = run.next_request() {
+            match request.repair.clone() {
+                Some(repaired) => run.record("fixes", Ok(repaired), self)?,
+                None => run.record(generator.name(), generator.draft(self, &request), self)?,
+            };
+        }
+        Ok(run.finish())
+    }
+    
+    /// Check text against a boolean, scoped composition of registry constraints
+    pub fn check_composition(&self, text: &str, composition: &composition::Composition) -> Result<ConstraintResult> {
+        composition.check(text, &self.registry)
+    }
+    
+    /// Apply chosen fixes and check the edited text against a registry constraint
+    ///
+    /// Overlapping fixes are skipped; see `fixes::apply_fixes`.
+    pub fn apply_fixes(&self, text: &str, fixes: &[Fix], constraint: &str, config: &serde_json::Value) -> Result<FixOutcome> {
+        let constraint = self.registry.create_constraint(constraint, config)?;
+        let mut outcome = fixes::apply_fixes(text, fixes)?;
+        outcome.check = Some(constraint.check(&outcome.text)?);
+        Ok(outcome)
+    }
+    
+    /// Open an incremental checking session on a document
+    ///
+    /// The first update lists every violation. Later updates, fed the
+    /// editor's deltas, re-check only the units that changed; see `session`.
+    pub fn open_check_session(
+        &mut self,
+        text: &str,
+        constraint: &str,
+        config: serde_json::Value,
+        scope: Option<composition::Scope>,
+    ) -> OulipoResult<SessionUpdate> {
+        let id = self.next_session;
+        let (session, update) = session::CheckSession::open(id, text, constraint, config, scope, &self.registry)?;
+        self.next_session += 1;
+        self.sessions.insert(id, session);
+        Ok(update)
+    }
+    
+    /// Apply text deltas to a session and report the violations that changed
+    pub fn update_check_session(&mut self, session: u64, deltas: &[TextDelta]) -> OulipoResult<SessionUpdate> {
+        self.sessions
+            .get_mut(&session)
+            .ok_or_else(|| OulipoError::ValidationError(format!("No checking session {}", session)))?
+            .update(deltas, &self.registry)
+    }
+    
+    /// An open checking session, for its current text and violations
+    pub fn check_session(&self, session: u64) -> Option<&session::CheckSession> {
+        self.sessions.get(&session)
+    }
+    
+    /// Close a checking session; returns whether it was open
+    pub fn close_check_session(&mut self, session: u64) -> bool {
+        self.sessions.remove(&session).is_some()
+    }
+    
+    /// Load user rules from a file and save them there whenever they change
+    ///
+    /// The format follows the extension: TOML for `.toml`, JSON otherwise. A
+    /// missing file is not an error; it is written once a rule is defined.
+    pub fn attach_rule_file(&mut self, path: impl AsRef<Path>) -> OulipoResult<()> {
+        let path = path.as_ref();
+        if path.exists() {
+            let source = std::fs::read_to_string(path)?;
+            let definitions = rules::parse_rules(&source, rules::RuleFormat::for_path(path))?;
+            self.register_rules(definitions)?;
+        }
+        self.rules_file = Some(path.to_path_buf());
+        Ok(())
+    }
+    
+    /// Define user rules as registry constraints, replacing rules of the same names
+    ///
+    /// Either every rule is registered or, when one is invalid or would
+    /// shadow a built-in constraint, none is. Returns the rules' names.
+    pub fn define_rules(&mut self, definitions: Vec<rules::RuleDefinition>) -> OulipoResult<Vec<String>> {
+        let names = self.register_rules(definitions)?;
+        self.save_rules()?;
+        Ok(names)
+    }
+    
+    /// Define user rules from a JSON or TOML document; see `define_rules`
+    pub fn import_rules(&mut self, source: &str, format: rules::RuleFormat) -> OulipoResult<Vec<String>> {
+        self.define_rules(rules::parse_rules(source, format)?)
+    }
+    
+    /// Remove a user rule; returns whether there was one by that name
+    pub fn remove_rule(&mut self, name: &str) -> OulipoResult<bool> {
+        let Some(index) = self.user_rules.iter().position(|rule| rule.name == name) else {
+            return Ok(false);
+        };
+        self.user_rules.remove(index);
+        self.registry.unregister(name);
+        self.save_rules()?;
+        Ok(true)
+    }
+    
+    /// The user rules, in the order they were defined
+    pub fn user_rules(&self) -> &[rules::RuleDefinition] {
+        &self.user_rules
+    }
+    
+    /// Write the user rules as a document `import_rules` reads back
+    pub fn export_rules(&self, format: rules::RuleFormat) -> OulipoResult<String> {
+        rules::write_rules(&self.user_rules, format)
+    }
+    
+    /// Compile every rule, then register them all
+    fn register_rules(&mut self, definitions: Vec<rules::RuleDefinition>) -> OulipoResult<Vec<String>> {
+        let mut compiled = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            let name = definition.name.clone();
+            let user_rule = self.user_rules.iter().any(|rule| rule.name == name);
+            if self.registry.contains(&name) && !user_rule {
+                return Err(OulipoError::InvalidConfig(format!(
+                    "Rule '{}' would replace the built-in constraint of that name",
+                    name
+                )));
+            }
+            if compiled.iter().any(|rule: &rules::CompiledRule| rule.definition().name == name) {
+                return Err(OulipoError::InvalidConfig(format!("Rule '{}' is defined twice", name)));
+            }
+            compiled.push(rules::CompiledRule::compile(definition)?);
+        }
+        
+        let mut names = Vec::with_capacity(compiled.len());
+        for rule in compiled {
+            let definition = rule.definition().clone();
+            match self.user_rules.iter_mut().find(|existing| existing.name == definition.name) {
+                Some(existing) => *existing = definition.clone(),
+                None => self.user_rules.push(definition.clone()),
+            }
+            self.registry.register(Box::new(rules::RuleFactory::new(rule)));
+            names.push(definition.name);
+        }
+        Ok(names)
+    }
+    
+    /// Save the user rules to the attached file, if any, replacing it whole
+    fn save_rules(&self) -> OulipoResult<()> {
+        let Some(path) = &self.rules_file else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = rules::write_rules(&self.user_rules, rules::RuleFormat::for_path(path))?;
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, contents)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+    
     /// Check text using a predefined constraint preset
     pub fn check_with_preset(&self, text: &str, preset_name: &str) -> Result<builder::WorkflowResult> {
-        let config = match preset_name {
-            "strict" => builder::ConstraintPresets::strict_writing().build()?,
-            "minimal" => builder::ConstraintPresets::minimal().build()?,
-            "experimental" => builder::ConstraintPresets::experimental().build()?,
-            _ => return Err(anyhow::anyhow!("Unknown preset: {}", preset_name)),
-        };
+        let config = builder::ConstraintPresets::by_name(preset_name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown preset: {}", preset_name))?
+            .build()?;
         
         self.check_with_workflow(text, &config)
     }
     
     /// Create a custom constraint using the registry system
     pub fn create_custom_constraint(&self, name: &str, config: serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
-        let registry = self.constraint_registry();
-        registry.create_constraint(name, &config)
+        self.registry.create_constraint(name, &config)
     }
     
     /// List all available constraint types
     pub fn list_available_constraints(&self) -> Vec<registry::ConstraintInfo> {
-        let registry = self.constraint_registry();
-        registry.list_constraints()
+        self.registry.list_constraints()
     }
     
     /// Generate a workflow summary from constraint results
-    fn generate_workflow_summary(&self, results: &[ConstraintResult]) -> String {
+    fn generate_workflow_summary(&self, results: &[ConstraintResult], score: f64) -> String {
         let total = results.len();
         let passed = results.iter().filter(|r| r.success).count();
         let failed = total - passed;
@@ -375,7 +833,7 @@ impl OulipoService {
         if failed == 0 {
             format!("✅ All {} constraints satisfied", total)
         } else {
-            format!("❌ {}/{} constraints failed", failed, total)
+            format!("❌ {}/{} constraints failed ({:.0}% adherence)", failed, total, score * 100.0)
         }
     }
 }
@@ -0,0 +1,307 @@
+//! Pronunciation lexicon and syllable counting.
+//!
+//! Pronunciations use CMUdict's ARPAbet notation, where every vowel phoneme
+//! carries a stress digit (`0` unstressed, `1` primary, `2` secondary). Words
+//! missing from the lexicon fall back to a spelling-based syllable estimate.
+
+use crate::oulipo::{
+    errors::OulipoResult,
+    utils::{is_function_word, word_spans},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// Built-in pronunciation lexicon in CMUdict format
+const DEFAULT_LEXICON: &str = include_str!("data/cmudict_en.txt");
+
+/// Stress level of a syllable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stress {
+    Unstressed,
+    Primary,
+    Secondary,
+}
+
+impl Stress {
+    /// Whether the syllable counts as stressed for scansion
+    pub fn is_stressed(self) -> bool {
+        !matches!(self, Stress::Unstressed)
+    }
+}
+
+/// Where a syllable count came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyllableSource {
+    Dictionary,
+    Estimated,
+}
+
+/// A single pronunciation as a list of ARPAbet phonemes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pronunciation {
+    phonemes: Vec<String>,
+}
+
+impl Pronunciation {
+    /// Parse a space-separated ARPAbet pronunciation such as `P OW1 AH0 M`
+    pub fn parse(arpabet: &str) -> Self {
+        Self {
+            phonemes: arpabet.split_whitespace().map(|p| p.to_uppercase()).collect(),
+        }
+    }
+
+    /// The phonemes of this pronunciation
+    pub fn phonemes(&self) -> &[String] {
+        &self.phonemes
+    }
+
+    /// Number of syllables (one per vowel phoneme)
+    pub fn syllable_count(&self) -> usize {
+        self.phonemes.iter().filter(|p| is_vowel_phoneme(p)).count()
+    }
+
+    /// Stress of each syllable in order
+    pub fn stresses(&self) -> Vec<Stress> {
+        self.phonemes
+            .iter()
+            .filter_map(|p| match p.chars().last() {
+                Some('0') => Some(Stress::Unstressed),
+                Some('1') => Some(Stress::Primary),
+                Some('2') => Some(Stress::Secondary),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Word to pronunciation lookup table
+pub struct PronunciationDictionary {
+    entries: HashMap<String, Vec<Pronunciation>>,
+}
+
+impl PronunciationDictionary {
+    /// Create a dictionary with the built-in lexicon
+    pub fn new() -> Self {
+        Self::from_cmudict(DEFAULT_LEXICON)
+    }
+
+    /// Shared instance of the built-in lexicon
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<PronunciationDictionary>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// Create an empty dictionary; every lookup falls back to estimation
+    pub fn empty() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Parse CMUdict-formatted contents (`WORD  PH1 PH2 ...`, `;;;` comments, `WORD(2)` variants)
+    pub fn from_cmudict(contents: &str) -> Self {
+        let mut dictionary = Self::empty();
+        dictionary.extend_from_cmudict(contents);
+        dictionary
+    }
+
+    /// Load a CMUdict file from disk
+    pub fn from_cmudict_file(path: impl AsRef<Path>) -> OulipoResult<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::from_cmudict(&contents))
+    }
+
+    /// Add entries from CMUdict-formatted contents, keeping existing ones
+    pub fn extend_from_cmudict(&mut self, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(";;;") {
+                continue;
+            }
+            let Some((word, phonemes)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            // Alternate pronunciations are written WORD(1), WORD(2), ...
+            let word = word.split('(').next().unwrap_or(word).to_lowercase();
+            let pronunciation = Pronunciation::parse(phonemes);
+            if !pronunciation.phonemes.is_empty() {
+                self.entries.entry(word).or_default().push(pronunciation);
+            }
+        }
+    }
+
+    /// Number of words with at least one pronunciation
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the dictionary has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Primary pronunciation of a word
+    pub fn lookup(&self, word: &str) -> Option<&Pronunciation> {
+        self.variants(word).first()
+    }
+
+    /// All known pronunciations of a word
+    pub fn variants(&self, word: &str) -> &[Pronunciation] {
+        let lower = word.to_lowercase().replace('’', "'");
+        if let Some(found) = self.entries.get(&lower) {
+            return found;
+        }
+        // Possessives share the base word's pronunciation closely enough for counting
+        lower
+            .strip_suffix("'s")
+            .and_then(|base| self.entries.get(base))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Syllable count of a word and whether it came from the lexicon
+    pub fn syllables(&self, word: &str) -> (usize, SyllableSource) {
+        match self.lookup(word) {
+            Some(pronunciation) => (pronunciation.syllable_count(), SyllableSource::Dictionary),
+            None => (estimate_syllables(word), SyllableSource::Estimated),
+        }
+    }
+
+    /// Syllable count of a word
+    pub fn syllable_count(&self, word: &str) -> usize {
+        self.syllables(word).0
+    }
+
+    /// Total syllables in a line or passage
+    pub fn text_syllables(&self, text: &str) -> usize {
+        word_spans(text).iter().map(|(_, word)| self.syllable_count(word)).sum()
+    }
+
+    /// The sound a word begins with, as an ARPAbet consonant or `vowel`
+    ///
+    /// Every vowel sound counts as the same onset, as in traditional
+    /// alliteration. Words missing from the lexicon fall back to their spelling.
+    pub fn initial_sound(&self, word: &str) -> (String, SyllableSource) {
+        match self.lookup(word).and_then(|p| p.phonemes().first()) {
+            Some(phoneme) if is_vowel_phoneme(phoneme) => ("vowel".to_string(), SyllableSource::Dictionary),
+            Some(phoneme) => (phoneme.clone(), SyllableSource::Dictionary),
+            None => (spelled_onset(word), SyllableSource::Estimated),
+        }
+    }
+
+    /// Whether a word carries stress: a content word with a stressed syllable
+    ///
+    /// Function words are unstressed even when the lexicon marks their one
+    /// syllable as stressed; unknown content words count as stressed.
+    pub fn is_stressed_word(&self, word: &str) -> bool {
+        !is_function_word(word)
+            && self
+                .lookup(word)
+                .is_none_or(|p| p.stresses().contains(&Stress::Primary))
+    }
+}
+
+impl Default for PronunciationDictionary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ARPAbet vowels carry a stress digit
+fn is_vowel_phoneme(phoneme: &str) -> bool {
+    phoneme.ends_with(|c: char| c.is_ascii_digit())
+}
+
+/// Guess the first sound of a word from its spelling, in the form of `initial_sound`
+pub fn spelled_onset(word: &str) -> String {
+    let lower: String = word.chars().filter(|c| c.is_alphabetic()).flat_map(|c| c.to_lowercase()).collect();
+    // Digraphs and silent letters that change the opening sound
+    const DIGRAPHS: &[(&str, &str)] = &[
+        ("ph", "F"), ("kn", "N"), ("gn", "N"), ("pn", "N"), ("wr", "R"), ("ps", "S"), ("wh", "W"),
+        ("sh", "SH"), ("ch", "CH"), ("th", "TH"), ("qu", "K"), ("ce", "S"), ("ci", "S"), ("cy", "S"),
+    ];
+    if let Some((_, sound)) = DIGRAPHS.iter().find(|(prefix, _)| lower.starts_with(prefix)) {
+        return sound.to_string();
+    }
+    match lower.chars().next() {
+        None => String::new(),
+        Some('a' | 'e' | 'i' | 'o' | 'u') => "vowel".to_string(),
+        Some('c' | 'k') => "K".to_string(),
+        Some('h') => "HH".to_string(),
+        Some('j') => "JH".to_string(),
+        Some('x') => "Z".to_string(),
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase().to_string(),
+        Some(c) => c.to_string(),
+    }
+}
+
+/// Estimate the syllable count of a word from its spelling
+///
+/// Counts vowel groups, then corrects for silent final `e`, silent `-es`/`-ed`
+/// endings, syllabic `-le` and common vowel pairs that split into two syllables.
+pub fn estimate_syllables(word: &str) -> usize {
+    let word: String = word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    if word.is_empty() {
+        return 0;
+    }
+
+    let chars: Vec<char> = word.chars().collect();
+    let is_vowel_at = |i: usize| {
+        let c = chars[i];
+        "aeiouàâäéèêëîïôöùûü".contains(c) || (c == 'y' && i > 0)
+    };
+
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for i in 0..chars.len() {
+        let vowel = is_vowel_at(i);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    let n = chars.len();
+    let consonant_before = |i: usize| i < n && !is_vowel_at(i);
+
+    // Silent final e ("stone"), but not syllabic -le ("candle") or "be"/"the"
+    if n > 2 && chars[n - 1] == 'e' && !is_vowel_at(n - 2) {
+        let syllabic_le = chars[n - 2] == 'l' && n > 3 && consonant_before(n - 3);
+        if !syllabic_le {
+            count -= 1;
+        }
+    }
+
+    // Silent -es / -ed ("stones", "walked"), except after sibilants and t/d
+    if n > 3 && (word.ends_with("es") || word.ends_with("ed")) && !is_vowel_at(n - 3) {
+        let before = chars[n - 3];
+        let voiced_es = word.ends_with("es") && (matches!(before, 's' | 'x' | 'z' | 'c' | 'g')
+            || word.ends_with("ches") || word.ends_with("shes"));
+        let voiced_ed = word.ends_with("ed") && matches!(before, 't' | 'd');
+        if !voiced_es && !voiced_ed {
+            count -= 1;
+        }
+    }
+
+    // Vowel pairs that are usually two syllables ("lion", "poem", "create")
+    for pair in ["ia", "io", "iu", "eo", "oe", "ua"] {
+        for (idx, _) in word.match_indices(pair) {
+            let softened = idx > 0 && matches!(chars[idx - 1], 't' | 's' | 'c' | 'g' | 'x');
+            let common_digraph = (pair == "oe" && idx + 2 == n)
+                || (pair == "ua" && idx > 0 && matches!(chars[idx - 1], 'q' | 'g'));
+            if !softened && !common_digraph {
+                count += 1;
+            }
+        }
+    }
+
+    count.max(1)
+}
@@ -2,16 +2,32 @@
 //! Registry system for managing all available constraints and generators.
 
 use crate::oulipo::{
-    types::{Constraint, Generator, Transformer, ConstraintResult},
-    constraints::UnivocalicConstraint,
+    types::{Constraint, ConstraintResult},
+    composition::Scope,
+    constraints::{
+        meter::{Foot, Meter, Substitution},
+        palindrome::PalindromeMode,
+        snowball::SnowballVariant,
+        GhazalConstraint, HaikuConstraint, LipogramConstraint, MeterConstraint, NinaConstraint, PantoumConstraint,
+        RhymeSchemeConstraint, RondeauConstraint, TerzaRimaConstraint, VillanelleConstraint, PalindromeConstraint, PrisonersConstraint, SestinaConstraint,
+        SnowballConstraint, UnivocalicConstraint, HeterogramConstraint, IsogramConstraint, LetterQuotaConstraint,
+        PangramConstraint, TautogramConstraint, AlliterationConstraint, InitialMatch, BeauPresentConstraint,
+        BelleAbsenteConstraint, BelleAbsenteUnit,
+    },
+    validators::{CharacterFrequencyConstraint, TextLengthConstraint, WordCountConstraint},
     errors::{OulipoError, OulipoResult},
+    phonetics::PronunciationDictionary,
+    rhyme::RhymeLevel,
 };
 use std::collections::HashMap;
+use std::sync::Arc;
 use anyhow::Result;
 
 /// Registry for constraint factories
 pub struct ConstraintRegistry {
     constraints: HashMap<String, Box<dyn ConstraintFactory>>,
+    /// The lexicon sound-based constraints are created with
+    pronunciations: Arc<PronunciationDictionary>,
 }
 
 /// Factory trait for creating constraints
@@ -20,52 +36,1145 @@ pub trait ConstraintFactory: Send + Sync {
     fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>>;
     
     /// Get the constraint name
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
     
     /// Get the constraint description
-    fn description(&self) -> &'static str;
+    fn description(&self) -> &str;
+    
+    /// Create a constraint that reads syllables, stress and sounds from `pronunciations`
+    ///
+    /// Factories of sound-based constraints override this; the rest ignore the lexicon.
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        _pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        self.create(config)
+    }
     
     /// Get configuration schema for this constraint
     fn config_schema(&self) -> serde_json::Value;
+
+    /// The largest unit the constraint can check on its own
+    ///
+    /// Checking each unit separately must find the same violations as checking
+    /// the whole text, so incremental sessions can re-check only edited units.
+    fn locality(&self) -> Scope {
+        Scope::Whole
+    }
+}
+
+/// Factory for creating univocalic constraints
+pub struct UnivocalicFactory;
+
+impl ConstraintFactory for UnivocalicFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let vowel = config["allowed_vowel"]
+            .as_str()
+            .and_then(|s| s.chars().next())
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'allowed_vowel' in config".to_string()))?;
+            
+        let constraint = UnivocalicConstraint::new(vowel)?;
+        Ok(Box::new(constraint))
+    }
+    
+    fn name(&self) -> &'static str {
+        "univocalic"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Text must use only one vowel throughout"
+    }
+    
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "allowed_vowel": {
+                    "type": "string",
+                    "pattern": "^[aeiouAEIOU]$",
+                    "description": "The only vowel allowed in the text"
+                }
+            },
+            "required": ["allowed_vowel"]
+        })
+    }
+
+    fn locality(&self) -> Scope {
+        Scope::Line
+    }
+}
+
+/// Factory for creating lipogram constraints
+pub struct LipogramFactory;
+
+impl ConstraintFactory for LipogramFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let letter = config["forbidden_letter"]
+            .as_str()
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'forbidden_letter' in config".to_string()))?;
+
+        let constraint = LipogramConstraint::new(letter)?;
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "lipogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text must avoid a specific letter"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "forbidden_letter": {
+                    "type": "string",
+                    "pattern": "^[a-zA-Z]$",
+                    "description": "The letter that must not appear in the text"
+                }
+            },
+            "required": ["forbidden_letter"]
+        })
+    }
+
+    fn locality(&self) -> Scope {
+        Scope::Line
+    }
+}
+
+/// Factory for creating beau présent constraints
+pub struct BeauPresentFactory;
+
+impl ConstraintFactory for BeauPresentFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let name = config["name"]
+            .as_str()
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'name' in config".to_string()))?;
+        Ok(Box::new(BeauPresentConstraint::new(name)?))
+    }
+
+    fn name(&self) -> &'static str {
+        "beau_present"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text may only use the letters of a given name"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "The name whose letters the text may use"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    fn locality(&self) -> Scope {
+        Scope::Line
+    }
+}
+
+/// Factory for creating belle absente constraints
+pub struct BelleAbsenteFactory;
+
+impl ConstraintFactory for BelleAbsenteFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let name = config["name"]
+            .as_str()
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'name' in config".to_string()))?;
+        let unit = match &config["unit"] {
+            serde_json::Value::Null => BelleAbsenteUnit::default(),
+            value => value
+                .as_str()
+                .and_then(BelleAbsenteUnit::parse)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown belle absente unit: {}", value)))?,
+        };
+        Ok(Box::new(BelleAbsenteConstraint::new(name)?.with_unit(unit)))
+    }
+
+    fn name(&self) -> &'static str {
+        "belle_absente"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each line omits one letter of the alphabet, and the omissions spell a name"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "The name spelled by the missing letters"
+                },
+                "unit": {
+                    "type": "string",
+                    "enum": ["line", "stanza"],
+                    "default": "line",
+                    "description": "Whether each line or each stanza hides one letter"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+}
+
+/// Factory for creating palindrome constraints
+pub struct PalindromeFactory;
+
+impl ConstraintFactory for PalindromeFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let mode = match &config["mode"] {
+            serde_json::Value::Null => PalindromeMode::Letter,
+            value => value
+                .as_str()
+                .and_then(PalindromeMode::parse)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown palindrome mode: {}", value)))?,
+        };
+        Ok(Box::new(PalindromeConstraint::new().with_mode(mode)))
+    }
+
+    fn name(&self) -> &'static str {
+        "palindrome"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text must read the same forwards and backwards"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "type": "string",
+                    "enum": ["letter", "word", "line"],
+                    "default": "letter",
+                    "description": "Mirror letters, whole words or whole lines"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating snowball constraints
+pub struct SnowballFactory;
+
+impl ConstraintFactory for SnowballFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let variant = match &config["variant"] {
+            serde_json::Value::Null => SnowballVariant::Ascending,
+            value => value
+                .as_str()
+                .and_then(SnowballVariant::parse)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown snowball variant: {}", value)))?,
+        };
+        let mut constraint = SnowballConstraint::new().with_variant(variant);
+        if let Some(start) = optional_usize(config, "start")? {
+            constraint = constraint.with_start(start);
+        }
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "snowball"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each word must be one letter longer than the previous"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "variant": {
+                    "type": "string",
+                    "enum": ["ascending", "melting", "diamond", "lines"],
+                    "default": "ascending",
+                    "description": "Growing words, shrinking words, growing then shrinking, or one more word per line"
+                },
+                "start": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Length of the first word (or words in the first line); melting snowballs default to the first word"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating prisoner's constraints
+pub struct PrisonersFactory;
+
+impl ConstraintFactory for PrisonersFactory {
+    fn create(&self, _config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        Ok(Box::new(PrisonersConstraint::new()))
+    }
+
+    fn name(&self) -> &'static str {
+        "prisoners"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text may only use letters without loops"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    fn locality(&self) -> Scope {
+        Scope::Line
+    }
+}
+
+/// Factory for creating sestina constraints
+pub struct SestinaFactory;
+
+impl ConstraintFactory for SestinaFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let end_words = string_list(config, "end_words")?;
+        let constraint = SestinaConstraint::new(end_words)?;
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "sestina"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lines must end with six rotating end words across six stanzas and an envoi"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "end_words": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 6,
+                    "maxItems": 6,
+                    "description": "The six end words, in the order of the first stanza"
+                }
+            },
+            "required": ["end_words"]
+        })
+    }
+}
+
+/// Factory for creating n-ina (quenine) constraints
+pub struct NinaFactory;
+
+impl ConstraintFactory for NinaFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let end_words = string_list(config, "end_words")?;
+        let require_envoi = config["require_envoi"].as_bool().unwrap_or(true);
+        let constraint = NinaConstraint::new(end_words)?.with_envoi(require_envoi);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "nina"
+    }
+
+    fn description(&self) -> &'static str {
+        "n end words rotating through n stanzas by the spiral permutation, closed by an envoi"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "end_words": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "description": "The n end words in first-stanza order; n must be 1, 2, 3, 5, 6, 9, 11, 14, 18, ..."
+                },
+                "require_envoi": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Require the closing envoi of ceil(n/2) lines"
+                }
+            },
+            "required": ["end_words"]
+        })
+    }
+}
+
+/// Factory for creating villanelle constraints
+pub struct VillanelleFactory;
+
+impl ConstraintFactory for VillanelleFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let constraint = VillanelleConstraint::new().with_min_level(rhyme_level(config, RhymeLevel::Perfect)?);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "villanelle"
+    }
+
+    fn description(&self) -> &'static str {
+        "19 lines in five tercets and a quatrain, with two alternating refrains"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "min_level": rhyme_level_schema("perfect")
+            }
+        })
+    }
+}
+
+/// Factory for creating pantoum constraints
+pub struct PantoumFactory;
+
+impl ConstraintFactory for PantoumFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let circular = config["circular_ending"].as_bool().unwrap_or(false);
+        Ok(Box::new(PantoumConstraint::new().with_circular_ending(circular)))
+    }
+
+    fn name(&self) -> &'static str {
+        "pantoum"
+    }
+
+    fn description(&self) -> &'static str {
+        "Quatrains whose second and fourth lines become the first and third of the next"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "circular_ending": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Require the last stanza to repeat the first stanza's third and first lines"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating terza rima constraints
+pub struct TerzaRimaFactory;
+
+impl ConstraintFactory for TerzaRimaFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let constraint = TerzaRimaConstraint::new().with_min_level(rhyme_level(config, RhymeLevel::Perfect)?);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "terza_rima"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tercets chained ABA BCB CDC, the middle rhyme of each leading the next"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "min_level": rhyme_level_schema("perfect")
+            }
+        })
+    }
+}
+
+/// Factory for creating rondeau constraints
+pub struct RondeauFactory;
+
+impl ConstraintFactory for RondeauFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let constraint = RondeauConstraint::new().with_min_level(rhyme_level(config, RhymeLevel::Perfect)?);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "rondeau"
+    }
+
+    fn description(&self) -> &'static str {
+        "15 lines on two rhymes, with the opening words returning as a short refrain"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "min_level": rhyme_level_schema("perfect")
+            }
+        })
+    }
+}
+
+/// Factory for creating ghazal constraints
+pub struct GhazalFactory;
+
+impl ConstraintFactory for GhazalFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let mut constraint = GhazalConstraint::new().with_min_level(rhyme_level(config, RhymeLevel::Slant)?);
+        match &config["radif"] {
+            serde_json::Value::Null => {}
+            serde_json::Value::String(radif) => constraint = constraint.with_radif(radif),
+            _ => return Err(OulipoError::InvalidConfig("'radif' must be a string".to_string())),
+        }
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "ghazal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Couplets where both opening lines and every second line end in the same radif"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "radif": {
+                    "type": "string",
+                    "description": "Refrain word or phrase; detected from the opening couplet when omitted"
+                },
+                "min_level": rhyme_level_schema("slant")
+            }
+        })
+    }
+}
+
+/// Factory for creating haiku constraints
+pub struct HaikuFactory;
+
+impl ConstraintFactory for HaikuFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        self.create_with_pronunciations(config, &PronunciationDictionary::shared())
+    }
+
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        let constraint = HaikuConstraint::new().with_pronunciations(Arc::clone(pronunciations));
+        let constraint = match optional_usize_list(config, "pattern")? {
+            Some(pattern) => constraint.with_pattern(pattern)?,
+            None => constraint,
+        };
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "haiku"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lines must follow a syllable pattern, 5-7-5 by default"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 1 },
+                    "minItems": 1,
+                    "default": [5, 7, 5],
+                    "description": "Syllables expected on each line"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating metrical scansion constraints
+pub struct MeterFactory;
+
+impl ConstraintFactory for MeterFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        self.create_with_pronunciations(config, &PronunciationDictionary::shared())
+    }
+
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        let meter = match (config["meter"].as_str(), config["foot"].as_str()) {
+            (Some(name), _) => Meter::parse(name)?,
+            (None, Some(foot)) => {
+                let foot = Foot::parse(foot)
+                    .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown foot: '{}'", foot)))?;
+                let feet = optional_usize(config, "feet")?
+                    .ok_or_else(|| OulipoError::InvalidConfig("Missing 'feet' in config".to_string()))?;
+                Meter::new(foot, feet)?
+            }
+            (None, None) => Meter::iambic_pentameter(),
+        };
+
+        let substitutions: Vec<Substitution> = match &config["substitutions"] {
+            serde_json::Value::Null => Vec::new(),
+            value => serde_json::from_value(value.clone())
+                .map_err(|e| OulipoError::InvalidConfig(format!("Invalid 'substitutions': {}", e)))?,
+        };
+        let strict = config["strict_monosyllables"].as_bool().unwrap_or(false);
+
+        let constraint = MeterConstraint::new(meter)
+            .with_pronunciations(Arc::clone(pronunciations))
+            .with_substitutions(substitutions)
+            .with_strict_monosyllables(strict);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "meter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every line must scan in the given meter, e.g. iambic pentameter"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "meter": {
+                    "type": "string",
+                    "default": "iambic pentameter",
+                    "description": "Meter name such as 'iambic pentameter' or 'trochaic tetrameter'"
+                },
+                "foot": {
+                    "type": "string",
+                    "enum": ["iamb", "trochee", "anapest", "dactyl", "spondee", "amphibrach"],
+                    "description": "Foot type, used with 'feet' instead of 'meter'"
+                },
+                "feet": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Feet per line"
+                },
+                "substitutions": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "enum": ["feminine_ending", "initial_inversion", "headless", "catalexis"]
+                    },
+                    "description": "Variations accepted in place of the regular pattern"
+                },
+                "strict_monosyllables": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Use lexicon stress for one-syllable words instead of letting them fit either position"
+                }
+            }
+        })
+    }
 }
 
-/// Factory for creating univocalic constraints
-pub struct UnivocalicFactory;
+/// Factory for creating rhyme scheme constraints
+pub struct RhymeSchemeFactory;
 
-impl ConstraintFactory for UnivocalicFactory {
+impl ConstraintFactory for RhymeSchemeFactory {
     fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
-        let vowel = config["allowed_vowel"]
+        self.create_with_pronunciations(config, &PronunciationDictionary::shared())
+    }
+
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        let scheme = config["scheme"]
+            .as_str()
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'scheme' in config".to_string()))?;
+
+        let constraint = RhymeSchemeConstraint::new(scheme)?
+            .with_pronunciations(Arc::clone(pronunciations))
+            .with_min_level(rhyme_level(config, RhymeLevel::Perfect)?);
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "rhyme_scheme"
+    }
+
+    fn description(&self) -> &'static str {
+        "Lines must rhyme according to a declared scheme such as ABAB CDCD EFEF GG"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "scheme": {
+                    "type": "string",
+                    "pattern": "^[A-Za-z\\s]+$",
+                    "description": "One letter per line, spaces between stanzas (e.g. 'ABAB CDCD EFEF GG')"
+                },
+                "min_level": rhyme_level_schema("perfect")
+            },
+            "required": ["scheme"]
+        })
+    }
+}
+
+/// Factory for creating text length validators
+pub struct TextLengthFactory;
+
+impl ConstraintFactory for TextLengthFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let min_length = optional_usize(config, "min_length")?.unwrap_or(0);
+        let max_length = optional_usize(config, "max_length")?;
+
+        let constraint = TextLengthConstraint::new(min_length, max_length)?;
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "text_length"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text length must fall within the given bounds"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "min_length": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Minimum number of characters"
+                },
+                "max_length": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Maximum number of characters"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating word count validators
+pub struct WordCountFactory;
+
+impl ConstraintFactory for WordCountFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let min_words = optional_usize(config, "min_words")?.unwrap_or(0);
+        let max_words = optional_usize(config, "max_words")?;
+
+        let constraint = WordCountConstraint::new(min_words, max_words)?;
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "word_count"
+    }
+
+    fn description(&self) -> &'static str {
+        "Number of words must fall within the given bounds"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "min_words": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Minimum number of words"
+                },
+                "max_words": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Maximum number of words"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating character frequency validators
+pub struct CharacterFrequencyFactory;
+
+impl ConstraintFactory for CharacterFrequencyFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let target_char = config["target_char"]
             .as_str()
             .and_then(|s| s.chars().next())
-            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'allowed_vowel' in config".to_string()))?;
-            
-        let constraint = UnivocalicConstraint::new(vowel)?;
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'target_char' in config".to_string()))?;
+        let max_frequency = optional_usize(config, "max_frequency")?
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'max_frequency' in config".to_string()))?;
+
+        Ok(Box::new(CharacterFrequencyConstraint::new(target_char, max_frequency)))
+    }
+
+    fn name(&self) -> &'static str {
+        "character_frequency"
+    }
+
+    fn description(&self) -> &'static str {
+        "A character may appear at most a given number of times"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "target_char": {
+                    "type": "string",
+                    "minLength": 1,
+                    "maxLength": 1,
+                    "description": "The character whose occurrences are counted"
+                },
+                "max_frequency": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Maximum number of occurrences allowed"
+                }
+            },
+            "required": ["target_char", "max_frequency"]
+        })
+    }
+}
+
+/// Factory for creating pangram constraints
+pub struct PangramFactory;
+
+impl ConstraintFactory for PangramFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let constraint = match config["alphabet"].as_str() {
+            Some(alphabet) => PangramConstraint::new().with_alphabet(alphabet)?,
+            None => PangramConstraint::new(),
+        };
         Ok(Box::new(constraint))
     }
-    
+
     fn name(&self) -> &'static str {
-        "univocalic"
+        "pangram"
     }
-    
+
     fn description(&self) -> &'static str {
-        "Text must use only one vowel throughout"
+        "Every letter of the alphabet must appear"
     }
-    
+
     fn config_schema(&self) -> serde_json::Value {
         serde_json::json!({
             "type": "object",
             "properties": {
-                "allowed_vowel": {
+                "alphabet": {
                     "type": "string",
-                    "pattern": "^[aeiouAEIOU]$",
-                    "description": "The only vowel allowed in the text"
+                    "default": "abcdefghijklmnopqrstuvwxyz",
+                    "description": "Letters that must all appear"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating heterogram constraints
+pub struct HeterogramFactory;
+
+impl ConstraintFactory for HeterogramFactory {
+    fn create(&self, _config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        Ok(Box::new(HeterogramConstraint::new()))
+    }
+
+    fn name(&self) -> &'static str {
+        "heterogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "No letter may appear more than once"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+}
+
+/// Factory for creating n-isogram constraints
+pub struct IsogramFactory;
+
+impl ConstraintFactory for IsogramFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let n = optional_usize(config, "n")?
+            .ok_or_else(|| OulipoError::InvalidConfig("Missing 'n' in config".to_string()))?;
+        Ok(Box::new(IsogramConstraint::new(n)?))
+    }
+
+    fn name(&self) -> &'static str {
+        "isogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every letter used must appear exactly n times"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "n": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "How many times each letter used must appear"
                 }
             },
-            "required": ["allowed_vowel"]
+            "required": ["n"]
+        })
+    }
+}
+
+/// Factory for creating per-letter quota constraints
+pub struct LetterQuotaFactory;
+
+impl ConstraintFactory for LetterQuotaFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        let quotas = serde_json::from_value(config["quotas"].clone())
+            .map_err(|e| OulipoError::InvalidConfig(format!("Invalid 'quotas' in config: {}", e)))?;
+        Ok(Box::new(LetterQuotaConstraint::from_letter_map(quotas)?))
+    }
+
+    fn name(&self) -> &'static str {
+        "letter_quota"
+    }
+
+    fn description(&self) -> &'static str {
+        "Each listed letter must appear within its minimum and maximum count"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "quotas": {
+                    "type": "object",
+                    "description": "Quotas keyed by single letter, e.g. { \"e\": { \"max\": 3 }, \"z\": { \"min\": 1 } }",
+                    "propertyNames": { "minLength": 1, "maxLength": 1 },
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "min": { "type": "integer", "minimum": 0 },
+                            "max": { "type": "integer", "minimum": 0 }
+                        }
+                    }
+                }
+            },
+            "required": ["quotas"]
+        })
+    }
+}
+
+/// Factory for creating tautogram constraints
+pub struct TautogramFactory;
+
+impl ConstraintFactory for TautogramFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        self.create_with_pronunciations(config, &PronunciationDictionary::shared())
+    }
+
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        let mut constraint = TautogramConstraint::new()
+            .with_pronunciations(Arc::clone(pronunciations))
+            .with_match(initial_match(config, InitialMatch::Letter)?)
+            .with_function_words(config["allow_function_words"].as_bool().unwrap_or(false));
+        if let Some(letter) = config["letter"].as_str() {
+            constraint = constraint.with_target(letter);
+        }
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "tautogram"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every word must begin with the same letter or sound"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "letter": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "Required initial; in sound mode an example word also works. Defaults to the most common initial"
+                },
+                "match": initial_match_schema("letter"),
+                "allow_function_words": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Skip articles, prepositions and other function words"
+                }
+            }
+        })
+    }
+}
+
+/// Factory for creating alliteration constraints
+pub struct AlliterationFactory;
+
+impl ConstraintFactory for AlliterationFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        self.create_with_pronunciations(config, &PronunciationDictionary::shared())
+    }
+
+    fn create_with_pronunciations(
+        &self,
+        config: &serde_json::Value,
+        pronunciations: &Arc<PronunciationDictionary>,
+    ) -> OulipoResult<Box<dyn Constraint>> {
+        let mut constraint = AlliterationConstraint::new()
+            .with_pronunciations(Arc::clone(pronunciations))
+            .with_match(initial_match(config, InitialMatch::Sound)?);
+        if let Some(letter) = config["letter"].as_str() {
+            constraint = constraint.with_target(letter);
+        }
+        Ok(Box::new(constraint))
+    }
+
+    fn name(&self) -> &'static str {
+        "alliteration"
+    }
+
+    fn description(&self) -> &'static str {
+        "Every stressed word must begin with the same sound"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "letter": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "Required initial; in sound mode an example word also works. Defaults to the most common initial"
+                },
+                "match": initial_match_schema("sound")
+            }
         })
     }
 }
 
+/// Read an optional non-negative integer field from a constraint config
+fn optional_usize(config: &serde_json::Value, key: &str) -> OulipoResult<Option<usize>> {
+    match &config[key] {
+        serde_json::Value::Null => Ok(None),
+        value => value
+            .as_u64()
+            .map(|v| Some(v as usize))
+            .ok_or_else(|| OulipoError::InvalidConfig(format!("'{}' must be a non-negative integer", key))),
+    }
+}
+
+/// Read an optional list of non-negative integers from a constraint config
+fn optional_usize_list(config: &serde_json::Value, key: &str) -> OulipoResult<Option<Vec<usize>>> {
+    match &config[key] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .map(|v| {
+                v.as_u64()
+                    .map(|n| n as usize)
+                    .ok_or_else(|| OulipoError::InvalidConfig(format!("'{}' must contain only non-negative integers", key)))
+            })
+            .collect::<OulipoResult<Vec<_>>>()
+            .map(Some),
+        _ => Err(OulipoError::InvalidConfig(format!("'{}' must be an array of integers", key))),
+    }
+}
+
+/// Read an optional rhyme level such as "perfect" or "slant" from a constraint config
+fn rhyme_level(config: &serde_json::Value, default: RhymeLevel) -> OulipoResult<RhymeLevel> {
+    match &config["min_level"] {
+        serde_json::Value::Null => Ok(default),
+        value => value
+            .as_str()
+            .and_then(RhymeLevel::parse)
+            .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown rhyme level: {}", value))),
+    }
+}
+
+/// Schema for a `min_level` rhyme setting
+fn rhyme_level_schema(default: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "enum": ["eye", "slant", "perfect", "identical"],
+        "default": default,
+        "description": "Weakest rhyme that counts as a match"
+    })
+}
+
+/// Read an optional letter-or-sound matching mode from a constraint config
+fn initial_match(config: &serde_json::Value, default: InitialMatch) -> OulipoResult<InitialMatch> {
+    match &config["match"] {
+        serde_json::Value::Null => Ok(default),
+        value => value
+            .as_str()
+            .and_then(InitialMatch::parse)
+            .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown initial match: {}", value))),
+    }
+}
+
+/// Schema for a `match` letter-or-sound setting
+fn initial_match_schema(default: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "string",
+        "enum": ["letter", "sound"],
+        "default": default,
+        "description": "Compare first letters or first sounds"
+    })
+}
+
+/// Read a required list of strings from a constraint config
+fn string_list(config: &serde_json::Value, key: &str) -> OulipoResult<Vec<String>> {
+    config[key]
+        .as_array()
+        .ok_or_else(|| OulipoError::InvalidConfig(format!("Missing '{}' in config", key)))?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("'{}' must contain only strings", key)))
+        })
+        .collect()
+}
+
 impl Default for ConstraintRegistry {
     fn default() -> Self {
         Self::new()
@@ -77,10 +1186,36 @@ impl ConstraintRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             constraints: HashMap::new(),
+            pronunciations: PronunciationDictionary::shared(),
         };
         
         // Register built-in constraints
         registry.register(Box::new(UnivocalicFactory));
+        registry.register(Box::new(LipogramFactory));
+        registry.register(Box::new(BeauPresentFactory));
+        registry.register(Box::new(BelleAbsenteFactory));
+        registry.register(Box::new(PalindromeFactory));
+        registry.register(Box::new(SnowballFactory));
+        registry.register(Box::new(PrisonersFactory));
+        registry.register(Box::new(SestinaFactory));
+        registry.register(Box::new(NinaFactory));
+        registry.register(Box::new(VillanelleFactory));
+        registry.register(Box::new(PantoumFactory));
+        registry.register(Box::new(TerzaRimaFactory));
+        registry.register(Box::new(RondeauFactory));
+        registry.register(Box::new(GhazalFactory));
+        registry.register(Box::new(HaikuFactory));
+        registry.register(Box::new(MeterFactory));
+        registry.register(Box::new(RhymeSchemeFactory));
+        registry.register(Box::new(TextLengthFactory));
+        registry.register(Box::new(WordCountFactory));
+        registry.register(Box::new(CharacterFrequencyFactory));
+        registry.register(Box::new(PangramFactory));
+        registry.register(Box::new(HeterogramFactory));
+        registry.register(Box::new(IsogramFactory));
+        registry.register(Box::new(LetterQuotaFactory));
+        registry.register(Box::new(TautogramFactory));
+        registry.register(Box::new(AlliterationFactory));
         
         registry
     }
@@ -90,12 +1225,27 @@ impl ConstraintRegistry {
         self.constraints.insert(factory.name().to_string(), factory);
     }
     
+    /// Remove a constraint factory, returning whether one was registered
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.constraints.remove(name).is_some()
+    }
+
     /// Create a constraint by name with configuration
     pub fn create_constraint(&self, name: &str, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
         let factory = self.constraints.get(name)
             .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown constraint: {}", name)))?;
             
-        factory.create(config)
+        factory.create_with_pronunciations(config, &self.pronunciations)
+    }
+    
+    /// The lexicon sound-based constraints are created with
+    pub fn pronunciations(&self) -> &Arc<PronunciationDictionary> {
+        &self.pronunciations
+    }
+    
+    /// Create sound-based constraints with `pronunciations` from now on
+    pub fn set_pronunciations(&mut self, pronunciations: Arc<PronunciationDictionary>) {
+        self.pronunciations = pronunciations;
     }
     
     /// Get list of all available constraint names
@@ -103,6 +1253,16 @@ impl ConstraintRegistry {
         self.constraints.keys().map(|s| s.as_str()).collect()
     }
     
+    /// Check whether a constraint is registered under the given name
+    pub fn contains(&self, name: &str) -> bool {
+        self.constraints.contains_key(name)
+    }
+
+    /// The largest unit a constraint can check on its own; see `ConstraintFactory::locality`
+    pub fn locality(&self, name: &str) -> Option<Scope> {
+        self.constraints.get(name).map(|f| f.locality())
+    }
+
     /// Get configuration schema for a constraint
     pub fn get_config_schema(&self, name: &str) -> Option<serde_json::Value> {
         self.constraints.get(name).map(|f| f.config_schema())
@@ -0,0 +1,219 @@
+//! Rhyme comparison of line-final words.
+//!
+//! Words are compared by their phonetic rime: the phonemes from the last
+//! stressed vowel to the end of the word. Matching rimes make a perfect rhyme,
+//! a shared vowel or shared final consonants make a slant rhyme, and matching
+//! spellings without matching sounds make an eye rhyme ("cough" / "bough").
+
+use crate::oulipo::{
+    phonetics::{Pronunciation, PronunciationDictionary, Stress},
+    utils::word_spans,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How closely two words rhyme, from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RhymeLevel {
+    None,
+    /// Spelled alike but pronounced differently ("love" / "prove" in spelling only)
+    Eye,
+    /// Same stressed vowel or same final consonants ("soul" / "all")
+    Slant,
+    /// Same sounds from the last stressed vowel onwards ("night" / "light")
+    Perfect,
+    /// The same word
+    Identical,
+}
+
+impl RhymeLevel {
+    /// Parse a level name as used in constraint configs
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "none" => Some(RhymeLevel::None),
+            "eye" => Some(RhymeLevel::Eye),
+            "slant" | "near" | "half" => Some(RhymeLevel::Slant),
+            "perfect" | "full" => Some(RhymeLevel::Perfect),
+            "identical" => Some(RhymeLevel::Identical),
+            _ => None,
+        }
+    }
+}
+
+/// Compares words by rhyme using a pronunciation dictionary
+pub struct RhymeEngine {
+    pronunciations: Arc<PronunciationDictionary>,
+}
+
+impl RhymeEngine {
+    /// Create an engine backed by the built-in pronunciation lexicon
+    pub fn new() -> Self {
+        Self::with_pronunciations(PronunciationDictionary::shared())
+    }
+
+    /// Create an engine backed by a specific pronunciation dictionary
+    pub fn with_pronunciations(pronunciations: Arc<PronunciationDictionary>) -> Self {
+        Self { pronunciations }
+    }
+
+    /// Phonetic rime of a word's primary pronunciation, without stress digits
+    pub fn rime(&self, word: &str) -> Option<Vec<String>> {
+        self.pronunciations.lookup(word).map(phonetic_rime)
+    }
+
+    /// How closely two words rhyme
+    ///
+    /// Every pronunciation variant is tried and the strongest match wins. When
+    /// either word is missing from the lexicon, matching spelled rimes are taken
+    /// as a perfect rhyme since there is nothing better to go on.
+    pub fn compare(&self, first: &str, second: &str) -> RhymeLevel {
+        let (a, b) = (normalize_word(first), normalize_word(second));
+        if a.is_empty() || b.is_empty() {
+            return RhymeLevel::None;
+        }
+        if a == b {
+            return RhymeLevel::Identical;
+        }
+
+        let spelled_alike = {
+            let rime = spelling_rime(&a);
+            rime.len() >= 2 && rime == spelling_rime(&b)
+        };
+
+        let (variants_a, variants_b) = (self.pronunciations.variants(&a), self.pronunciations.variants(&b));
+        if variants_a.is_empty() || variants_b.is_empty() {
+            return if spelled_alike { RhymeLevel::Perfect } else { RhymeLevel::None };
+        }
+
+        let phonetic = variants_a
+            .iter()
+            .flat_map(|pa| variants_b.iter().map(move |pb| compare_rimes(&phonetic_rime(pa), &phonetic_rime(pb))))
+            .max()
+            .unwrap_or(RhymeLevel::None);
+
+        if phonetic == RhymeLevel::None && spelled_alike {
+            RhymeLevel::Eye
+        } else {
+            phonetic
+        }
+    }
+
+    /// Whether two words rhyme at least at the given level
+    pub fn rhymes(&self, first: &str, second: &str, min_level: RhymeLevel) -> bool {
+        self.compare(first, second) >= min_level
+    }
+
+    /// Label each word with a scheme letter, reusing the letter of the first
+    /// earlier word it rhymes with at `min_level` or better
+    pub fn detect_scheme(&self, words: &[&str], min_level: RhymeLevel) -> Vec<char> {
+        let mut letters: Vec<char> = Vec::with_capacity(words.len());
+        let mut next = 0u8;
+
+        for (idx, word) in words.iter().enumerate() {
+            let earlier = (0..idx).find(|&prev| self.rhymes(words[prev], word, min_level));
+            match earlier {
+                Some(prev) => letters.push(letters[prev]),
+                None => {
+                    letters.push(scheme_letter(next));
+                    next = next.saturating_add(1);
+                }
+            }
+        }
+
+        letters
+    }
+}
+
+impl Default for RhymeEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Last word of a line with its byte offset in the line
+pub fn end_word(line: &str) -> Option<(usize, &str)> {
+    word_spans(line).pop()
+}
+
+/// Lowercase a word and normalise curly apostrophes for comparison
+pub fn normalize_word(word: &str) -> String {
+    word.trim().to_lowercase().replace('’', "'")
+}
+
+/// Phonemes from the last stressed vowel to the end, without stress digits
+///
+/// Words with no stressed vowel ("the") use their last vowel instead.
+pub fn phonetic_rime(pronunciation: &Pronunciation) -> Vec<String> {
+    let phonemes = pronunciation.phonemes();
+    let stress_of = |p: &String| match p.chars().last() {
+        Some('1') => Some(Stress::Primary),
+        Some('2') => Some(Stress::Secondary),
+        Some('0') => Some(Stress::Unstressed),
+        _ => None,
+    };
+
+    let start = phonemes
+        .iter()
+        .rposition(|p| stress_of(p) == Some(Stress::Primary))
+        .or_else(|| phonemes.iter().rposition(|p| stress_of(p).is_some_and(Stress::is_stressed)))
+        .or_else(|| phonemes.iter().rposition(|p| stress_of(p).is_some()))
+        .unwrap_or(0);
+
+    phonemes[start..]
+        .iter()
+        .map(|p| p.trim_end_matches(|c: char| c.is_ascii_digit()).to_string())
+        .collect()
+}
+
+/// Spelled rime: the last vowel group and everything after it
+///
+/// A silent final `e` is skipped over when finding the vowel group, so "love"
+/// gives "ove" rather than "e".
+pub fn spelling_rime(word: &str) -> String {
+    let chars: Vec<char> = word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    let is_vowel = |i: usize| "aeiou".contains(chars[i]) || (chars[i] == 'y' && i > 0);
+
+    let n = chars.len();
+    let search_end = if n > 2 && chars[n - 1] == 'e' && !is_vowel(n - 2) { n - 1 } else { n };
+    let Some(mut start) = (0..search_end).rev().find(|&i| is_vowel(i)) else {
+        return chars.into_iter().collect();
+    };
+    while start > 0 && is_vowel(start - 1) {
+        start -= 1;
+    }
+
+    chars[start..].iter().collect()
+}
+
+fn compare_rimes(a: &[String], b: &[String]) -> RhymeLevel {
+    if a.is_empty() || b.is_empty() {
+        return RhymeLevel::None;
+    }
+    if a == b {
+        return RhymeLevel::Perfect;
+    }
+
+    // Assonance: the stressed vowel matches
+    let same_vowel = a[0] == b[0];
+    // Consonance: everything after the stressed vowel matches
+    let same_coda = a.len() > 1 && a[1..] == b[1..];
+    if same_vowel || same_coda {
+        RhymeLevel::Slant
+    } else {
+        RhymeLevel::None
+    }
+}
+
+/// Letters A..Z, then a..z for unusually long poems
+fn scheme_letter(index: u8) -> char {
+    match index {
+        0..=25 => (b'A' + index) as char,
+        26..=51 => (b'a' + index - 26) as char,
+        _ => '?',
+    }
+}
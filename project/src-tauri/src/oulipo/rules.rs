@@ -0,0 +1,565 @@
+//! User-defined constraints written as declarative rules.
+//!
+//! A rule is data: a name, the scope it applies to, and a list of checks that
+//! must all hold in every unit of that scope. A check counts either matches
+//! of a regular expression or words satisfying a predicate (length, letters,
+//! membership in a word list), and bounds the count. "No word over 7 letters"
+//! is a words check with `where = { min_length = 8 }` and `max = 0`.
+//!
+//! Rules are read from JSON or TOML, compiled once, and registered in the
+//! `ConstraintRegistry` like any built-in constraint, with a schema generated
+//! from the definition.
+
+use crate::oulipo::{
+    types::{Constraint, ConstraintResult, Violation},
+    composition::Scope,
+    errors::{OulipoError, OulipoResult},
+    registry::ConstraintFactory,
+    utils::{share, word_spans},
+};
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+/// A house constraint written as data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleDefinition {
+    /// The registry name: lowercase letters, digits, `_` and `-`
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The unit every check applies to; named ranges are not supported
+    #[serde(default)]
+    pub scope: Scope,
+    /// Word lists that `in_list` and `not_in_list` can name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lists: BTreeMap<String, Vec<String>>,
+    /// Checks that must all hold in each unit
+    pub checks: Vec<RuleCheck>,
+}
+
+/// One counted check of a rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum RuleCheck {
+    /// Matches of a regular expression in the unit
+    Pattern {
+        pattern: String,
+        min: Option<usize>,
+        max: Option<usize>,
+        /// The issue reported when the check fails, instead of the generated one
+        message: Option<String>,
+    },
+    /// Words of the unit that satisfy a predicate
+    Words {
+        #[serde(rename = "where", default)]
+        predicate: Box<WordPredicate>,
+        /// Which words of the unit are counted
+        #[serde(default)]
+        position: WordPosition,
+        min: Option<usize>,
+        max: Option<usize>,
+        message: Option<String>,
+    },
+}
+
+/// Which words of a unit a words check looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordPosition {
+    #[default]
+    Any,
+    First,
+    Last,
+}
+
+/// Conditions a word must all meet; letters are compared case-insensitively
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WordPredicate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_with: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_with: Option<String>,
+    /// Uses no letter outside these
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only_letters: Option<String>,
+    /// Uses at least one of these letters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub any_letters: Option<String>,
+    /// Uses none of these letters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_letters: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_list: Option<WordList>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_in_list: Option<WordList>,
+    /// A regular expression the whole word must match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// A word list given inline or by the name of one of the rule's `lists`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WordList {
+    Named(String),
+    Words(Vec<String>),
+}
+
+/// A set of rules as stored in a JSON or TOML file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleFile {
+    #[serde(default)]
+    pub rules: Vec<RuleDefinition>,
+}
+
+/// How a rule file is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl RuleFormat {
+    /// Parse a format name as used in commands
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "json" => Some(RuleFormat::Json),
+            "toml" => Some(RuleFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// The format a file's extension calls for; anything but `.toml` is JSON
+    pub fn for_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => RuleFormat::Toml,
+            _ => RuleFormat::Json,
+        }
+    }
+}
+
+/// Read rules from a JSON or TOML document with a top-level `rules` list
+pub fn parse_rules(source: &str, format: RuleFormat) -> OulipoResult<Vec<RuleDefinition>> {
+    let file: RuleFile = match format {
+        RuleFormat::Json => serde_json::from_str(source)?,
+        RuleFormat::Toml => toml::from_str(source)
+            .map_err(|e| OulipoError::InvalidConfig(format!("Invalid TOML rules: {}", e)))?,
+    };
+    Ok(file.rules)
+}
+
+/// Write rules as a JSON or TOML document that `parse_rules` reads back
+pub fn write_rules(rules: &[RuleDefinition], format: RuleFormat) -> OulipoResult<String> {
+    let file = RuleFile { rules: rules.to_vec() };
+    Ok(match format {
+        RuleFormat::Json => serde_json::to_string_pretty(&file)?,
+        RuleFormat::Toml => toml::to_string_pretty(&file)
+            .map_err(|e| OulipoError::InvalidConfig(format!("Rules cannot be written as TOML: {}", e)))?,
+    })
+}
+
+/// A word predicate with its lists and pattern ready to test
+#[derive(Debug)]
+struct CompiledPredicate {
+    predicate: WordPredicate,
+    in_list: Option<HashSet<String>>,
+    not_in_list: Option<HashSet<String>>,
+    pattern: Option<Regex>,
+}
+
+impl CompiledPredicate {
+    fn matches(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        let letters: Vec<char> = lower.chars().filter(|c| c.is_alphabetic()).collect();
+        let p = &self.predicate;
+        p.min_length.is_none_or(|min| letters.len() >= min)
+            && p.max_length.is_none_or(|max| letters.len() <= max)
+            && p.starts_with.as_ref().is_none_or(|prefix| lower.starts_with(&prefix.to_lowercase()))
+            && p.ends_with.as_ref().is_none_or(|suffix| lower.ends_with(&suffix.to_lowercase()))
+            && p.only_letters.as_ref().is_none_or(|allowed| letters.iter().all(|&c| contains_letter(allowed, c)))
+            && p.any_letters.as_ref().is_none_or(|wanted| letters.iter().any(|&c| contains_letter(wanted, c)))
+            && p.no_letters.as_ref().is_none_or(|banned| !letters.iter().any(|&c| contains_letter(banned, c)))
+            && self.in_list.as_ref().is_none_or(|list| list.contains(&lower))
+            && self.not_in_list.as_ref().is_none_or(|list| !list.contains(&lower))
+            && self.pattern.as_ref().is_none_or(|pattern| pattern.is_match(word))
+    }
+
+    /// The predicate in words, e.g. "with at least 8 letters"
+    fn describe(&self) -> String {
+        let p = &self.predicate;
+        let mut parts = Vec::new();
+        match (p.min_length, p.max_length) {
+            (Some(min), Some(max)) => parts.push(format!("with {} to {} letters", min, max)),
+            (Some(min), None) => parts.push(format!("with at least {} letters", min)),
+            (None, Some(max)) => parts.push(format!("with at most {} letters", max)),
+            (None, None) => {}
+        }
+        if let Some(prefix) = &p.starts_with {
+            parts.push(format!("starting with '{}'", prefix));
+        }
+        if let Some(suffix) = &p.ends_with {
+            parts.push(format!("ending with '{}'", suffix));
+        }
+        if let Some(letters) = &p.only_letters {
+            parts.push(format!("using only '{}'", letters));
+        }
+        if let Some(letters) = &p.any_letters {
+            parts.push(format!("using any of '{}'", letters));
+        }
+        if let Some(letters) = &p.no_letters {
+            parts.push(format!("without '{}'", letters));
+        }
+        if let Some(list) = &p.in_list {
+            parts.push(format!("from {}", describe_list(list)));
+        }
+        if let Some(list) = &p.not_in_list {
+            parts.push(format!("not from {}", describe_list(list)));
+        }
+        if let Some(pattern) = &p.pattern {
+            parts.push(format!("matching /{}/", pattern));
+        }
+        parts.join(" and ")
+    }
+}
+
+fn contains_letter(letters: &str, letter: char) -> bool {
+    letters.chars().flat_map(char::to_lowercase).any(|c| c == letter)
+}
+
+fn describe_list(list: &WordList) -> String {
+    match list {
+        WordList::Named(name) => format!("the list '{}'", name),
+        WordList::Words(words) if words.len() <= 5 => format!("[{}]", words.join(", ")),
+        WordList::Words(words) => format!("a list of {} words", words.len()),
+    }
+}
+
+/// A check with its regular expressions compiled
+#[derive(Debug)]
+enum CompiledCheck {
+    Pattern { pattern: Regex, min: Option<usize>, max: Option<usize>, message: Option<String> },
+    Words { predicate: Box<CompiledPredicate>, position: WordPosition, min: Option<usize>, max: Option<usize>, message: Option<String> },
+}
+
+/// A rule validated and ready to check text
+#[derive(Debug)]
+pub struct CompiledRule {
+    definition: RuleDefinition,
+    checks: Vec<CompiledCheck>,
+}
+
+impl CompiledRule {
+    /// Validate a definition and compile its patterns and word lists
+    pub fn compile(definition: RuleDefinition) -> OulipoResult<Self> {
+        let invalid = |message: String| OulipoError::InvalidConfig(format!("Rule '{}': {}", definition.name, message));
+        let valid_name = !definition.name.is_empty()
+            && definition.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_name {
+            return Err(invalid("names may only use lowercase letters, digits, '_' and '-'".to_string()));
+        }
+        if matches!(definition.scope, Scope::Range(_)) {
+            return Err(invalid("rules cannot be scoped to a named range".to_string()));
+        }
+        if definition.checks.is_empty() {
+            return Err(invalid("a rule needs at least one check".to_string()));
+        }
+
+        let mut checks = Vec::with_capacity(definition.checks.len());
+        for (index, check) in definition.checks.iter().enumerate() {
+            let (min, max) = match check {
+                RuleCheck::Pattern { min, max, .. } | RuleCheck::Words { min, max, .. } => (*min, *max),
+            };
+            match (min, max) {
+                (None, None) => return Err(invalid(format!("check {} needs a 'min' or a 'max'", index + 1))),
+                (Some(min), Some(max)) if min > max => {
+                    return Err(invalid(format!("check {} has 'min' {} above 'max' {}", index + 1, min, max)))
+                }
+                _ => {}
+            }
+
+            checks.push(match check {
+                RuleCheck::Pattern { pattern, min, max, message } => {
+                    let regex = Regex::new(pattern).map_err(|e| invalid(format!("check {}: {}", index + 1, e)))?;
+                    // An empty match would be counted between every pair of characters
+                    if regex.is_match("") {
+                        return Err(invalid(format!("check {}: /{}/ matches empty text", index + 1, pattern)));
+                    }
+                    CompiledCheck::Pattern {
+                        pattern: regex,
+                        min: *min,
+                        max: *max,
+                        message: message.clone(),
+                    }
+                }
+                RuleCheck::Words { predicate, position, min, max, message } => {
+                    let list = |list: &Option<WordList>| -> OulipoResult<Option<HashSet<String>>> {
+                        let words = match list {
+                            None => return Ok(None),
+                            Some(WordList::Words(words)) => words,
+                            Some(WordList::Named(name)) => definition
+                                .lists
+                                .get(name)
+                                .ok_or_else(|| invalid(format!("check {} names an unknown list '{}'", index + 1, name)))?,
+                        };
+                        Ok(Some(words.iter().map(|w| w.trim().to_lowercase()).collect()))
+                    };
+                    let pattern = match &predicate.pattern {
+                        Some(pattern) => Some(
+                            Regex::new(&format!("^(?:{})$", pattern))
+                                .map_err(|e| invalid(format!("check {}: {}", index + 1, e)))?,
+                        ),
+                        None => None,
+                    };
+                    CompiledCheck::Words {
+                        predicate: Box::new(CompiledPredicate {
+                            in_list: list(&predicate.in_list)?,
+                            not_in_list: list(&predicate.not_in_list)?,
+                            pattern,
+                            predicate: (**predicate).clone(),
+                        }),
+                        position: *position,
+                        min: *min,
+                        max: *max,
+                        message: message.clone(),
+                    }
+                }
+            });
+        }
+
+        Ok(Self { definition, checks })
+    }
+
+    /// The definition the rule was compiled from
+    pub fn definition(&self) -> &RuleDefinition {
+        &self.definition
+    }
+
+    /// Check every unit of the rule's scope against every check
+    pub fn check(&self, text: &str) -> ConstraintResult {
+        let scope = &self.definition.scope;
+        let units = scope.units(text).unwrap_or_default();
+        let mut violations = Vec::new();
+        let mut passed = 0;
+
+        for (index, &(offset, unit)) in units.iter().enumerate() {
+            let label = scope.unit_label(index);
+            for check in &self.checks {
+                let before = violations.len();
+                match check {
+                    CompiledCheck::Pattern { pattern, min, max, message } => {
+                        let found: Vec<_> = pattern.find_iter(unit).collect();
+                        let count = found.len();
+                        for m in found.iter().skip(max.unwrap_or(usize::MAX)) {
+                            violations.push(Violation {
+                                position: offset + m.start(),
+                                length: m.len(),
+                                span: None,
+                                issue: message.clone().unwrap_or_else(|| format!(
+                                    "{}: '{}' is one of {} matches of /{}/, but at most {} are allowed",
+                                    label, m.as_str(), count, pattern, max.unwrap_or(0)
+                                )),
+                                suggestion: Some(format!("Remove or reword '{}'", m.as_str())),
+                                fixes: Vec::new(),
+                            });
+                        }
+                        if let Some(min) = min.filter(|&min| count < min) {
+                            violations.push(Violation {
+                                position: offset,
+                                length: unit.len(),
+                                span: None,
+                                issue: message.clone().unwrap_or_else(|| format!(
+                                    "{}: /{}/ matches {} times, but needs at least {}",
+                                    label, pattern, count, min
+                                )),
+                                suggestion: Some(format!("Add text matching /{}/", pattern)),
+                                fixes: Vec::new(),
+                            });
+                        }
+                    }
+                    CompiledCheck::Words { predicate, position, min, max, message } => {
+                        let words = word_spans(unit);
+                        let candidates: Vec<(usize, &str)> = match position {
+                            WordPosition::Any => words,
+                            WordPosition::First => words.first().copied().into_iter().collect(),
+                            WordPosition::Last => words.last().copied().into_iter().collect(),
+                        };
+                        let matching: Vec<(usize, &str)> =
+                            candidates.into_iter().filter(|(_, word)| predicate.matches(word)).collect();
+                        let count = matching.len();
+                        let described = predicate.describe();
+                        let kind = if described.is_empty() { "a word".to_string() } else { format!("a word {}", described) };
+
+                        for &(position, word) in matching.iter().skip(max.unwrap_or(usize::MAX)) {
+                            violations.push(Violation {
+                                position: offset + position,
+                                length: word.len(),
+                                span: None,
+                                issue: message.clone().unwrap_or_else(|| match max {
+                                    Some(0) => format!("{}: '{}' is {}, which '{}' forbids", label, word, kind, self.definition.name),
+                                    _ => format!("{}: '{}' is {}, one more than the {} allowed", label, word, kind, max.unwrap_or(0)),
+                                }),
+                                suggestion: Some(format!("Replace '{}'", word)),
+                                fixes: Vec::new(),
+                            });
+                        }
+                        if let Some(min) = min.filter(|&min| count < min) {
+                            let issue = match position {
+                                WordPosition::First => format!("{} should start with {}", label, kind),
+                                WordPosition::Last => format!("{} should end with {}", label, kind),
+                                WordPosition::Any => format!("{} has {} of {}, but needs at least {}", label, count, kind, min),
+                            };
+                            violations.push(Violation {
+                                position: offset,
+                                length: unit.len(),
+                                span: None,
+                                issue: message.clone().unwrap_or(issue),
+                                suggestion: Some(format!("Work in {}", kind)),
+                                fixes: Vec::new(),
+                            });
+                        }
+                    }
+                }
+                if violations.len() == before {
+                    passed += 1;
+                }
+            }
+        }
+        violations.sort_by_key(|v| v.position);
+
+        let success = violations.is_empty();
+        let violation_count = violations.len();
+        let name = &self.definition.name;
+        ConstraintResult {
+            success,
+            result: Some(if success {
+                format!("Satisfies {}", name)
+            } else {
+                format!("{} has {} violations", name, violation_count)
+            }),
+            violations,
+            suggestions: if success || self.definition.description.is_empty() {
+                Vec::new()
+            } else {
+                vec![self.definition.description.clone()]
+            },
+            metadata: serde_json::json!({
+                "constraint_type": "user_rule",
+                "rule": name,
+                "scope": scope,
+                "unit_count": units.len(),
+                "violation_count": violation_count
+            }),
+        }
+        .with_score(share(passed, units.len() * self.checks.len()))
+        .located(text)
+    }
+
+    /// A schema for the rule: it takes no configuration, and describes its checks
+    pub fn schema(&self) -> serde_json::Value {
+        let checks: Vec<String> = self
+            .checks
+            .iter()
+            .map(|check| {
+                let (what, min, max) = match check {
+                    CompiledCheck::Pattern { pattern, min, max, .. } => (format!("matches of /{}/", pattern), min, max),
+                    CompiledCheck::Words { predicate, position, min, max, .. } => {
+                        let described = predicate.describe();
+                        let words = match position {
+                            WordPosition::Any => "words".to_string(),
+                            WordPosition::First => "first words".to_string(),
+                            WordPosition::Last => "last words".to_string(),
+                        };
+                        (if described.is_empty() { words } else { format!("{} {}", words, described) }, min, max)
+                    }
+                };
+                match (min, max) {
+                    (Some(min), Some(max)) if min == max => format!("exactly {} {}", min, what),
+                    (Some(min), Some(max)) => format!("{} to {} {}", min, max, what),
+                    (Some(min), None) => format!("at least {} {}", min, what),
+                    (_, max) => format!("at most {} {}", max.unwrap_or(0), what),
+                }
+            })
+            .collect();
+
+        serde_json::json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false,
+            "description": self.definition.description,
+            "x-user-rule": {
+                "scope": self.definition.scope,
+                "checks": checks
+            }
+        })
+    }
+}
+
+/// A registry factory for one user rule
+pub struct RuleFactory {
+    rule: Arc<CompiledRule>,
+}
+
+impl RuleFactory {
+    pub fn new(rule: CompiledRule) -> Self {
+        Self { rule: Arc::new(rule) }
+    }
+}
+
+impl ConstraintFactory for RuleFactory {
+    fn create(&self, config: &serde_json::Value) -> OulipoResult<Box<dyn Constraint>> {
+        if config.as_object().is_some_and(|settings| !settings.is_empty()) {
+            return Err(OulipoError::InvalidConfig(format!(
+                "'{}' is a user rule and takes no configuration",
+                self.rule.definition.name
+            )));
+        }
+        Ok(Box::new(RuleConstraint { rule: Arc::clone(&self.rule) }))
+    }
+
+    fn name(&self) -> &str {
+        &self.rule.definition.name
+    }
+
+    fn description(&self) -> &str {
+        &self.rule.definition.description
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        self.rule.schema()
+    }
+
+    /// Each check is counted within one unit, so units can be checked apart
+    fn locality(&self) -> Scope {
+        self.rule.definition.scope.clone()
+    }
+}
+
+/// A user rule as a constraint
+pub struct RuleConstraint {
+    rule: Arc<CompiledRule>,
+}
+
+impl Constraint for RuleConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        Ok(self.rule.check(text))
+    }
+
+    fn name(&self) -> &str {
+        &self.rule.definition.name
+    }
+
+    fn description(&self) -> &str {
+        &self.rule.definition.description
+    }
+}
@@ -0,0 +1,282 @@
+//! Incremental checking sessions for live editing.
+//!
+//! A session holds one document and one registry constraint. The editor sends
+//! each change as a delta. The session patches its copy of the text, splits it
+//! into units of the constraint's locality (lines for a lipogram, the whole
+//! text for a sestina) and checks only the units whose text it has not seen.
+//! A violation keeps its id for as long as it survives the edits, so an update
+//! reports just the violations that appeared and the ids of those that went.
+
+use crate::oulipo::{
+    types::Violation,
+    composition::Scope,
+    errors::{OulipoError, OulipoResult},
+    registry::ConstraintRegistry,
+    span::{OffsetUnit, SpanIndex},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Replace `start..end` of the session text with `text`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextDelta {
+    pub start: usize,
+    pub end: usize,
+    #[serde(default)]
+    pub text: String,
+    /// What `start` and `end` count; editors running JavaScript send `utf16`
+    #[serde(default)]
+    pub unit: OffsetUnit,
+}
+
+impl TextDelta {
+    /// Replace bytes `start..end` with `text`
+    pub fn new(start: usize, end: usize, text: &str) -> Self {
+        Self { start, end, text: text.to_string(), unit: OffsetUnit::Byte }
+    }
+
+    /// Insert `text` at byte `position`
+    pub fn insert(position: usize, text: &str) -> Self {
+        Self::new(position, position, text)
+    }
+}
+
+/// A violation with an id that stays the same while the violation survives edits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedViolation {
+    pub id: u64,
+    #[serde(flatten)]
+    pub violation: Violation,
+}
+
+/// What changed when a session was opened or updated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionUpdate {
+    /// The session's id, for later updates
+    pub session: u64,
+    /// Violations that appeared, located in the updated text
+    pub added: Vec<TrackedViolation>,
+    /// Ids of violations that went away
+    pub removed: Vec<u64>,
+    /// Units that had to be checked, out of `total_units`
+    pub checked_units: usize,
+    pub total_units: usize,
+    /// Whether the text now satisfies the constraint
+    pub success: bool,
+    pub violation_count: usize,
+}
+
+/// The check of one unit, with positions relative to the unit
+#[derive(Debug, Clone)]
+struct UnitCheck {
+    success: bool,
+    violations: Vec<Violation>,
+}
+
+/// A byte range `start..end` replaced by `inserted` bytes
+#[derive(Debug, Clone, Copy)]
+struct Edit {
+    start: usize,
+    end: usize,
+    inserted: usize,
+}
+
+impl Edit {
+    /// Where `start..end` moved to, or `None` if the edit touched it
+    fn map(&self, start: usize, end: usize) -> Option<usize> {
+        if end <= self.start {
+            Some(start)
+        } else if start >= self.end {
+            Some(start - self.end + self.start + self.inserted)
+        } else {
+            None
+        }
+    }
+}
+
+/// A document checked incrementally against one registry constraint
+pub struct CheckSession {
+    id: u64,
+    constraint: String,
+    config: serde_json::Value,
+    scope: Scope,
+    text: String,
+    /// Checks by unit text; equal units share a check
+    checks: HashMap<String, UnitCheck>,
+    violations: Vec<TrackedViolation>,
+    success: bool,
+    next_violation: u64,
+}
+
+impl CheckSession {
+    /// Open a session on `text` and check it in full
+    ///
+    /// Without a scope, the constraint's own locality is used; see
+    /// `ConstraintFactory::locality`. A wider scope than the constraint
+    /// supports changes what is checked, as scoping a composition does.
+    pub fn open(
+        id: u64,
+        text: &str,
+        constraint: &str,
+        config: serde_json::Value,
+        scope: Option<Scope>,
+        registry: &ConstraintRegistry,
+    ) -> OulipoResult<(Self, SessionUpdate)> {
+        let scope = match scope {
+            Some(scope) => scope,
+            None => registry
+                .locality(constraint)
+                .ok_or_else(|| OulipoError::InvalidConfig(format!("Unknown constraint: {}", constraint)))?,
+        };
+        if scope.units("").is_none() {
+            return Err(OulipoError::InvalidConfig("Sessions cannot be scoped to a named range".to_string()));
+        }
+
+        let mut session = Self {
+            id,
+            constraint: constraint.to_string(),
+            config,
+            scope,
+            text: text.to_string(),
+            checks: HashMap::new(),
+            violations: Vec::new(),
+            success: true,
+            next_violation: 1,
+        };
+        let update = session.refresh(&[], registry)?;
+        Ok((session, update))
+    }
+
+    /// Apply deltas, each to the text left by the one before, and re-check the changed units
+    ///
+    /// A delta outside the text or splitting a character is an error, and
+    /// leaves the session as it was.
+    pub fn update(&mut self, deltas: &[TextDelta], registry: &ConstraintRegistry) -> OulipoResult<SessionUpdate> {
+        let mut text = self.text.clone();
+        let mut edits = Vec::with_capacity(deltas.len());
+        for delta in deltas {
+            let (start, end) = match delta.unit {
+                OffsetUnit::Byte => (delta.start, delta.end),
+                unit => {
+                    let index = SpanIndex::new(&text);
+                    (
+                        index.byte_offset(delta.start, unit).unwrap_or(usize::MAX),
+                        index.byte_offset(delta.end, unit).unwrap_or(usize::MAX),
+                    )
+                }
+            };
+            if start > end || end > text.len() || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                return Err(OulipoError::TextProcessing(format!(
+                    "Delta {}..{} ({:?}) does not fall on character boundaries of the text",
+                    delta.start, delta.end, delta.unit
+                )));
+            }
+            text.replace_range(start..end, &delta.text);
+            edits.push(Edit { start, end, inserted: delta.text.len() });
+        }
+        self.text = text;
+        self.refresh(&edits, registry)
+    }
+
+    /// The session's id
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The current text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The unit the text is checked in
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Every current violation, in text order
+    pub fn violations(&self) -> &[TrackedViolation] {
+        &self.violations
+    }
+
+    /// Whether the text satisfies the constraint
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    /// Re-check units not seen before, and match the violations with those before `edits`
+    fn refresh(&mut self, edits: &[Edit], registry: &ConstraintRegistry) -> OulipoResult<SessionUpdate> {
+        let constraint = registry.create_constraint(&self.constraint, &self.config)?;
+        let units = self.scope.units(&self.text).unwrap_or_default();
+
+        let mut previous = std::mem::take(&mut self.checks);
+        let mut checked_units = 0;
+        for &(_, unit) in &units {
+            if self.checks.contains_key(unit) {
+                continue;
+            }
+            let check = match previous.remove(unit) {
+                Some(check) => check,
+                None => {
+                    checked_units += 1;
+                    let result = constraint
+                        .check(unit)
+                        .map_err(|e| OulipoError::ValidationError(format!("{} failed on a unit: {}", self.constraint, e)))?;
+                    UnitCheck { success: result.success, violations: result.violations }
+                }
+            };
+            self.checks.insert(unit.to_string(), check);
+        }
+
+        // Violations that moved with the text keep their ids
+        let mut surviving: HashMap<(usize, usize), Vec<(u64, &str)>> = HashMap::new();
+        for tracked in &self.violations {
+            let violation = &tracked.violation;
+            let moved = edits.iter().try_fold(violation.position, |position, edit| {
+                edit.map(position, position + violation.length)
+            });
+            if let Some(position) = moved {
+                surviving.entry((position, violation.length)).or_default().push((tracked.id, violation.issue.as_str()));
+            }
+        }
+
+        let index = SpanIndex::new(&self.text);
+        let mut kept = HashSet::new();
+        let mut violations = Vec::new();
+        let mut added = HashSet::new();
+        for &(offset, unit) in &units {
+            for violation in &self.checks[unit].violations {
+                let mut violation = violation.clone().shifted(offset);
+                violation.locate(&index);
+                let survivor = surviving.get_mut(&(violation.position, violation.length)).and_then(|candidates| {
+                    let found = candidates.iter().position(|(_, issue)| *issue == violation.issue)?;
+                    Some(candidates.swap_remove(found).0)
+                });
+                let id = match survivor {
+                    Some(id) => {
+                        kept.insert(id);
+                        id
+                    }
+                    None => {
+                        self.next_violation += 1;
+                        added.insert(self.next_violation - 1);
+                        self.next_violation - 1
+                    }
+                };
+                violations.push(TrackedViolation { id, violation });
+            }
+        }
+        let removed: Vec<u64> = self.violations.iter().map(|v| v.id).filter(|id| !kept.contains(id)).collect();
+
+        self.success = units.iter().all(|(_, unit)| self.checks[*unit].success);
+        self.violations = violations;
+        Ok(SessionUpdate {
+            session: self.id,
+            added: self.violations.iter().filter(|v| added.contains(&v.id)).cloned().collect(),
+            removed,
+            checked_units,
+            total_units: units.len(),
+            success: self.success,
+            violation_count: self.violations.len(),
+        })
+    }
+}
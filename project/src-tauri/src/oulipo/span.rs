@@ -0,0 +1,170 @@
+//! Text positions in every unit a front end may need.
+//!
+//! Constraints find violations by byte offset, which is what Rust slicing
+//! uses. Editors count differently: JavaScript strings index UTF-16 code
+//! units, and people read lines and columns. A `TextSpan` carries all of them,
+//! so a highlight lands on the same characters whether the text is plain
+//! ASCII, accented or full of emoji.
+
+use serde::{Deserialize, Serialize};
+
+/// A half-open range of offsets in one unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OffsetRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A 1-based line and column; columns count characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The same stretch of text as byte, character and UTF-16 ranges, and as lines and columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TextSpan {
+    /// Byte offsets, for slicing the Rust string
+    pub byte: OffsetRange,
+    /// Unicode scalar values, as counted by `str::chars`
+    pub char: OffsetRange,
+    /// UTF-16 code units, as counted by JavaScript strings
+    pub utf16: OffsetRange,
+    /// Position of the first character
+    pub start: LineColumn,
+    /// Position just after the last character
+    pub end: LineColumn,
+}
+
+impl TextSpan {
+    /// The span of bytes `start..end` of `text`
+    ///
+    /// Builds a `SpanIndex` for the one span; index the text once when
+    /// locating many spans.
+    pub fn new(text: &str, start: usize, end: usize) -> Self {
+        SpanIndex::new(text).span(start, end)
+    }
+}
+
+/// What an offset counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetUnit {
+    /// Bytes of the UTF-8 text
+    #[default]
+    Byte,
+    /// Unicode scalar values
+    Char,
+    /// UTF-16 code units, as JavaScript strings count
+    Utf16,
+}
+
+/// Offsets of the start of a line in each unit
+#[derive(Debug, Clone, Copy)]
+struct LineStart {
+    byte: usize,
+    char: usize,
+    utf16: usize,
+}
+
+/// Line starts of a text, for converting many byte offsets cheaply
+pub struct SpanIndex<'a> {
+    text: &'a str,
+    lines: Vec<LineStart>,
+}
+
+impl<'a> SpanIndex<'a> {
+    /// Index the start of every line of `text`
+    pub fn new(text: &'a str) -> Self {
+        let mut lines = vec![LineStart { byte: 0, char: 0, utf16: 0 }];
+        let (mut chars, mut utf16) = (0, 0);
+        for (byte, ch) in text.char_indices() {
+            chars += 1;
+            utf16 += ch.len_utf16();
+            if ch == '\n' {
+                lines.push(LineStart { byte: byte + 1, char: chars, utf16 });
+            }
+        }
+        Self { text, lines }
+    }
+
+    /// The span of bytes `start..end`
+    ///
+    /// Offsets past the end are clamped to it, and offsets inside a character
+    /// widen the span to cover the whole character.
+    pub fn span(&self, start: usize, end: usize) -> TextSpan {
+        let start = self.floor_boundary(start.min(self.text.len()));
+        let end = self.ceil_boundary(end.clamp(start, self.text.len()));
+        let (start_char, start_utf16, start_position) = self.point(start);
+        let (end_char, end_utf16, end_position) = self.point(end);
+        TextSpan {
+            byte: OffsetRange { start, end },
+            char: OffsetRange { start: start_char, end: end_char },
+            utf16: OffsetRange { start: start_utf16, end: end_utf16 },
+            start: start_position,
+            end: end_position,
+        }
+    }
+
+    /// The byte offset of a character offset, if it is within the text
+    pub fn byte_from_char(&self, offset: usize) -> Option<usize> {
+        let line = self.lines.partition_point(|l| l.char <= offset) - 1;
+        let start = self.lines[line];
+        let mut count = start.char;
+        for (byte, _) in self.text[start.byte..].char_indices() {
+            if count == offset {
+                return Some(start.byte + byte);
+            }
+            count += 1;
+        }
+        (count == offset).then_some(self.text.len())
+    }
+
+    /// The byte offset of a UTF-16 offset, if it falls on a character boundary
+    pub fn byte_from_utf16(&self, offset: usize) -> Option<usize> {
+        let line = self.lines.partition_point(|l| l.utf16 <= offset) - 1;
+        let start = self.lines[line];
+        let mut count = start.utf16;
+        for (byte, ch) in self.text[start.byte..].char_indices() {
+            if count >= offset {
+                return (count == offset).then_some(start.byte + byte);
+            }
+            count += ch.len_utf16();
+        }
+        (count == offset).then_some(self.text.len())
+    }
+
+    /// The byte offset of an offset in any unit, if it falls on a character boundary
+    pub fn byte_offset(&self, offset: usize, unit: OffsetUnit) -> Option<usize> {
+        match unit {
+            OffsetUnit::Byte => (offset <= self.text.len() && self.text.is_char_boundary(offset)).then_some(offset),
+            OffsetUnit::Char => self.byte_from_char(offset),
+            OffsetUnit::Utf16 => self.byte_from_utf16(offset),
+        }
+    }
+
+    /// Character offset, UTF-16 offset and line/column of a byte offset on a boundary
+    fn point(&self, byte: usize) -> (usize, usize, LineColumn) {
+        let line = self.lines.partition_point(|l| l.byte <= byte) - 1;
+        let start = self.lines[line];
+        let before = &self.text[start.byte..byte];
+        let chars = before.chars().count();
+        let utf16: usize = before.chars().map(char::len_utf16).sum();
+        (start.char + chars, start.utf16 + utf16, LineColumn { line: line + 1, column: chars + 1 })
+    }
+
+    fn floor_boundary(&self, mut byte: usize) -> usize {
+        while !self.text.is_char_boundary(byte) {
+            byte -= 1;
+        }
+        byte
+    }
+
+    fn ceil_boundary(&self, mut byte: usize) -> usize {
+        while !self.text.is_char_boundary(byte) {
+            byte += 1;
+        }
+        byte
+    }
+}
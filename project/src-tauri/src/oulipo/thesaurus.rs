@@ -0,0 +1,329 @@
+//! Offline thesaurus: synonyms, antonyms and hypernyms by part of speech.
+//!
+//! Two data formats are read. Moby-style lists (`headword,synonym,...`) give
+//! untagged synonyms, closest first. WordNet data files (`data.noun`,
+//! `data.verb`, ...) give tagged synsets whose pointers supply antonyms (`!`),
+//! hypernyms (`@`, `@i`) and, for adjective clusters, similar words (`&`).
+//! The built-in data combines a small list of each.
+
+use crate::oulipo::errors::OulipoResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// Built-in synonym list in Moby thesaurus format
+const DEFAULT_MOBY: &str = include_str!("data/synonyms_en.txt");
+
+/// Built-in synsets in WordNet data-file format
+const DEFAULT_WORDNET: &str = include_str!("data/wordnet_en.txt");
+
+/// Part of speech of a thesaurus sense
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+}
+
+impl PartOfSpeech {
+    /// Parse a part-of-speech name or WordNet synset type (`n`, `v`, `a`, `s`, `r`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "noun" | "n" => Some(PartOfSpeech::Noun),
+            "verb" | "v" => Some(PartOfSpeech::Verb),
+            "adjective" | "adj" | "a" | "s" => Some(PartOfSpeech::Adjective),
+            "adverb" | "adv" | "r" => Some(PartOfSpeech::Adverb),
+            _ => None,
+        }
+    }
+}
+
+/// One meaning of a word with its related words
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sense {
+    /// `None` for untagged (Moby) entries
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+    /// More general terms ("canine" for "dog")
+    pub hypernyms: Vec<String>,
+    pub gloss: Option<String>,
+}
+
+/// Everything the thesaurus knows about a word, optionally for one part of speech
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThesaurusEntry {
+    pub word: String,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+    pub hypernyms: Vec<String>,
+    pub senses: Vec<Sense>,
+}
+
+/// Word to senses lookup table
+pub struct Thesaurus {
+    entries: HashMap<String, Vec<Sense>>,
+}
+
+impl Thesaurus {
+    /// Create a thesaurus with the built-in data
+    pub fn new() -> Self {
+        let mut thesaurus = Self::empty();
+        thesaurus.extend_from_moby(DEFAULT_MOBY);
+        thesaurus.extend_from_wordnet(DEFAULT_WORDNET);
+        thesaurus
+    }
+
+    /// Shared instance of the built-in thesaurus
+    pub fn shared() -> Arc<Self> {
+        static SHARED: OnceLock<Arc<Thesaurus>> = OnceLock::new();
+        SHARED.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    /// Create an empty thesaurus
+    pub fn empty() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Add Moby-format synonyms: `headword,synonym,synonym,...` per line, `#` comments
+    ///
+    /// Synonyms for a headword already in the thesaurus are appended to its
+    /// untagged sense, skipping duplicates.
+    pub fn extend_from_moby(&mut self, contents: &str) {
+        for line in contents.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let mut fields = line.split(',').map(normalize).filter(|field| !field.is_empty());
+            let Some(headword) = fields.next() else { continue };
+            let senses = self.entries.entry(headword.clone()).or_default();
+            let index = match senses.iter().position(|s| s.part_of_speech.is_none()) {
+                Some(index) => index,
+                None => {
+                    senses.push(Sense::default());
+                    senses.len() - 1
+                }
+            };
+            let sense = &mut senses[index];
+            for synonym in fields {
+                if synonym != headword && !sense.synonyms.contains(&synonym) {
+                    sense.synonyms.push(synonym);
+                }
+            }
+        }
+    }
+
+    /// Add synsets from WordNet data-file contents
+    ///
+    /// Each word of a synset gets a sense whose synonyms are the other words of
+    /// the synset (then those of similar adjectives). Lexical antonym pointers
+    /// apply to the word they name; semantic ones to the whole synset. Licence
+    /// lines (starting with spaces) and malformed lines are skipped.
+    pub fn extend_from_wordnet(&mut self, contents: &str) {
+        let synsets: Vec<Synset> = contents.lines().filter_map(Synset::parse).collect();
+        let index: HashMap<(PartOfSpeech, &str), &Synset> =
+            synsets.iter().map(|s| ((s.part_of_speech, s.offset.as_str()), s)).collect();
+        let target_words = |pointer: &Pointer| -> Vec<String> {
+            let Some(target) = index.get(&(pointer.part_of_speech, pointer.offset.as_str())) else {
+                return Vec::new();
+            };
+            match pointer.target {
+                0 => target.words.clone(),
+                n => target.words.get(n - 1).cloned().into_iter().collect(),
+            }
+        };
+
+        for synset in &synsets {
+            for (i, word) in synset.words.iter().enumerate() {
+                let applies = |pointer: &&Pointer| pointer.source == 0 || pointer.source == i + 1;
+                let mut sense = Sense {
+                    part_of_speech: Some(synset.part_of_speech),
+                    synonyms: synset.words.iter().filter(|w| *w != word).cloned().collect(),
+                    antonyms: Vec::new(),
+                    hypernyms: Vec::new(),
+                    gloss: synset.gloss.clone(),
+                };
+                for pointer in synset.pointers.iter().filter(applies) {
+                    let related = match pointer.symbol.as_str() {
+                        "!" => &mut sense.antonyms,
+                        "@" | "@i" => &mut sense.hypernyms,
+                        "&" => &mut sense.synonyms,
+                        _ => continue,
+                    };
+                    for target in target_words(pointer) {
+                        if target != *word && !related.contains(&target) {
+                            related.push(target);
+                        }
+                    }
+                }
+                self.entries.entry(word.clone()).or_default().push(sense);
+            }
+        }
+    }
+
+    /// Load a Moby-format thesaurus from disk
+    pub fn from_moby_file(path: impl AsRef<Path>) -> OulipoResult<Self> {
+        let mut thesaurus = Self::empty();
+        thesaurus.extend_from_moby(&std::fs::read_to_string(path)?);
+        Ok(thesaurus)
+    }
+
+    /// Load WordNet data files (`data.noun`, `data.verb`, ...) from disk
+    pub fn from_wordnet_files<P: AsRef<Path>>(paths: &[P]) -> OulipoResult<Self> {
+        let mut thesaurus = Self::empty();
+        for path in paths {
+            thesaurus.extend_from_wordnet(&std::fs::read_to_string(path)?);
+        }
+        Ok(thesaurus)
+    }
+
+    /// Number of headwords
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the thesaurus has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Every sense of a word, untagged ones included
+    pub fn senses(&self, word: &str) -> &[Sense] {
+        self.entries.get(&normalize(word)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Senses of one part of speech, or every sense when `pos` is `None`
+    fn matching(&self, word: &str, pos: Option<PartOfSpeech>) -> impl Iterator<Item = &Sense> {
+        self.senses(word).iter().filter(move |sense| pos.is_none() || sense.part_of_speech == pos)
+    }
+
+    fn collect<'a>(senses: impl Iterator<Item = &'a Vec<String>>) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        for word in senses.flatten() {
+            if !words.contains(word) {
+                words.push(word.clone());
+            }
+        }
+        words
+    }
+
+    /// Synonyms across matching senses, closest first
+    ///
+    /// With a part of speech, untagged (Moby) senses are left out.
+    pub fn synonyms(&self, word: &str, pos: Option<PartOfSpeech>) -> Vec<String> {
+        Self::collect(self.matching(word, pos).map(|s| &s.synonyms))
+    }
+
+    /// Antonyms across matching senses
+    pub fn antonyms(&self, word: &str, pos: Option<PartOfSpeech>) -> Vec<String> {
+        Self::collect(self.matching(word, pos).map(|s| &s.antonyms))
+    }
+
+    /// Hypernyms (more general terms) across matching senses
+    pub fn hypernyms(&self, word: &str, pos: Option<PartOfSpeech>) -> Vec<String> {
+        Self::collect(self.matching(word, pos).map(|s| &s.hypernyms))
+    }
+
+    /// Parts of speech the word has tagged senses for
+    pub fn parts_of_speech(&self, word: &str) -> Vec<PartOfSpeech> {
+        let mut parts = Vec::new();
+        for pos in self.senses(word).iter().filter_map(|s| s.part_of_speech) {
+            if !parts.contains(&pos) {
+                parts.push(pos);
+            }
+        }
+        parts
+    }
+
+    /// Everything known about a word, optionally for one part of speech
+    pub fn lookup(&self, word: &str, pos: Option<PartOfSpeech>) -> ThesaurusEntry {
+        ThesaurusEntry {
+            word: normalize(word),
+            part_of_speech: pos,
+            synonyms: self.synonyms(word, pos),
+            antonyms: self.antonyms(word, pos),
+            hypernyms: self.hypernyms(word, pos),
+            senses: self.matching(word, pos).cloned().collect(),
+        }
+    }
+}
+
+impl Default for Thesaurus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowercase a word or phrase; WordNet joins phrase words with underscores
+fn normalize(word: &str) -> String {
+    word.trim().replace('_', " ").to_lowercase()
+}
+
+/// A pointer from one synset (or one of its words) to another
+struct Pointer {
+    symbol: String,
+    offset: String,
+    part_of_speech: PartOfSpeech,
+    /// 1-based word numbers, 0 for the whole synset
+    source: usize,
+    target: usize,
+}
+
+/// One line of a WordNet data file
+struct Synset {
+    offset: String,
+    part_of_speech: PartOfSpeech,
+    words: Vec<String>,
+    pointers: Vec<Pointer>,
+    gloss: Option<String>,
+}
+
+impl Synset {
+    /// `offset lex_filenum ss_type w_cnt word lex_id ... p_cnt ptr... [frames] | gloss`
+    fn parse(line: &str) -> Option<Self> {
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let (fields, gloss) = match line.split_once('|') {
+            Some((fields, gloss)) => (fields, Some(gloss.trim().to_string()).filter(|g| !g.is_empty())),
+            None => (line, None),
+        };
+        let mut fields = fields.split_whitespace();
+        let offset = fields.next()?.to_string();
+        let _lex_filenum = fields.next()?;
+        let part_of_speech = PartOfSpeech::parse(fields.next()?)?;
+
+        let word_count = usize::from_str_radix(fields.next()?, 16).ok()?;
+        let mut words = Vec::with_capacity(word_count);
+        for _ in 0..word_count {
+            // Adjectives may carry a syntactic marker such as "(a)" or "(ip)"
+            let word = fields.next()?;
+            words.push(normalize(word.split('(').next().unwrap_or(word)));
+            let _lex_id = fields.next()?;
+        }
+
+        let pointer_count: usize = fields.next()?.parse().ok()?;
+        let mut pointers = Vec::with_capacity(pointer_count);
+        for _ in 0..pointer_count {
+            let symbol = fields.next()?.to_string();
+            let offset = fields.next()?.to_string();
+            let part_of_speech = PartOfSpeech::parse(fields.next()?)?;
+            let source_target = fields.next()?;
+            if source_target.len() != 4 {
+                return None;
+            }
+            pointers.push(Pointer {
+                symbol,
+                offset,
+                part_of_speech,
+                source: usize::from_str_radix(&source_target[..2], 16).ok()?,
+                target: usize::from_str_radix(&source_target[2..], 16).ok()?,
+            });
+        }
+
+        Some(Self { offset, part_of_speech, words, pointers, gloss })
+    }
+}
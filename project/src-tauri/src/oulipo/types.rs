@@ -1,6 +1,7 @@
 
 //! Common types and data structures for the Oulipo constraint system.
 
+use crate::oulipo::span::{SpanIndex, TextSpan};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
@@ -22,14 +23,79 @@ pub struct ConstraintResult {
 /// Represents a violation of a constraint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Violation {
-    /// Character position where the violation occurs
+    /// Byte offset where the violation occurs
     pub position: usize,
-    /// Length of the violating text span
+    /// Byte length of the violating text span
     pub length: usize,
+    /// The violating text in every offset unit, set by `ConstraintResult::located`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<TextSpan>,
     /// Description of what rule was violated
     pub issue: String,
     /// Optional suggestion for fixing the violation
     pub suggestion: Option<String>,
+    /// Edits that resolve the violation mechanically, most likely first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<Fix>,
+}
+
+/// A machine-applicable edit: replace `position..position + length` with `replacement`
+///
+/// Alternative fixes for the same violation overlap, so at most one of them
+/// can be applied; see `fixes::apply_fixes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fix {
+    /// Short description for a quick-fix menu, e.g. "Replace with 'stone'"
+    pub label: String,
+    /// Byte offset of the text to replace
+    pub position: usize,
+    /// Byte length of the text to replace; zero inserts
+    pub length: usize,
+    /// The replaced text in every offset unit, set by `ConstraintResult::located`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<TextSpan>,
+    /// Text to put in its place; empty deletes
+    pub replacement: String,
+    /// How likely the edit is to be what the writer wants, between 0 and 1
+    pub confidence: f64,
+}
+
+impl Fix {
+    /// An edit of bytes `position..position + length`
+    pub fn new(label: impl Into<String>, position: usize, length: usize, replacement: impl Into<String>, confidence: f64) -> Self {
+        Self {
+            label: label.into(),
+            position,
+            length,
+            span: None,
+            replacement: replacement.into(),
+            confidence: confidence.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Byte offset just past the replaced text, saturating for fixes from outside
+    pub fn end(&self) -> usize {
+        self.position.saturating_add(self.length)
+    }
+}
+
+impl Violation {
+    /// Move the violation and its fixes `offset` bytes later, e.g. from a line into its poem
+    pub fn shifted(mut self, offset: usize) -> Self {
+        self.position += offset;
+        for fix in &mut self.fixes {
+            fix.position += offset;
+        }
+        self
+    }
+
+    /// Set the span of the violation and its fixes from their byte positions in the indexed text
+    pub fn locate(&mut self, index: &SpanIndex) {
+        self.span = Some(index.span(self.position, self.position + self.length));
+        for fix in &mut self.fixes {
+            fix.span = Some(index.span(fix.position, fix.end()));
+        }
+    }
 }
 
 /// Configuration for text validation
@@ -58,10 +124,10 @@ pub trait Constraint {
     fn check(&self, text: &str) -> Result<ConstraintResult>;
     
     /// Get the name of this constraint
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
     
     /// Get a description of this constraint
-    fn description(&self) -> &'static str;
+    fn description(&self) -> &str;
 }
 
 /// Trait for text generators
@@ -89,6 +155,55 @@ pub trait Transformer {
 }
 
 impl ConstraintResult {
+    /// Fill in the span of every violation and fix from its byte position and length in `text`
+    ///
+    /// Spans already present are recomputed, so a result whose positions were
+    /// shifted into a larger text can be located again against it.
+    pub fn located(mut self, text: &str) -> Self {
+        if !self.violations.is_empty() {
+            let index = SpanIndex::new(text);
+            for violation in &mut self.violations {
+                violation.locate(&index);
+            }
+        }
+        self
+    }
+    
+    /// How closely the text adheres to the constraint, from 0 to 1
+    ///
+    /// Constraints with a natural measure record it with `with_score`, such as
+    /// the share of lines ending on the right sestina word or of vowels that
+    /// conform in a univocalic. Otherwise a pass scores 1 and a failure 0.
+    pub fn score(&self) -> f64 {
+        match self.metadata.get("score").and_then(|score| score.as_f64()) {
+            Some(score) => score.clamp(0.0, 1.0),
+            None if self.success => 1.0,
+            None => 0.0,
+        }
+    }
+
+    /// Record the adherence score in the metadata, rounded to three decimals
+    ///
+    /// A passing result always scores 1, whatever the measure says, and a NaN
+    /// scores 0. Null metadata becomes an object; metadata that is neither
+    /// null nor an object is left as it is, without a score.
+    pub fn with_score(mut self, score: f64) -> Self {
+        let score = if self.success {
+            1.0
+        } else if score.is_nan() {
+            0.0
+        } else {
+            score.clamp(0.0, 1.0)
+        };
+        if self.metadata.is_null() {
+            self.metadata = serde_json::json!({});
+        }
+        if let Some(metadata) = self.metadata.as_object_mut() {
+            metadata.insert("score".to_string(), serde_json::json!((score * 1000.0).round() / 1000.0));
+        }
+        self
+    }
+
     /// Create a successful result
     pub fn success(result: String, suggestions: Vec<String>, metadata: serde_json::Value) -> Self {
         Self {
@@ -31,6 +31,15 @@ pub fn word_count(text: &str) -> usize {
     text.split_whitespace().count()
 }
 
+/// The share of `whole` that `part` makes up; an empty whole counts as fully met
+pub fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        1.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
 /// Get character frequency map
 pub fn char_frequency(text: &str) -> std::collections::HashMap<char, usize> {
     let mut freq = std::collections::HashMap::new();
@@ -42,7 +51,7 @@ pub fn char_frequency(text: &str) -> std::collections::HashMap<char, usize> {
 
 /// Split text into sentences
 pub fn split_sentences(text: &str) -> Vec<&str> {
-    text.split(|c| c == '.' || c == '!' || c == '?')
+    text.split(['.', '!', '?'])
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
         .collect()
@@ -57,3 +66,252 @@ pub fn is_palindrome(text: &str) -> bool {
     
     normalized == normalized.chars().rev().collect::<String>()
 }
+
+/// Irregular English plurals as (singular, plural) pairs
+const IRREGULAR_PLURALS: &[(&str, &str)] = &[
+    ("child", "children"),
+    ("foot", "feet"),
+    ("goose", "geese"),
+    ("man", "men"),
+    ("mouse", "mice"),
+    ("ox", "oxen"),
+    ("person", "people"),
+    ("tooth", "teeth"),
+    ("woman", "women"),
+];
+
+/// Apply the capitalisation of `template` to `word`
+///
+/// All-caps templates (longer than one letter) give an all-caps word, a leading
+/// capital gives a capitalised word, and anything else gives lowercase.
+pub fn match_case(template: &str, word: &str) -> String {
+    let letters: Vec<char> = template.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return word.to_uppercase();
+    }
+    
+    let lower = word.to_lowercase();
+    if letters.first().is_some_and(|c| c.is_uppercase()) {
+        let mut chars = lower.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => lower,
+        }
+    } else {
+        lower
+    }
+}
+
+/// Possible singular forms of an English word, most likely first
+///
+/// The word itself is always included last so callers can fall back to it.
+pub fn singular_candidates(word: &str) -> Vec<String> {
+    let lower = word.to_lowercase();
+    let mut candidates = Vec::new();
+    
+    if let Some((singular, _)) = IRREGULAR_PLURALS.iter().find(|(_, plural)| *plural == lower) {
+        candidates.push(singular.to_string());
+    }
+    if let Some(stem) = lower.strip_suffix("ies") {
+        candidates.push(format!("{}y", stem));
+    }
+    if let Some(stem) = lower.strip_suffix("ves") {
+        candidates.push(format!("{}f", stem));
+        candidates.push(format!("{}fe", stem));
+    }
+    if let Some(stem) = lower.strip_suffix("es") {
+        candidates.push(stem.to_string());
+    }
+    if let Some(stem) = lower.strip_suffix('s') {
+        if !stem.ends_with('s') {
+            candidates.push(stem.to_string());
+        }
+    }
+    
+    candidates.push(lower);
+    candidates
+}
+
+/// Regular (or known irregular) English plural of a noun
+pub fn pluralize(noun: &str) -> String {
+    let lower = noun.to_lowercase();
+    if let Some((_, plural)) = IRREGULAR_PLURALS.iter().find(|(singular, _)| *singular == lower) {
+        return plural.to_string();
+    }
+    
+    let ends_with_consonant_y = lower.ends_with('y')
+        && !lower[..lower.len() - 1].ends_with(|c: char| is_vowel(c));
+    
+    if ends_with_consonant_y {
+        format!("{}ies", &lower[..lower.len() - 1])
+    } else if let Some(stem) = lower.strip_suffix("fe") {
+        format!("{}ves", stem)
+    } else if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| lower.ends_with(suffix)) {
+        format!("{}es", lower)
+    } else {
+        format!("{}s", lower)
+    }
+}
+
+/// Check whether `plural` is an accepted plural of `singular` ("cities" for "city", "leaves" for "leaf")
+pub fn is_plural_of(plural: &str, singular: &str) -> bool {
+    let plural = plural.to_lowercase();
+    let singular = singular.to_lowercase();
+    if pluralize(&singular) == plural {
+        return true;
+    }
+    
+    let stem = singular.strip_suffix("fe").or_else(|| singular.strip_suffix('f'));
+    stem.is_some_and(|stem| plural == format!("{}ves", stem))
+}
+
+/// Split text into words with their byte offsets
+///
+/// A word is a run of letters, optionally joined by apostrophes ("don't",
+/// "moon's"); digits, punctuation and whitespace separate words.
+pub fn word_spans(text: &str) -> Vec<(usize, &str)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+    
+    while let Some((idx, ch)) = chars.next() {
+        let joins_word = (ch == '\'' || ch == '’')
+            && start.is_some()
+            && chars.peek().is_some_and(|&(_, next)| next.is_alphabetic());
+        
+        if ch.is_alphabetic() || joins_word {
+            start.get_or_insert(idx);
+        } else if let Some(s) = start.take() {
+            spans.push((s, &text[s..idx]));
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, &text[s..]));
+    }
+    
+    spans
+}
+
+/// Non-empty lines with their byte offsets
+///
+/// Blank lines (stanza breaks) are skipped and line endings are not included
+/// in the returned slices.
+pub fn line_spans(text: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    let mut lines = Vec::new();
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if !content.trim().is_empty() {
+            lines.push((offset, content));
+        }
+        offset += line.len();
+    }
+    lines
+}
+
+/// Blank-line separated stanzas, each a list of non-empty lines with byte offsets
+pub fn stanza_spans(text: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut stanzas: Vec<Vec<(usize, &str)>> = vec![Vec::new()];
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if stanzas.last().is_some_and(|s| !s.is_empty()) {
+                stanzas.push(Vec::new());
+            }
+        } else if let Some(stanza) = stanzas.last_mut() {
+            stanza.push((offset, content));
+        }
+        offset += line.len();
+    }
+    stanzas.retain(|s| !s.is_empty());
+    stanzas
+}
+
+/// Sentences with their byte offsets
+///
+/// A sentence runs up to and including its closing `.`, `!` or `?` (and any
+/// closing quotes or brackets straight after); surrounding whitespace is not
+/// included. Trailing text without a closing mark is the last sentence.
+pub fn sentence_spans(text: &str) -> Vec<(usize, &str)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        if !matches!(ch, '.' | '!' | '?') {
+            continue;
+        }
+        let mut end = idx + ch.len_utf8();
+        while let Some(&(next_idx, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '"' | '\'' | '”' | '’' | ')' | ']') {
+                end = next_idx + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        push_trimmed(&mut spans, text, start, end);
+        start = end;
+    }
+    push_trimmed(&mut spans, text, start, text.len());
+    spans
+}
+
+/// Paragraphs with their byte offsets
+///
+/// Paragraphs are separated by blank lines. Text without any blank line is
+/// read as prose typed one paragraph per line.
+pub fn paragraph_spans(text: &str) -> Vec<(usize, &str)> {
+    let blocks = stanza_spans(text);
+    if blocks.len() < 2 && blocks.first().is_some_and(|lines| lines.len() > 1) {
+        return line_spans(text);
+    }
+    blocks
+        .into_iter()
+        .filter_map(|lines| {
+            let &(start, _) = lines.first()?;
+            let &(last, content) = lines.last()?;
+            Some((start, &text[start..last + content.len()]))
+        })
+        .collect()
+}
+
+/// Push `text[start..end]` without surrounding whitespace, unless nothing is left
+fn push_trimmed<'a>(spans: &mut Vec<(usize, &'a str)>, text: &'a str, start: usize, end: usize) {
+    let slice = &text[start..end];
+    let trimmed = slice.trim_start();
+    let offset = start + slice.len() - trimmed.len();
+    let trimmed = trimmed.trim_end();
+    if !trimmed.is_empty() {
+        spans.push((offset, trimmed));
+    }
+}
+
+/// Lowercased words of a line joined by single spaces, for comparing repeated lines
+///
+/// Punctuation and spacing differences are ignored, so "Do not go gentle," and
+/// "do not go gentle" compare equal.
+pub fn normalize_line(line: &str) -> String {
+    word_spans(line)
+        .into_iter()
+        .map(|(_, word)| word.to_lowercase().replace('’', "'"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Common English function words: articles, pronouns, prepositions, conjunctions and auxiliaries
+pub const FUNCTION_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "nor", "so", "yet", "if", "as", "than", "that", "this",
+    "these", "those", "of", "in", "on", "at", "by", "to", "for", "from", "with", "into", "onto",
+    "upon", "over", "under", "up", "down", "off", "out", "about", "i", "me", "my", "we", "us",
+    "our", "you", "your", "he", "him", "his", "she", "her", "it", "its", "they", "them", "their",
+    "am", "is", "are", "was", "were", "be", "been", "being", "do", "does", "did", "have", "has",
+    "had", "will", "would", "shall", "should", "can", "could", "may", "might", "must", "not", "no",
+    "who", "whom", "whose", "which", "what", "when", "where", "while",
+];
+
+/// Whether a word is a function word (case-insensitive)
+pub fn is_function_word(word: &str) -> bool {
+    let lower = word.to_lowercase().replace('’', "'");
+    FUNCTION_WORDS.contains(&lower.as_str())
+}
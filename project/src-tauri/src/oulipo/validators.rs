@@ -1,27 +1,134 @@
 // Text validation utilities for Oulipo constraints
-use crate::oulipo::{ConstraintResult, Violation};
+use crate::oulipo::{
+    types::{ConstraintResult, Violation, Fix, Constraint},
+    errors::{OulipoError, OulipoResult},
+    utils::share,
+};
 use anyhow::Result;
 
+/// Text length validator usable as a constraint
+pub struct TextLengthConstraint {
+    min_length: usize,
+    max_length: Option<usize>,
+}
+
+impl TextLengthConstraint {
+    /// Create a new length constraint with an optional upper bound
+    pub fn new(min_length: usize, max_length: Option<usize>) -> OulipoResult<Self> {
+        if max_length.is_some_and(|max| max < min_length) {
+            return Err(OulipoError::InvalidConfig(
+                "'max_length' must not be smaller than 'min_length'".to_string()
+            ));
+        }
+
+        Ok(Self { min_length, max_length })
+    }
+}
+
+impl Constraint for TextLengthConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        validate_text_length(text, self.min_length, self.max_length)
+    }
+
+    fn name(&self) -> &'static str {
+        "Text Length"
+    }
+
+    fn description(&self) -> &'static str {
+        "Text length must fall within the given bounds"
+    }
+}
+
+/// Word count validator usable as a constraint
+pub struct WordCountConstraint {
+    min_words: usize,
+    max_words: Option<usize>,
+}
+
+impl WordCountConstraint {
+    /// Create a new word count constraint with an optional upper bound
+    pub fn new(min_words: usize, max_words: Option<usize>) -> OulipoResult<Self> {
+        if max_words.is_some_and(|max| max < min_words) {
+            return Err(OulipoError::InvalidConfig(
+                "'max_words' must not be smaller than 'min_words'".to_string()
+            ));
+        }
+
+        Ok(Self { min_words, max_words })
+    }
+}
+
+impl Constraint for WordCountConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        validate_word_count(text, self.min_words, self.max_words)
+    }
+
+    fn name(&self) -> &'static str {
+        "Word Count"
+    }
+
+    fn description(&self) -> &'static str {
+        "Number of words must fall within the given bounds"
+    }
+}
+
+/// Character frequency validator usable as a constraint
+pub struct CharacterFrequencyConstraint {
+    target_char: char,
+    max_frequency: usize,
+}
+
+impl CharacterFrequencyConstraint {
+    /// Create a new constraint capping how often a character may appear
+    pub fn new(target_char: char, max_frequency: usize) -> Self {
+        Self { target_char, max_frequency }
+    }
+}
+
+impl Constraint for CharacterFrequencyConstraint {
+    fn check(&self, text: &str) -> Result<ConstraintResult> {
+        check_character_frequency(text, self.target_char, self.max_frequency)
+    }
+
+    fn name(&self) -> &'static str {
+        "Character Frequency"
+    }
+
+    fn description(&self) -> &'static str {
+        "A character may appear at most a given number of times"
+    }
+}
+
+/// Check that the text has between `min_length` and `max_length` characters
+///
+/// Lengths count characters, not bytes, so accented letters and emoji count once.
+/// Text that is too long comes with fixes trimming it to the limit. The score
+/// is how close the length comes to the nearer bound.
 pub fn validate_text_length(text: &str, min_length: usize, max_length: Option<usize>) -> Result<ConstraintResult> {
-    let text_length = text.len();
+    let text_length = text.chars().count();
     let mut violations = Vec::new();
     
     if text_length < min_length {
         violations.push(Violation {
             position: 0,
-            length: text_length,
+            length: text.len(),
+            span: None,
             issue: format!("Text too short: {} characters (minimum {})", text_length, min_length),
             suggestion: Some(format!("Add {} more characters", min_length - text_length)),
+            fixes: Vec::new(),
         });
     }
     
     if let Some(max_len) = max_length {
         if text_length > max_len {
+            let overflow = text.char_indices().nth(max_len).map_or(text.len(), |(idx, _)| idx);
             violations.push(Violation {
-                position: max_len,
-                length: text_length - max_len,
+                position: overflow,
+                length: text.len() - overflow,
+                span: None,
                 issue: format!("Text too long: {} characters (maximum {})", text_length, max_len),
                 suggestion: Some(format!("Remove {} characters", text_length - max_len)),
+                fixes: trim_fixes(text, overflow, max_len),
             });
         }
     }
@@ -43,7 +150,39 @@ pub fn validate_text_length(text: &str, min_length: usize, max_length: Option<us
             "min_length": min_length,
             "max_length": max_length
         }),
-    })
+    }.with_score(bounded_score(text_length, min_length, max_length)).located(text))
+}
+
+/// How close `count` comes to `min..=max`: the share of the minimum reached, or of the excess avoided
+fn bounded_score(count: usize, min: usize, max: Option<usize>) -> f64 {
+    match max {
+        _ if count < min => share(count, min),
+        Some(max) if count > max => share(max, count),
+        _ => 1.0,
+    }
+}
+
+/// Fixes cutting the text down to the `max_len` characters before byte `overflow`
+///
+/// Cutting after the last whole word is offered first; the exact cut is the
+/// fallback when the limit falls mid-word.
+fn trim_fixes(text: &str, overflow: usize, max_len: usize) -> Vec<Fix> {
+    let kept = &text[..overflow];
+    let at_word_end = text[overflow..].starts_with(char::is_whitespace);
+    let whole_words = if at_word_end {
+        kept.trim_end().len()
+    } else {
+        kept.rfind(char::is_whitespace).map_or(0, |space| kept[..space].trim_end().len())
+    };
+
+    let mut fixes = Vec::new();
+    if whole_words > 0 {
+        fixes.push(Fix::new("Trim to the last whole word", whole_words, text.len() - whole_words, "", 0.7));
+    }
+    if whole_words != overflow || fixes.is_empty() {
+        fixes.push(Fix::new(format!("Trim to {} characters", max_len), overflow, text.len() - overflow, "", 0.5));
+    }
+    fixes
 }
 
 pub fn validate_word_count(text: &str, min_words: usize, max_words: Option<usize>) -> Result<ConstraintResult> {
@@ -54,8 +193,10 @@ pub fn validate_word_count(text: &str, min_words: usize, max_words: Option<usize
         violations.push(Violation {
             position: 0,
             length: text.len(),
+            span: None,
             issue: format!("Too few words: {} (minimum {})", word_count, min_words),
             suggestion: Some(format!("Add {} more words", min_words - word_count)),
+            fixes: Vec::new(),
         });
     }
     
@@ -64,8 +205,10 @@ pub fn validate_word_count(text: &str, min_words: usize, max_words: Option<usize
             violations.push(Violation {
                 position: 0,
                 length: text.len(),
+                span: None,
                 issue: format!("Too many words: {} (maximum {})", word_count, max_words),
                 suggestion: Some(format!("Remove {} words", word_count - max_words)),
+                fixes: Vec::new(),
             });
         }
     }
@@ -87,7 +230,7 @@ pub fn validate_word_count(text: &str, min_words: usize, max_words: Option<usize
             "min_words": min_words,
             "max_words": max_words
         }),
-    })
+    }.with_score(bounded_score(word_count, min_words, max_words)).located(text))
 }
 
 pub fn check_character_frequency(text: &str, target_char: char, max_frequency: usize) -> Result<ConstraintResult> {
@@ -99,8 +242,10 @@ pub fn check_character_frequency(text: &str, target_char: char, max_frequency: u
         vec![Violation {
             position: 0,
             length: text.len(),
+            span: None,
             issue: format!("Character '{}' appears {} times (maximum {})", target_char, char_count, max_frequency),
             suggestion: Some(format!("Remove {} occurrences of '{}'", char_count - max_frequency, target_char)),
+            fixes: Vec::new(),
         }]
     } else {
         vec![]
@@ -123,5 +268,5 @@ pub fn check_character_frequency(text: &str, target_char: char, max_frequency: u
             "frequency": char_count,
             "max_frequency": max_frequency
         }),
-    })
+    }.with_score(bounded_score(char_count, 0, Some(max_frequency))).located(text))
 }